@@ -101,6 +101,8 @@ function App() {
             status: p.status,
             thinking_text: null,
             current_task: null,
+            activity: null,
+            activity_summary: null,
             appearance: p.appearance,
             last_active_ts: p.ts,
           };
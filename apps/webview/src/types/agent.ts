@@ -29,6 +29,17 @@ export interface AppearanceProfile {
   skin_lightness: number;
 }
 
+// 도구 분류 (서버 측 config [activity.categories])
+export type ToolCategory =
+  | 'reading'
+  | 'editing'
+  | 'searching'
+  | 'running'
+  | 'web'
+  | 'delegating'
+  | 'planning'
+  | 'other';
+
 export interface MascotAgent {
   agent_id: string;
   display_name: string;
@@ -38,6 +49,8 @@ export interface MascotAgent {
   status: AgentStatus;
   thinking_text: string | null;
   current_task: string | null;
+  activity: ToolCategory | null;
+  activity_summary: string | null;
   appearance: AppearanceProfile;
   last_active_ts: string;
 }
@@ -1,4 +1,4 @@
-import type { AgentStatus, AppearanceProfile, MascotAgent } from './agent';
+import type { AgentStatus, AppearanceProfile, MascotAgent, ToolCategory } from './agent';
 
 // Rust -> WebView 이벤트 페이로드
 export interface AgentAppearedPayload {
@@ -18,9 +18,15 @@ export interface AgentUpdatePayload {
   prev_status: AgentStatus;
   thinking_text: string | null;
   current_task: string | null;
+  activity: ToolCategory | null;
+  activity_summary: string | null; // 예: "Editing lib.rs"
+  consecutive_failures: number;
+  unresponsive: boolean; // 세션 이벤트가 끊겨 응답 없음
   workspace_id: string;
   peer_agent_id: string | null;
   chat_message: string | null;
+  queued_for: string | null; // 대화 대기 중인 대상 agent_id
+  queue_position: number | null; // 대기 순번 (1부터)
   ts: string;
 }
 
@@ -82,6 +88,7 @@ export interface AgentResume {
   recent_events: ResumeEvent[];
   total_tasks_completed: number;
   total_tools_used: number;
+  consecutive_failures: number;
   first_seen_ts: string;
 }
 
@@ -6,15 +6,19 @@
  * 앱이 실행 중이 아니면 자동 실행을 시도한다.
  */
 
-import { readFileSync, existsSync } from 'node:fs';
-import { execFile } from 'node:child_process';
-import { basename } from 'node:path';
+import { readFileSync, writeFileSync, existsSync } from 'node:fs';
+import { execFile, execFileSync } from 'node:child_process';
+import { createHash } from 'node:crypto';
+import { tmpdir } from 'node:os';
+import { basename, join } from 'node:path';
 
 const COLLECTOR_URL = process.env.MASCOT_COLLECTOR_URL ?? 'http://127.0.0.1:4820';
 const HEALTH_TIMEOUT_MS = 500;
 const INGEST_TIMEOUT_MS = 5000;
 const LAUNCH_WAIT_MAX_MS = 5000;
 const LAUNCH_POLL_INTERVAL_MS = 500;
+const GIT_TIMEOUT_MS = 300;
+const GIT_REMOTE_CACHE_TTL_MS = 10 * 60 * 1000;
 
 async function main() {
   // 1. stdin에서 payload 읽기
@@ -32,6 +36,7 @@ async function main() {
     ...raw,
     _meta: {
       workspace_id: raw._meta?.workspace_id ?? deriveWorkspaceId(),
+      git_remote: raw._meta?.git_remote ?? deriveGitRemote(),
       terminal_session_id:
         raw._meta?.terminal_session_id ?? process.env.TERM_SESSION_ID ?? 'unknown',
       collected_at: new Date().toISOString(),
@@ -66,6 +71,63 @@ function deriveWorkspaceId() {
   return basename(process.cwd());
 }
 
+// 같은 저장소를 다른 경로(WSL/Windows, 여러 clone)에서 열어도 같은 워크스페이스로 묶이도록
+// origin URL에서 저장소 경로(`org/repo`)만 보낸다. URL에 담긴 토큰/비밀번호는 보내지 않는다.
+// hook마다 git을 실행하지 않도록 cwd별 결과를 임시 디렉토리에 캐시한다. git이 없거나 저장소가 아니면 생략.
+function deriveGitRemote() {
+  const cwd = process.env.CLAUDE_PROJECT_DIR ?? process.cwd();
+  const cacheKey = createHash('sha1').update(cwd).digest('hex');
+  const cacheFile = join(tmpdir(), `mascot-git-remote-${cacheKey}.json`);
+  try {
+    const cached = JSON.parse(readFileSync(cacheFile, 'utf-8'));
+    if (Date.now() - cached.at < GIT_REMOTE_CACHE_TTL_MS) {
+      return cached.path ?? undefined;
+    }
+  } catch {
+    // 캐시 없음
+  }
+
+  let path = null;
+  try {
+    const url = execFileSync('git', ['config', '--get', 'remote.origin.url'], {
+      cwd,
+      encoding: 'utf-8',
+      stdio: ['ignore', 'pipe', 'ignore'],
+      timeout: GIT_TIMEOUT_MS,
+    }).trim();
+    path = gitRemotePath(url);
+  } catch {
+    // git 없음 또는 저장소 아님
+  }
+
+  try {
+    writeFileSync(cacheFile, JSON.stringify({ at: Date.now(), path }));
+  } catch {
+    // 캐시 실패는 무시
+  }
+  return path ?? undefined;
+}
+
+// git remote URL → `org/repo` (services/workspace.rs의 git_remote_path와 같은 규칙).
+// `git@host:org/repo.git`, `https://token@host/org/repo`, `ssh://git@host:22/org/repo.git` 모두 같은 값.
+function gitRemotePath(url) {
+  let rest;
+  const scheme = url.indexOf('://');
+  if (scheme >= 0) {
+    const afterScheme = url.slice(scheme + 3);
+    const slash = afterScheme.indexOf('/');
+    if (slash < 0) return null;
+    rest = afterScheme.slice(slash + 1);
+  } else {
+    // scp 형식: [user@]host:path
+    const colon = url.indexOf(':');
+    if (colon < 0) return null;
+    rest = url.slice(colon + 1);
+  }
+  const path = rest.replace(/^\/+|\/+$/g, '').replace(/\.git$/, '').replace(/\/+$/, '');
+  return path.includes('/') ? path : null;
+}
+
 async function ensureAppRunning() {
   try {
     const res = await fetch(`${COLLECTOR_URL}/health`, {
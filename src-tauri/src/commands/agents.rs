@@ -1,11 +1,15 @@
 use crate::error::AppError;
 use crate::models::agent::*;
 use crate::models::event::*;
-use crate::services::state_machine;
+use crate::models::transition::{TimeInState, TimeInStateQuery, TransitionRecord};
+use crate::services::reconcile::{self, Repair};
+use crate::services::state_diagram::{self, DiagramFormat};
+use crate::services::{appearance, chat_queue, fingerprint, normalizer, state_machine};
 use crate::state::AppState;
-use crate::storage::agents_repo::AgentsRepo;
+use crate::storage::agent_store::AgentTx;
 use crate::storage::events_repo::EventsRepo;
-use crate::storage::state_repo::StateRepo;
+use crate::storage::thinking_repo::ThinkingRepo;
+use crate::storage::transitions_repo::TransitionsRepo;
 use serde::Serialize;
 use tauri::Emitter;
 
@@ -39,22 +43,7 @@ pub struct DisplayConfigResponse {
 pub async fn get_all_agents(
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<MascotAgent>, AppError> {
-    let agents_repo = AgentsRepo::new(state.db.clone());
-    let state_repo = StateRepo::new(state.db.clone());
-
-    let mut agents = agents_repo.get_all()?;
-    let states = state_repo.get_all()?;
-
-    // agents에 현재 상태 덮어쓰기
-    for agent in &mut agents {
-        if let Some(s) = states.iter().find(|s| s.agent_id == agent.agent_id) {
-            agent.status = s.status.clone();
-            agent.thinking_text = s.thinking_text.clone();
-            agent.current_task = s.current_task.clone();
-        }
-    }
-
-    Ok(agents)
+    state.agents.all_agents()
 }
 
 /// 에이전트 이력서 정보 반환 (ipc-protocol.md §3.1)
@@ -63,22 +52,16 @@ pub async fn get_agent_resume(
     state: tauri::State<'_, AppState>,
     agent_id: String,
 ) -> Result<serde_json::Value, AppError> {
-    let agents_repo = AgentsRepo::new(state.db.clone());
     let events_repo = EventsRepo::new(state.db.clone());
-    let state_repo = StateRepo::new(state.db.clone());
-
-    let agent = match agents_repo.get_by_id(&agent_id)? {
-        Some(mut a) => {
-            // 현재 상태 반영
-            if let Some(s) = state_repo.get(&agent_id)? {
-                a.status = s.status;
-                a.thinking_text = s.thinking_text;
-                a.current_task = s.current_task;
-            }
-            a
-        }
-        None => return Ok(serde_json::json!(null)),
+
+    // 현재 상태가 반영된 에이전트
+    let Some(agent) = state.agents.get_agent(&agent_id)? else {
+        return Ok(serde_json::json!(null));
     };
+    let consecutive_failures = state
+        .agents
+        .get_state(&agent_id)?
+        .map_or(0, |s| s.consecutive_failures);
 
     let recent_events = events_repo.get_recent_by_agent(
         &agent_id,
@@ -86,18 +69,61 @@ pub async fn get_agent_resume(
     )?;
     let total_tasks_completed = events_repo.count_completed_tasks(&agent_id)?;
     let total_tools_used = events_repo.count_tools_used(&agent_id)?;
+    let recent_thinking = ThinkingRepo::new(state.db.clone())
+        .get_recent(&agent_id, state.config.thinking.history_limit)?;
 
     let resume = serde_json::json!({
         "agent": agent,
         "recent_events": recent_events,
+        "recent_thinking": recent_thinking,
         "total_tasks_completed": total_tasks_completed,
         "total_tools_used": total_tools_used,
+        "consecutive_failures": consecutive_failures,
         "first_seen_ts": agent.last_active_ts,
     });
 
     Ok(resume)
 }
 
+/// 에이전트/상태별 머문 시간 합계 (ipc-protocol.md §3.1).
+/// from/to는 RFC3339, to 생략 시 현재 시각. agent_id/workspace_id로 좁힐 수 있다.
+#[tauri::command]
+pub async fn get_time_in_state(
+    state: tauri::State<'_, AppState>,
+    agent_id: Option<String>,
+    workspace_id: Option<String>,
+    from: String,
+    to: Option<String>,
+) -> Result<Vec<TimeInState>, AppError> {
+    let query = TimeInStateQuery { agent_id, workspace_id, from, to };
+    time_in_state(&state, &query)
+}
+
+/// get_time_in_state와 GET /api/time-in-state 공통
+pub(crate) fn time_in_state(
+    state: &AppState,
+    query: &TimeInStateQuery,
+) -> Result<Vec<TimeInState>, AppError> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let (from_ms, to_ms) = query.range_ms(now_ms)?;
+    TransitionsRepo::new(state.db.clone()).time_in_state(
+        query.agent_id.as_deref(),
+        query.workspace_id.as_deref(),
+        from_ms,
+        to_ms,
+        now_ms,
+    )
+}
+
+/// 현재 전이 테이블 + 타이머 전이를 Mermaid/DOT 텍스트로 내보낸다 (ipc-protocol.md §3.1)
+#[tauri::command]
+pub async fn export_state_diagram(
+    state: tauri::State<'_, AppState>,
+    format: DiagramFormat,
+) -> Result<String, AppError> {
+    Ok(state_diagram::render(&state.config.state_machine, format))
+}
+
 /// WebView에서 Spine 스켈레톤 로드 후 슬롯 개수 전달 (ipc-protocol.md §3.1)
 #[tauri::command]
 pub async fn set_slot_counts(
@@ -113,6 +139,53 @@ pub async fn set_slot_counts(
     Ok(())
 }
 
+/// WebView 콜백을 기다리다 멈춘 상태를 보정한다 (state-machine.md §5.2, ipc-protocol.md §3.1).
+/// 시작할 때 자동으로 한 번 실행되며, 멈춘 캐릭터가 보이면 수동으로도 실행할 수 있다.
+#[tauri::command]
+pub async fn repair_states(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<Repair>, AppError> {
+    reconcile_states(&state, &app_handle, "manual")
+}
+
+/// 모든 에이전트의 일시 상태/남은 대화 상대를 보정한다. 보정마다 state_repaired 이벤트를 기록한다.
+/// reason은 payload에 남긴다 ("startup", "manual").
+pub(crate) fn reconcile_states(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    reason: &str,
+) -> Result<Vec<Repair>, AppError> {
+    let events_repo = EventsRepo::new(state.db.clone());
+    let mut repairs = Vec::new();
+
+    for agent_state in state.agents.all_states()? {
+        let last_event = events_repo.last_session_event_type(&agent_state.agent_id)?;
+        let Some(repair) = reconcile::plan(&agent_state, last_event.as_ref()) else {
+            continue;
+        };
+        tracing::info!(
+            "reconcile ({reason}): agent={} {:?} → {:?}, cleared_peer={:?}",
+            repair.agent_id, repair.from_status, repair.to_status, repair.cleared_peer,
+        );
+
+        let payload = serde_json::json!({
+            "reason": reason,
+            "from_status": repair.from_status,
+            "to_status": repair.to_status,
+            "cleared_peer": repair.cleared_peer,
+            "last_event_type": last_event,
+            "last_event_ts": agent_state.last_event_ts,
+        });
+        let mut event = synthetic_event(&agent_state, "reconcile", EventType::StateRepaired, payload);
+        event.severity = Severity::Warn;
+        record_and_apply(state, app_handle, agent_state, &event)?;
+        repairs.push(repair);
+    }
+
+    Ok(repairs)
+}
+
 /// WebView가 Spine 애니메이션 완료를 알림 (synthetic 이벤트)
 #[tauri::command]
 pub async fn notify_animation_done(
@@ -131,7 +204,8 @@ pub async fn notify_animation_done(
         }
     };
 
-    process_synthetic_event(&state, &app_handle, &agent_id, event_type).await
+    let payload = serde_json::json!({ "animation": animation });
+    process_synthetic_event(&state, &app_handle, &agent_id, event_type, payload).await
 }
 
 /// WebView가 캐릭터 이동 완료를 알림 (synthetic 이벤트)
@@ -151,7 +225,8 @@ pub async fn notify_movement_done(
         }
     };
 
-    process_synthetic_event(&state, &app_handle, &agent_id, event_type).await
+    let payload = serde_json::json!({ "movement_type": movement_type });
+    process_synthetic_event(&state, &app_handle, &agent_id, event_type, payload).await
 }
 
 /// WebView가 대화 말풍선 표시 완료를 알림
@@ -161,7 +236,8 @@ pub async fn notify_chat_done(
     app_handle: tauri::AppHandle,
     agent_id: String,
 ) -> Result<(), AppError> {
-    process_synthetic_event(&state, &app_handle, &agent_id, EventType::MessageDone).await
+    let payload = serde_json::json!({ "animation": "chat_bubble" });
+    process_synthetic_event(&state, &app_handle, &agent_id, EventType::MessageDone, payload).await
 }
 
 /// 드래그 완료 시 캐릭터의 새 home_x를 DB에 저장한다.
@@ -171,9 +247,7 @@ pub async fn notify_drag_drop(
     agent_id: String,
     new_home_x: f64,
 ) -> Result<(), AppError> {
-    let state_repo = StateRepo::new(state.db.clone());
-    state_repo.update_home_x(&agent_id, new_home_x)?;
-    Ok(())
+    state.agents.set_home_x(&agent_id, new_home_x)
 }
 
 /// 화면 배치 설정 반환 (ipc-protocol.md §3.1)
@@ -214,73 +288,336 @@ async fn process_synthetic_event(
     app_handle: &tauri::AppHandle,
     agent_id: &str,
     event_type: EventType,
+    payload: serde_json::Value,
 ) -> Result<(), AppError> {
-    let state_repo = StateRepo::new(state.db.clone());
+    apply_synthetic_event(state, app_handle, agent_id, "webview", event_type, None, payload)?;
+    Ok(())
+}
 
-    let mut agent_state = match state_repo.get(agent_id)? {
-        Some(s) => s,
-        None => {
-            tracing::warn!("synthetic event for unknown agent: {agent_id}");
-            return Ok(());
-        }
+/// synthetic 이벤트 하나를 만들어 기록 + 전이 + emit 한다. 처리 후 에이전트 상태를 반환 (미등록이면 None).
+/// 대화 대기열에서 차례가 온 방문자의 message_sent도 이 경로로 다시 처리한다.
+fn apply_synthetic_event(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+    origin: &str,
+    event_type: EventType,
+    target_agent_id: Option<String>,
+    payload: serde_json::Value,
+) -> Result<Option<AgentStatus>, AppError> {
+    let Some(agent_state) = state.agents.get_state(agent_id)? else {
+        tracing::warn!("synthetic event for unknown agent: {agent_id}");
+        return Ok(None);
     };
 
-    let ts = chrono::Utc::now().to_rfc3339();
-    let synthetic_event = NormalizedEvent {
-        id: format!("syn_{}", chrono::Utc::now().format("%Y%m%d%H%M%S%f")),
+    let mut event = synthetic_event(&agent_state, origin, event_type, payload);
+    event.target_agent_id = target_agent_id;
+    record_and_apply(state, app_handle, agent_state, &event).map(Some)
+}
+
+/// 에이전트 상태에서 synthetic 이벤트를 만든다. origin은 terminal_session_id로 남긴다
+/// ("webview", "heartbeat", "chat_queue", "reconcile"). payload에는 이벤트를 일으킨 애니메이션/규칙을 담는다.
+pub(crate) fn synthetic_event(
+    agent_state: &AgentState,
+    origin: &str,
+    event_type: EventType,
+    payload: serde_json::Value,
+) -> NormalizedEvent {
+    // 핑거프린트는 raw로 계산하므로 같은 세션의 다른 에이전트와 겹치지 않도록 agent_id를 넣는다
+    let raw = serde_json::json!({
+        "origin": origin,
+        "agent_id": agent_state.agent_id,
+        "payload": payload,
+    });
+    NormalizedEvent {
+        id: normalizer::generate_event_id(),
         version: "1.1".to_string(),
-        ts: ts.clone(),
+        ts: chrono::Utc::now().to_rfc3339(),
         event_type,
         source: EventSource::Synthetic,
         workspace_id: agent_state.workspace_id.clone(),
-        terminal_session_id: "webview".to_string(),
+        terminal_session_id: origin.to_string(),
         run_id: None,
         session_id: agent_state.session_id.clone(),
-        agent_id: agent_id.to_string(),
+        agent_id: agent_state.agent_id.clone(),
         target_agent_id: None,
         task_id: None,
         severity: Severity::Debug,
-        payload: serde_json::json!({}),
+        payload,
         thinking_text: None,
-        raw: serde_json::json!({}),
-    };
+        raw,
+        redactions: Vec::new(),
+    }
+}
 
-    let result = state_machine::on_event(
-        &synthetic_event,
-        &mut agent_state,
-        &state.config.state_machine,
-        0,
+/// synthetic 이벤트를 events에 기록하고 같은 트랜잭션에서 전이한 뒤 알린다 (state-machine.md §6).
+/// 기록은 이력용이므로 실패해도 전이는 진행한다.
+pub(crate) fn record_and_apply(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    agent_state: AgentState,
+    event: &NormalizedEvent,
+) -> Result<AgentStatus, AppError> {
+    let fingerprint = fingerprint::generate(
+        event,
+        state.config.fingerprint.ts_bucket_secs,
+        state.legacy_fingerprint_until.as_deref(),
     );
+    let applied = state.agents.transaction(|tx| {
+        if let Err(e) = EventsRepo::new(tx.conn()).insert(event, &fingerprint) {
+            tracing::warn!("record_and_apply: {:?} event insert failed: {e}", event.event_type);
+        }
+        transition(state, tx, agent_state, event)
+    })?;
+    Ok(publish(state, app_handle, applied, event))
+}
+
+/// 트랜잭션 안에서 계산하고 저장한 전이. 커밋된 뒤 publish로 알린다
+pub(crate) struct Applied {
+    agent_state: AgentState,
+    result: state_machine::TransitionResult,
+    was_unresponsive: bool,
+    had_peer: bool,
+}
+
+/// 이벤트 하나로 상태 전이 + agent_state/전이 이력 저장. 호출자의 unit of work 안에서 실행한다.
+/// Hook 이벤트(ingest)와 synthetic 이벤트(WebView, heartbeat, 대화 대기열, 상태 보정)가 모두
+/// transition(트랜잭션 안) → publish(커밋 후) 경로를 쓴다.
+pub(crate) fn transition(
+    state: &AppState,
+    tx: &mut AgentTx<'_>,
+    mut agent_state: AgentState,
+    event: &NormalizedEvent,
+) -> Result<Applied, AppError> {
+    let was_unresponsive = agent_state.unresponsive;
+    let had_peer = agent_state.peer_agent_id.is_some();
+    let result = state_machine::on_event(event, &mut agent_state, &state.config.state_machine);
+
+    tx.put_state(&agent_state)?;
 
-    state_repo.upsert(&agent_state)?;
-
-    if let state_machine::TransitionResult::Changed { prev_status, new_status } = result {
-        // 퇴장 완료 (disappear_done -> offline)
-        if new_status == AgentStatus::Offline {
-            let payload = serde_json::json!({
-                "agent_id": agent_id,
-                "ts": ts,
-            });
-            if let Err(e) = app_handle.emit("mascot://agent-departed", &payload) {
-                tracing::error!("process_synthetic_event: emit agent-departed failed: {e}");
+    if let state_machine::TransitionResult::Changed { prev_status, prev_since, .. } = &result {
+        // 전이 이력은 분석용이므로 실패해도 계속 진행한다
+        let record = TransitionRecord::new(&agent_state, prev_status, prev_since, Some(&event.id));
+        if let Err(e) = TransitionsRepo::new(tx.conn()).insert(&record) {
+            tracing::warn!("transition: history insert failed: {e}");
+        }
+    }
+
+    Ok(Applied { agent_state, result, was_unresponsive, had_peer })
+}
+
+/// 커밋된 전이를 알린다: 마감 재설정, Tauri emit, 대화 대기열 진행.
+/// 전이가 없어도 응답 없음 표시나 대화 상대가 바뀌었으면 상태 그대로 agent-update를 보낸다.
+pub(crate) fn publish(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    applied: Applied,
+    event: &NormalizedEvent,
+) -> AgentStatus {
+    let Applied { agent_state, result, was_unresponsive, had_peer } = applied;
+    let agent_id = &agent_state.agent_id;
+    state.scheduler.reschedule(&agent_state, &state.config);
+
+    match result {
+        state_machine::TransitionResult::Changed { prev_status, new_status, .. } => {
+            // 등장 (새 에이전트 포함: 등록 직후 상태는 offline)
+            if prev_status == AgentStatus::Offline && new_status == AgentStatus::Appearing {
+                emit_agent_appeared(state, app_handle, &agent_state, &event.ts);
             }
-        } else {
-            let payload = serde_json::json!({
-                "agent_id": agent_id,
-                "status": new_status,
-                "prev_status": prev_status,
-                "thinking_text": agent_state.thinking_text,
-                "current_task": agent_state.current_task,
-                "workspace_id": agent_state.workspace_id,
-                "peer_agent_id": agent_state.peer_agent_id,
-                "chat_message": serde_json::Value::Null,
-                "ts": ts,
-            });
-            if let Err(e) = app_handle.emit("mascot://agent-update", &payload) {
-                tracing::error!("process_synthetic_event: emit agent-update failed: {e}");
+
+            // 퇴장 완료 (disappear_done -> offline)
+            if new_status == AgentStatus::Offline {
+                let payload = serde_json::json!({
+                    "agent_id": agent_id,
+                    "ts": event.ts,
+                });
+                if let Err(e) = app_handle.emit("mascot://agent-departed", &payload) {
+                    tracing::error!("publish: emit agent-departed failed: {e}");
+                }
+            } else {
+                let payload = update_payload(
+                    state,
+                    &agent_state,
+                    &prev_status,
+                    extract_chat_message(event),
+                    &event.ts,
+                );
+                if let Err(e) = app_handle.emit("mascot://agent-update", &payload) {
+                    tracing::error!("publish: emit agent-update failed: {e}");
+                }
+            }
+
+            advance_chat_queue(state, app_handle, agent_id, &new_status);
+        }
+        // 같은 상태 유지 (도구 호출, 재시도 등): 전이 이력 없이 표시 정보만 갱신
+        state_machine::TransitionResult::Updated => {
+            emit_agent_refresh(state, app_handle, &agent_state);
+        }
+        state_machine::TransitionResult::NoOp => {
+            if agent_state.unresponsive != was_unresponsive
+                || agent_state.peer_agent_id.is_some() != had_peer
+            {
+                emit_agent_refresh(state, app_handle, &agent_state);
             }
         }
     }
 
-    Ok(())
+    agent_state.status
+}
+
+/// mascot://agent-appeared (ipc-protocol.md §2.2). 외형은 agent_id로 결정되므로 다시 계산한다
+fn emit_agent_appeared(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    agent_state: &AgentState,
+    ts: &str,
+) {
+    let slot_counts = match state.slot_counts.lock() {
+        Ok(s) => s.clone(),
+        Err(e) => {
+            tracing::warn!("emit_agent_appeared: slot_counts lock poisoned, using defaults: {e}");
+            SlotCounts::default()
+        }
+    };
+    let agent_id = &agent_state.agent_id;
+    let appearance = appearance::generate_appearance(agent_id, &slot_counts, &state.config.appearance);
+
+    let payload = serde_json::json!({
+        "agent_id": agent_id,
+        "display_name": agent_id.split('/').last().unwrap_or(agent_id),
+        "role": "worker",
+        "employment_type": "contractor",
+        "workspace_id": agent_state.workspace_id,
+        "status": agent_state.status,
+        "appearance": appearance,
+        "ts": ts,
+    });
+    if let Err(e) = app_handle.emit("mascot://agent-appeared", &payload) {
+        tracing::error!("emit_agent_appeared: emit agent-appeared failed: {e}");
+    }
+}
+
+/// mascot://agent-update payload (ipc-protocol.md §2.2)
+fn update_payload(
+    state: &AppState,
+    agent_state: &AgentState,
+    prev_status: &AgentStatus,
+    chat_message: Option<String>,
+    ts: &str,
+) -> serde_json::Value {
+    let queue = chat_queue::lookup(&state.chat_queue, &agent_state.agent_id);
+    serde_json::json!({
+        "agent_id": agent_state.agent_id,
+        "status": agent_state.status,
+        "prev_status": prev_status,
+        "thinking_text": agent_state.thinking_text,
+        "current_task": agent_state.current_task,
+        "activity": agent_state.activity,
+        "activity_summary": agent_state.activity_summary,
+        "consecutive_failures": agent_state.consecutive_failures,
+        "unresponsive": agent_state.unresponsive,
+        "workspace_id": agent_state.workspace_id,
+        "peer_agent_id": agent_state.peer_agent_id,
+        "chat_message": chat_message,
+        "queued_for": queue.as_ref().map(|(target, _)| target),
+        "queue_position": queue.as_ref().map(|(_, position)| position),
+        "ts": ts,
+    })
+}
+
+/// 전이 후 대화 대기열 갱신 (state-machine.md §10.6).
+/// 방문이 끝났으면 다음 대기자의 message_sent를 다시 처리해 walking으로 보내고,
+/// 남은 대기자에게 바뀐 순번을 알린다.
+pub(crate) fn advance_chat_queue(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+    status: &AgentStatus,
+) {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let timeout_secs = state.config.movement.chat_queue_timeout_secs;
+    let next = match state.chat_queue.lock() {
+        Ok(mut queue) => queue.on_transition(agent_id, status, now_ms, timeout_secs),
+        Err(e) => {
+            tracing::warn!("advance_chat_queue: chat_queue lock poisoned: {e}");
+            return;
+        }
+    };
+    let Some(next) = next else {
+        return;
+    };
+
+    tracing::info!("chat_queue: {} may now walk to {}", next.visitor, next.target);
+    emit_queue_positions(state, app_handle, &next.target);
+    start_visit(state, app_handle, next);
+}
+
+/// 차례가 온 방문자의 message_sent를 다시 처리해 walking으로 보낸다.
+/// 그 사이 상태가 바뀌어 walking으로 가지 못했으면 차례를 넘긴다.
+pub(crate) fn start_visit(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    next: chat_queue::QueuedVisit,
+) {
+    match apply_synthetic_event(
+        state,
+        app_handle,
+        &next.visitor,
+        "chat_queue",
+        EventType::MessageSent,
+        Some(next.target.clone()),
+        next.payload,
+    ) {
+        Ok(Some(status)) => advance_chat_queue(state, app_handle, &next.visitor, &status),
+        Ok(None) => advance_chat_queue(state, app_handle, &next.visitor, &AgentStatus::Offline),
+        Err(e) => {
+            tracing::warn!("advance_chat_queue: replay for {} failed: {e}", next.visitor);
+            advance_chat_queue(state, app_handle, &next.visitor, &AgentStatus::Offline);
+        }
+    }
+}
+
+/// 대상의 대기자 각각에게 현재 순번을 실은 agent-update를 emit한다 (상태는 그대로)
+pub(crate) fn emit_queue_positions(state: &AppState, app_handle: &tauri::AppHandle, target: &str) {
+    let waiting = match state.chat_queue.lock() {
+        Ok(queue) => queue.waiting(target),
+        Err(e) => {
+            tracing::warn!("emit_queue_positions: chat_queue lock poisoned: {e}");
+            return;
+        }
+    };
+    for visitor in waiting {
+        match state.agents.get_state(&visitor) {
+            Ok(Some(agent_state)) => emit_agent_refresh(state, app_handle, &agent_state),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("emit_queue_positions: state get failed for {visitor}: {e}"),
+        }
+    }
+}
+
+/// 상태 변화 없이 대기열 순번이나 응답 없음 표시만 바뀐 에이전트의 agent-update (status == prev_status).
+/// 대기에서 빠졌으면 queued_for/queue_position이 null이다.
+pub(crate) fn emit_agent_refresh(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    agent_state: &AgentState,
+) {
+    let payload = update_payload(
+        state,
+        agent_state,
+        &agent_state.status,
+        None,
+        &agent_state.last_event_ts,
+    );
+    if let Err(e) = app_handle.emit("mascot://agent-update", &payload) {
+        tracing::error!("emit_agent_refresh: emit agent-update failed: {e}");
+    }
+}
+
+/// 대화 메시지 추출 (message_sent payload)
+fn extract_chat_message(event: &NormalizedEvent) -> Option<String> {
+    event.payload
+        .get("message")
+        .and_then(|v| v.as_str())
+        .map(String::from)
 }
@@ -0,0 +1,12 @@
+use crate::error::AppError;
+use crate::models::event::SchemaErrorCount;
+use crate::state::AppState;
+use crate::storage::events_repo::EventsRepo;
+
+/// 워크스페이스별 schema_error 수 반환 (hooks-integration.md §7.5)
+#[tauri::command]
+pub async fn get_schema_error_counts(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SchemaErrorCount>, AppError> {
+    EventsRepo::new(state.db.clone()).count_schema_errors_by_workspace()
+}
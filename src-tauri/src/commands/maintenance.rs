@@ -0,0 +1,17 @@
+use crate::error::AppError;
+use crate::models::maintenance::{CompactReport, DbStats};
+use crate::services::retention;
+use crate::state::AppState;
+use crate::storage::maintenance_repo::MaintenanceRepo;
+
+/// DB 크기와 테이블별 행 수 반환 (ipc-protocol.md §3.1)
+#[tauri::command]
+pub async fn get_db_stats(state: tauri::State<'_, AppState>) -> Result<DbStats, AppError> {
+    MaintenanceRepo::new(state.db.clone()).stats()
+}
+
+/// 보존 정책을 바로 적용하고 전체 VACUUM한다 (ipc-protocol.md §3.1)
+#[tauri::command]
+pub async fn compact_db(state: tauri::State<'_, AppState>) -> Result<CompactReport, AppError> {
+    retention::compact(&state.db, &state.config.retention, chrono::Utc::now())
+}
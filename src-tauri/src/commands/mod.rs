@@ -1,2 +1,6 @@
 pub mod agents;
+pub mod events;
+pub mod maintenance;
+pub mod tasks;
 pub mod window;
+pub mod workspaces;
@@ -0,0 +1,13 @@
+use crate::error::AppError;
+use crate::models::task::TaskRecord;
+use crate::state::AppState;
+use crate::storage::tasks_repo::TasksRepo;
+
+/// 작업 보드 반환. workspace_id를 생략하면 전체 워크스페이스.
+#[tauri::command]
+pub async fn get_tasks(
+    state: tauri::State<'_, AppState>,
+    workspace_id: Option<String>,
+) -> Result<Vec<TaskRecord>, AppError> {
+    TasksRepo::new(state.db.clone()).get_all(workspace_id.as_deref())
+}
@@ -0,0 +1,85 @@
+use crate::error::AppError;
+use crate::models::workspace::{WorkspaceMerge, WorkspaceSummary};
+use crate::services::workspace;
+use crate::state::AppState;
+use crate::storage::workspaces_repo::WorkspacesRepo;
+use tauri::Emitter;
+
+/// 워크스페이스 목록 (에이전트/이벤트 수, 최근 이벤트, 별칭)
+#[tauri::command]
+pub async fn list_workspaces(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<WorkspaceSummary>, AppError> {
+    WorkspacesRepo::new(state.db.clone()).list()
+}
+
+/// from의 이벤트/에이전트/작업을 into로 합치고 from을 into의 별칭으로 남긴다.
+#[tauri::command]
+pub async fn merge_workspaces(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+    from: String,
+    into: String,
+) -> Result<WorkspaceMerge, AppError> {
+    let config = &state.config.workspace;
+    let from = workspace::normalize_alias(&from, config);
+    let into = workspace::normalize_alias(&into, config);
+    let repo = WorkspacesRepo::new(state.db.clone());
+    // agents/agent_state의 workspace_id도 바뀌므로 메모리 사본과 함께 갱신한다
+    let result = state.agents.bulk_update(|| repo.merge(&from, &into))?;
+    emit_changed(&app_handle, &result);
+    Ok(result)
+}
+
+/// 워크스페이스 이름 변경. 새 이름이 이미 있으면 거부한다 (합치려면 merge_workspaces).
+#[tauri::command]
+pub async fn rename_workspace(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+    from: String,
+    to: String,
+) -> Result<WorkspaceMerge, AppError> {
+    let config = &state.config.workspace;
+    let from = workspace::normalize_alias(&from, config);
+    let to = workspace::normalize_alias(&to, config);
+    let repo = WorkspacesRepo::new(state.db.clone());
+    if repo.exists(&to)? {
+        return Err(AppError::InvalidArgument(format!(
+            "workspace {to:?} already exists"
+        )));
+    }
+    let result = state.agents.bulk_update(|| repo.merge(&from, &to))?;
+    emit_changed(&app_handle, &result);
+    Ok(result)
+}
+
+/// 이후 수신되는 alias 워크스페이스 이벤트를 workspace_id로 기록한다. 기존 데이터는 옮기지 않는다.
+#[tauri::command]
+pub async fn set_workspace_alias(
+    state: tauri::State<'_, AppState>,
+    alias: String,
+    workspace_id: String,
+) -> Result<(), AppError> {
+    let config = &state.config.workspace;
+    WorkspacesRepo::new(state.db.clone()).set_alias(
+        &workspace::normalize_alias(&alias, config),
+        &workspace::normalize_alias(&workspace_id, config),
+    )
+}
+
+/// 별칭 삭제. 삭제했으면 true.
+#[tauri::command]
+pub async fn remove_workspace_alias(
+    state: tauri::State<'_, AppState>,
+    alias: String,
+) -> Result<bool, AppError> {
+    let alias = workspace::normalize_alias(&alias, &state.config.workspace);
+    WorkspacesRepo::new(state.db.clone()).remove_alias(&alias)
+}
+
+fn emit_changed(app_handle: &tauri::AppHandle, result: &WorkspaceMerge) {
+    let payload = serde_json::json!({ "from": result.from, "to": result.into });
+    if let Err(e) = app_handle.emit("mascot://workspaces-changed", &payload) {
+        tracing::error!("workspaces: emit workspaces-changed failed: {e}");
+    }
+}
@@ -1,5 +1,10 @@
 use crate::error::{AppError, ConfigError};
+use crate::models::agent::{AgentStatus, ToolCategory};
+use crate::models::event::{EventType, FailureClass, Severity};
+use crate::models::transition::{TimerRule, TransitionRule};
+use crate::services::{failure, state_machine};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -7,12 +12,19 @@ pub struct AppConfig {
     pub server: ServerConfig,
     pub display: DisplayConfig,
     pub state_machine: StateMachineConfig,
-    pub heartbeat: HeartbeatConfig,
+    pub liveness: LivenessConfig,
     pub movement: MovementConfig,
     pub appearance: AppearanceConfig,
     pub resume: ResumeConfig,
     pub auth: AuthConfig,
     pub drag: DragConfig,
+    pub thinking: ThinkingConfig,
+    pub fingerprint: FingerprintConfig,
+    pub redaction: RedactionConfig,
+    pub normalizer: NormalizerConfig,
+    pub activity: ActivityConfig,
+    pub workspace: WorkspaceConfig,
+    pub retention: RetentionConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -37,19 +49,41 @@ pub struct StateMachineConfig {
     pub fatal_keywords: Vec<String>,
     pub retryable_keywords: Vec<String>,
     pub fatal_consecutive_failures: u32,
-    pub timer_transitions: TimerTransitionsConfig,
+    /// 전이 테이블 (state-machine.md §4.1). 위에서부터 평가하여 처음 매치한 항목으로 전이
+    pub transitions: Vec<TransitionRule>,
+    /// 타이머 전이 (state-machine.md §5). 같은 상태의 규칙은 위에서부터 처음 만료된 것을 적용
+    pub timers: Vec<TimerRule>,
+    /// 실패 분류 규칙. 위에서부터 평가하고, 키워드 목록보다 먼저 적용한다
+    #[serde(default)]
+    pub failure_rules: Vec<FailureRule>,
 }
 
+/// `[[state_machine.failure_rules]]` 하나 (state-machine.md §4.3).
+/// 지정한 조건을 모두 만족하면 매치한다.
 #[derive(Debug, Deserialize, Clone)]
-pub struct TimerTransitionsConfig {
-    pub idle_to_resting_secs: u64,
-    pub completed_to_disappear_secs: u64,
-    pub chat_timeout_secs: u64,
+pub struct FailureRule {
+    pub name: String,
+    pub class: FailureClass,
+    /// tool_name glob (`*`, `?`) 목록. 비어 있으면 모든 도구
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// error_message에 대한 정규식
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// payload.exit_code 값 또는 범위 (`"127"`, `"126-127"`). 하나라도 포함되면 매치
+    #[serde(default)]
+    pub exit_codes: Vec<String>,
 }
 
+/// Stop 없이 끊긴 세션 감지 (state-machine.md §5.1). 세션 이벤트의 last_event_ts 기준
 #[derive(Debug, Deserialize, Clone)]
-pub struct HeartbeatConfig {
-    pub interval_secs: u64,
+pub struct LivenessConfig {
+    /// 이 시간 동안 이벤트가 없으면 응답 없음으로 표시
+    pub unresponsive_after_secs: u64,
+    /// 이 시간 동안 이벤트가 없으면 퇴장 (agent_stopped)
+    pub disappear_after_secs: u64,
+    /// 감시할 상태. idle/pending_input처럼 사용자를 기다리는 상태는 조용한 게 정상이다
+    pub statuses: Vec<AgentStatus>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -92,6 +126,101 @@ pub struct DragConfig {
     pub push_strength: f64,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct ThinkingConfig {
+    /// 같은 에이전트의 thinking_updated 최소 간격 (ms)
+    pub throttle_ms: u64,
+    /// 에이전트별로 보관하는 thinking 스니펫 최대 개수
+    pub history_limit: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FingerprintConfig {
+    /// 중복 판정 시 ts를 묶는 버킷 크기 (초). 0이면 ts 원문 사용
+    pub ts_bucket_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// 이 길이(문자 수)를 넘는 문자열 값은 절삭하여 저장
+    pub max_string_chars: usize,
+    /// 값 전체를 마스킹할 키 이름 규칙 (대소문자/`-`·`_` 무시, 접미사 매치)
+    pub keys: Vec<String>,
+    /// 기본 제공 토큰 패턴 (API 키, JWT, private key 등) 사용 여부
+    pub use_builtin_patterns: bool,
+    /// 추가 정규식 규칙. `secret` 캡처 그룹이 있으면 그 부분만 마스킹
+    #[serde(default)]
+    pub patterns: Vec<RedactionPattern>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RedactionPattern {
+    pub name: String,
+    pub regex: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct NormalizerConfig {
+    /// 사용자 정의 정규화 규칙. 위에서부터 평가하며 내장 매핑보다 먼저 적용된다
+    #[serde(default)]
+    pub rules: Vec<NormalizerRule>,
+}
+
+/// `[[normalizer.rules]]` 하나 (hooks-integration.md §6.3)
+#[derive(Debug, Deserialize, Clone)]
+pub struct NormalizerRule {
+    pub name: String,
+    /// 일치해야 하는 hook_type (예: "PreToolUse")
+    pub hook_type: String,
+    /// tool_name glob (`*`, `?`). 없으면 모든 도구
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    /// tool_input 기준 JSON Pointer → 기대값. 모두 같아야 매치
+    #[serde(default)]
+    pub conditions: BTreeMap<String, serde_json::Value>,
+    pub event_type: EventType,
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    /// task_id를 읽을 raw payload 기준 JSON Pointer
+    #[serde(default)]
+    pub task_id: Option<String>,
+    /// payload 필드 이름 → raw payload 기준 JSON Pointer
+    #[serde(default)]
+    pub payload: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ActivityConfig {
+    /// agent-update의 activity_summary 최대 문자 수
+    pub summary_max_chars: usize,
+    /// 분류 → 도구 이름 glob 목록. 어디에도 없으면 other
+    pub categories: BTreeMap<ToolCategory, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WorkspaceConfig {
+    /// workspace_id를 소문자로 정규화 (Windows/macOS 경로 대소문자 차이 흡수)
+    pub case_insensitive: bool,
+    /// `_meta.git_remote`가 있으면 원격 저장소 경로(`org/repo`)를 workspace_id로 사용
+    pub use_git_remote: bool,
+}
+
+/// events 보존 정책. 백그라운드 정리 작업과 `compact_db`가 사용한다
+#[derive(Debug, Deserialize, Clone)]
+pub struct RetentionConfig {
+    /// 이벤트 보존 기간 (일). 지난 이벤트는 삭제
+    pub max_age_days: u32,
+    /// 이벤트 최대 행 수. 넘으면 오래된 것부터 삭제
+    pub max_events: u64,
+    /// raw_json(원본 payload) 보존 기간 (일). 지나면 정규화된 이벤트만 남긴다
+    pub raw_json_max_age_days: u32,
+    /// 정리 작업 주기 (초)
+    pub prune_interval_secs: u64,
+    /// 한 번에 지우는 행 수. 배치 사이에 DB 락을 놓아 ingest가 끼어들 수 있게 한다
+    pub prune_batch_size: usize,
+}
+
 impl AppConfig {
     /// config.toml 파일에서 설정을 로드한다.
     /// 파일이 없거나 파싱 실패 시 에러를 반환한다 (기본값 폴백 없음).
@@ -148,14 +277,43 @@ impl AppConfig {
             .into());
         }
 
-        if self.heartbeat.interval_secs == 0 {
+        if self.movement.chat_queue_timeout_secs == 0 {
             return Err(ConfigError::Validation {
-                field: "heartbeat.interval_secs".into(),
+                field: "movement.chat_queue_timeout_secs".into(),
                 reason: "must be > 0".into(),
             }
             .into());
         }
 
+        if self.liveness.unresponsive_after_secs == 0 {
+            return Err(ConfigError::Validation {
+                field: "liveness.unresponsive_after_secs".into(),
+                reason: "must be > 0".into(),
+            }
+            .into());
+        }
+
+        if self.liveness.disappear_after_secs <= self.liveness.unresponsive_after_secs {
+            return Err(ConfigError::Validation {
+                field: "liveness.disappear_after_secs".into(),
+                reason: "must be greater than unresponsive_after_secs".into(),
+            }
+            .into());
+        }
+
+        if self
+            .liveness
+            .statuses
+            .iter()
+            .any(|s| matches!(s, AgentStatus::Offline | AgentStatus::Disappearing))
+        {
+            return Err(ConfigError::Validation {
+                field: "liveness.statuses".into(),
+                reason: "offline/disappearing cannot be watched".into(),
+            }
+            .into());
+        }
+
         if self.appearance.skin_lightness_max > 100.0 || self.appearance.skin_lightness_min < 0.0 {
             return Err(ConfigError::Validation {
                 field: "appearance.skin_lightness".into(),
@@ -220,6 +378,175 @@ impl AppConfig {
             .into());
         }
 
+        if self.redaction.max_string_chars == 0 {
+            return Err(ConfigError::Validation {
+                field: "redaction.max_string_chars".into(),
+                reason: "must be > 0".into(),
+            }
+            .into());
+        }
+
+        for pattern in &self.redaction.patterns {
+            if let Err(e) = regex::Regex::new(&pattern.regex) {
+                return Err(ConfigError::Validation {
+                    field: format!("redaction.patterns.{}", pattern.name),
+                    reason: format!("invalid regex: {e}"),
+                }
+                .into());
+            }
+        }
+
+        if self.thinking.history_limit == 0 {
+            return Err(ConfigError::Validation {
+                field: "thinking.history_limit".into(),
+                reason: "must be > 0".into(),
+            }
+            .into());
+        }
+
+        if self.activity.summary_max_chars == 0 {
+            return Err(ConfigError::Validation {
+                field: "activity.summary_max_chars".into(),
+                reason: "must be > 0".into(),
+            }
+            .into());
+        }
+        for (category, patterns) in &self.activity.categories {
+            if patterns.iter().any(|p| p.trim().is_empty()) {
+                return Err(ConfigError::Validation {
+                    field: format!("activity.categories.{category:?}"),
+                    reason: "tool name pattern must not be empty".into(),
+                }
+                .into());
+            }
+        }
+
+        if let Err(reason) = state_machine::validate_transitions(&self.state_machine) {
+            return Err(ConfigError::Validation {
+                field: "state_machine.transitions".into(),
+                reason,
+            }
+            .into());
+        }
+
+        let mut failure_rule_names = std::collections::HashSet::new();
+        for (i, rule) in self.state_machine.failure_rules.iter().enumerate() {
+            let field = format!("state_machine.failure_rules[{i}]");
+            let invalid = |reason: String| -> AppError {
+                ConfigError::Validation {
+                    field: field.clone(),
+                    reason,
+                }
+                .into()
+            };
+
+            if rule.name.trim().is_empty() {
+                return Err(invalid("name must not be empty".into()));
+            }
+            if !failure_rule_names.insert(rule.name.as_str()) {
+                return Err(invalid(format!("duplicate rule name: {}", rule.name)));
+            }
+            if rule.tools.is_empty() && rule.regex.is_none() && rule.exit_codes.is_empty() {
+                return Err(invalid("at least one of tools, regex, exit_codes is required".into()));
+            }
+            if rule.tools.iter().any(|t| t.trim().is_empty()) {
+                return Err(invalid("tool glob must not be empty".into()));
+            }
+            if let Some(Err(e)) = rule.regex.as_deref().map(regex::Regex::new) {
+                return Err(invalid(format!("invalid regex: {e}")));
+            }
+            for range in &rule.exit_codes {
+                if failure::parse_exit_code_range(range).is_none() {
+                    return Err(invalid(format!("invalid exit code range: {range:?}")));
+                }
+            }
+        }
+
+        let mut rule_names = std::collections::HashSet::new();
+        for (i, rule) in self.normalizer.rules.iter().enumerate() {
+            let field = format!("normalizer.rules[{i}]");
+            let invalid = |reason: String| -> AppError {
+                ConfigError::Validation {
+                    field: field.clone(),
+                    reason,
+                }
+                .into()
+            };
+
+            if rule.name.trim().is_empty() {
+                return Err(invalid("name must not be empty".into()));
+            }
+            if !rule_names.insert(rule.name.as_str()) {
+                return Err(invalid(format!("duplicate rule name: {}", rule.name)));
+            }
+            if rule.hook_type.trim().is_empty() {
+                return Err(invalid("hook_type must not be empty".into()));
+            }
+            if rule.tool_name.as_deref().is_some_and(|g| g.is_empty()) {
+                return Err(invalid("tool_name glob must not be empty".into()));
+            }
+            // synthetic 이벤트와 schema_error는 hook에서 만들 수 없다
+            if matches!(
+                rule.event_type,
+                EventType::SchemaError
+                    | EventType::MessageSent
+                    | EventType::MessageReceived
+                    | EventType::AppearDone
+                    | EventType::DisappearDone
+                    | EventType::StartledDone
+                    | EventType::ArriveAtPeer
+                    | EventType::ArriveAtHome
+                    | EventType::MessageDone
+                    | EventType::AgentUnresponsive
+                    | EventType::TimerElapsed
+                    | EventType::StateRepaired
+            ) {
+                return Err(invalid(format!(
+                    "event_type {:?} cannot be produced by a rule",
+                    rule.event_type
+                )));
+            }
+            let pointers = rule
+                .conditions
+                .keys()
+                .chain(rule.payload.values())
+                .chain(rule.task_id.iter());
+            for pointer in pointers {
+                if !pointer.starts_with('/') {
+                    return Err(invalid(format!(
+                        "JSON pointer must start with '/': {pointer:?}"
+                    )));
+                }
+            }
+            if rule.payload.contains_key("tool_name") || rule.payload.contains_key("rule") {
+                return Err(invalid("payload keys tool_name and rule are reserved".into()));
+            }
+        }
+
+        let retention = &self.retention;
+        for (field, value) in [
+            ("retention.max_age_days", u64::from(retention.max_age_days)),
+            ("retention.max_events", retention.max_events),
+            ("retention.raw_json_max_age_days", u64::from(retention.raw_json_max_age_days)),
+            ("retention.prune_interval_secs", retention.prune_interval_secs),
+            ("retention.prune_batch_size", retention.prune_batch_size as u64),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::Validation {
+                    field: field.into(),
+                    reason: "must be > 0".into(),
+                }
+                .into());
+            }
+        }
+        if retention.raw_json_max_age_days > retention.max_age_days {
+            return Err(ConfigError::Validation {
+                field: "retention.raw_json_max_age_days".into(),
+                reason: "must not exceed max_age_days".into(),
+            }
+            .into());
+        }
+
         Ok(())
     }
 }
@@ -244,9 +571,12 @@ mod tests {
         let config = AppConfig::load(f.path()).expect("should parse valid config");
         assert_eq!(config.server.port, 4820);
         assert_eq!(config.server.host, "127.0.0.1");
-        assert_eq!(config.heartbeat.interval_secs, 10);
         assert_eq!(config.display.max_bubble_chars, 80);
         assert_eq!(config.state_machine.fatal_consecutive_failures, 3);
+        assert_eq!(config.thinking.history_limit, 20);
+        assert_eq!(config.fingerprint.ts_bucket_secs, 1);
+        assert_eq!(config.retention.raw_json_max_age_days, 3);
+        assert!(config.activity.categories[&ToolCategory::Editing].contains(&"Edit".to_string()));
     }
 
     #[test]
@@ -262,6 +592,15 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validation_raw_json_retention_exceeds_event_retention() {
+        let content = include_str!("../config.toml")
+            .replace("raw_json_max_age_days = 3", "raw_json_max_age_days = 60");
+        let f = write_temp_config(&content);
+        let err = AppConfig::load(f.path()).expect_err("raw_json outlives its event");
+        assert!(err.to_string().contains("retention.raw_json_max_age_days"), "{err}");
+    }
+
     #[test]
     fn test_validation_port_zero() {
         let mut content = include_str!("../config.toml").to_string();
@@ -270,4 +609,75 @@ mod tests {
         let result = AppConfig::load(f.path());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_load_normalizer_rule() {
+        let mut content = include_str!("../config.toml").to_string();
+        content.push_str(concat!(
+            "\n[[normalizer.rules]]\n",
+            "name = \"deploy\"\n",
+            "hook_type = \"PreToolUse\"\n",
+            "tool_name = \"mcp__deploy__*\"\n",
+            "event_type = \"task_started\"\n",
+            "task_id = \"/tool_input/deployment_id\"\n",
+            "conditions = { \"/env\" = \"production\" }\n",
+            "payload = { environment = \"/tool_input/env\" }\n",
+        ));
+        let f = write_temp_config(&content);
+        let config = AppConfig::load(f.path()).expect("should load");
+        let rule = &config.normalizer.rules[0];
+        assert_eq!(rule.event_type, EventType::TaskStarted);
+        assert_eq!(rule.conditions["/env"], serde_json::json!("production"));
+        assert!(rule.severity.is_none());
+    }
+
+    #[test]
+    fn test_validation_failure_rules() {
+        let base = include_str!("../config.toml");
+        let valid = "name = \"bash_not_found\"\nclass = \"fatal\"\ntools = [\"Bash\"]\nexit_codes = [\"126-127\"]\n";
+        let f = write_temp_config(&format!("{base}\n[[state_machine.failure_rules]]\n{valid}"));
+        let config = AppConfig::load(f.path()).expect("should load");
+        assert_eq!(config.state_machine.failure_rules[0].class, FailureClass::Fatal);
+
+        let broken = [
+            // 조건 없음
+            "name = \"a\"\nclass = \"retryable\"\n",
+            // 잘못된 정규식
+            "name = \"a\"\nclass = \"retryable\"\nregex = \"(unclosed\"\n",
+            // 뒤집힌 범위
+            "name = \"a\"\nclass = \"fatal\"\nexit_codes = [\"130-128\"]\n",
+        ];
+        for rule in broken {
+            let content = format!("{base}\n[[state_machine.failure_rules]]\n{rule}");
+            let f = write_temp_config(&content);
+            assert!(AppConfig::load(f.path()).is_err(), "{rule}");
+        }
+    }
+
+    #[test]
+    fn test_validation_invalid_normalizer_rule() {
+        let base = include_str!("../config.toml");
+        let broken = [
+            // 상대 경로 pointer
+            "name = \"a\"\nhook_type = \"PreToolUse\"\nevent_type = \"task_started\"\ntask_id = \"tool_input/id\"\n",
+            // synthetic 타입
+            "name = \"a\"\nhook_type = \"PreToolUse\"\nevent_type = \"appear_done\"\n",
+            // 알 수 없는 타입
+            "name = \"a\"\nhook_type = \"PreToolUse\"\nevent_type = \"exploded\"\n",
+        ];
+        for rule in broken {
+            let content = format!("{base}\n[[normalizer.rules]]\n{rule}");
+            let f = write_temp_config(&content);
+            assert!(AppConfig::load(f.path()).is_err(), "{rule}");
+        }
+    }
+
+    #[test]
+    fn test_validation_invalid_redaction_regex() {
+        let mut content = include_str!("../config.toml").to_string();
+        content.push_str("\n[[redaction.patterns]]\nname = \"broken\"\nregex = \"(unclosed\"\n");
+        let f = write_temp_config(&content);
+        let result = AppConfig::load(f.path());
+        assert!(result.is_err());
+    }
 }
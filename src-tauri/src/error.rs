@@ -22,6 +22,9 @@ pub enum AppError {
     #[error("database error: {0}")]
     Database(#[from] rusqlite::Error),
 
+    #[error("schema migration failed: {0}")]
+    Migration(String),
+
     #[error("http server error: {0}")]
     HttpServer(String),
 
@@ -36,6 +39,9 @@ pub enum AppError {
 
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
 }
 
 /// Tauri invoke 에러 응답용 직렬화.
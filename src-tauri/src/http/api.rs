@@ -0,0 +1,92 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use crate::commands::agents::time_in_state;
+use crate::error::AppError;
+use crate::http::server::IngestState;
+use crate::models::event::SchemaErrorCount;
+use crate::models::maintenance::DbStats;
+use crate::models::task::TaskRecord;
+use crate::models::transition::{TimeInState, TimeInStateQuery};
+use crate::services::state_diagram::{self, DiagramFormat};
+use crate::storage::events_repo::EventsRepo;
+use crate::storage::maintenance_repo::MaintenanceRepo;
+use crate::storage::tasks_repo::TasksRepo;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct TasksQuery {
+    pub workspace_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StateDiagramQuery {
+    pub format: Option<DiagramFormat>,
+}
+
+/// GET /api/tasks?workspace_id=... -- 작업 보드 조회
+pub async fn tasks_handler(
+    State(ingest): State<IngestState>,
+    Query(query): Query<TasksQuery>,
+) -> Result<Json<Vec<TaskRecord>>, (StatusCode, String)> {
+    TasksRepo::new(ingest.app_state.db.clone())
+        .get_all(query.workspace_id.as_deref())
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("api: get tasks failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("storage error: {e}"))
+        })
+}
+
+/// GET /api/schema-errors -- 워크스페이스별 schema_error 수
+pub async fn schema_errors_handler(
+    State(ingest): State<IngestState>,
+) -> Result<Json<Vec<SchemaErrorCount>>, (StatusCode, String)> {
+    EventsRepo::new(ingest.app_state.db.clone())
+        .count_schema_errors_by_workspace()
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("api: count schema errors failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("storage error: {e}"))
+        })
+}
+
+/// GET /api/db-stats -- DB 크기와 테이블별 행 수
+pub async fn db_stats_handler(
+    State(ingest): State<IngestState>,
+) -> Result<Json<DbStats>, (StatusCode, String)> {
+    MaintenanceRepo::new(ingest.app_state.db.clone())
+        .stats()
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("api: db stats failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("storage error: {e}"))
+        })
+}
+
+/// GET /api/time-in-state?from=...&to=...&agent_id=...&workspace_id=... -- 상태별 머문 시간
+pub async fn time_in_state_handler(
+    State(ingest): State<IngestState>,
+    Query(query): Query<TimeInStateQuery>,
+) -> Result<Json<Vec<TimeInState>>, (StatusCode, String)> {
+    time_in_state(&ingest.app_state, &query)
+        .map(Json)
+        .map_err(|e| match e {
+            AppError::InvalidArgument(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+            e => {
+                tracing::error!("api: time in state failed: {e}");
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("storage error: {e}"))
+            }
+        })
+}
+
+/// GET /api/state-diagram?format=mermaid|dot -- 상태 머신 다이어그램 (기본 mermaid)
+pub async fn state_diagram_handler(
+    State(ingest): State<IngestState>,
+    Query(query): Query<StateDiagramQuery>,
+) -> String {
+    state_diagram::render(
+        &ingest.app_state.config.state_machine,
+        query.format.unwrap_or(DiagramFormat::Mermaid),
+    )
+}
@@ -1,261 +1,478 @@
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
+use crate::commands::agents;
+use crate::error::AppError;
 use crate::http::server::IngestState;
 use crate::models::agent::*;
 use crate::models::event::*;
-use crate::services::{appearance, normalizer, state_machine};
-use crate::storage::agents_repo::AgentsRepo;
+use crate::models::task::TaskRecord;
+use crate::services::adapter::{self, SourceAdapter};
+use crate::services::{
+    activity, appearance, chat_queue, fingerprint, normalizer, state_machine, task_tracker,
+    thinking, validator, workspace,
+};
+use crate::storage::agent_store::AgentTx;
 use crate::storage::events_repo::EventsRepo;
-use crate::storage::state_repo::StateRepo;
+use crate::storage::tasks_repo::TasksRepo;
+use crate::storage::thinking_repo::ThinkingRepo;
+use crate::storage::workspaces_repo::WorkspacesRepo;
 use tauri::Emitter;
 
-/// POST /ingest -- hook payload 수신 -> 10단계 파이프라인 (hooks-integration.md SS5.3)
+/// POST /ingest -- Claude Code hook payload 수신 -> 10단계 파이프라인 (hooks-integration.md SS5.3)
 pub async fn ingest_handler(
     State(ingest): State<IngestState>,
     Json(payload): Json<serde_json::Value>,
 ) -> impl IntoResponse {
+    ingest_payload(&ingest, adapter::default_adapter(), &payload)
+}
+
+/// POST /ingest/{source} -- source별 어댑터로 정규화 (hooks-integration.md SS5.4)
+pub async fn ingest_source_handler(
+    State(ingest): State<IngestState>,
+    Path(source): Path<String>,
+    Json(payload): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    match adapter::find(&source) {
+        Some(adapter) => ingest_payload(&ingest, adapter, &payload),
+        None => (StatusCode::NOT_FOUND, format!("unknown source: {source}")),
+    }
+}
+
+fn ingest_payload(
+    ingest: &IngestState,
+    adapter: &dyn SourceAdapter,
+    payload: &serde_json::Value,
+) -> (StatusCode, String) {
     // Step 1: JSON 파싱은 axum이 처리 (실패 시 400 자동 반환)
 
-    // Step 2: 정규화
-    let event = match normalizer::normalize(&payload) {
+    // Step 2: 정규화 + 검증 (§7.5). 정규화/검증 실패는 거부하지 않고 schema_error로 기록
+    let event = match adapter.normalize(payload, &ingest.app_state.config.normalizer) {
         Ok(e) => e,
         Err(e) => {
-            tracing::warn!("ingest: normalization failed: {e}");
-            return (StatusCode::BAD_REQUEST, format!("normalization failed: {e}"));
+            tracing::warn!("ingest: normalization failed ({}): {e}", adapter.name());
+            let schema_error = adapter.schema_error(payload, vec![e.to_string()]);
+            return record_schema_error(ingest, schema_error);
         }
     };
 
+    ingest_event(ingest, event)
+}
+
+/// 정규화된 이벤트를 검증 → 마스킹 → 저장/전이/emit 한다 (Step 2 검증 ~ Step 10).
+/// 어댑터(/ingest)와 OTLP 수신(/v1/traces)이 공유한다.
+pub(crate) fn ingest_event(ingest: &IngestState, mut event: NormalizedEvent) -> (StatusCode, String) {
+    resolve_workspace(ingest, &mut event);
+
+    let violations = validator::validate(&event);
+    if !violations.is_empty() {
+        tracing::warn!("ingest: schema violations: {violations:?}");
+        let schema_error = validator::into_schema_error(event, violations);
+        return record_schema_error(ingest, schema_error);
+    }
+
+    // 핑거프린트는 마스킹 전 원본으로 계산 (§7.6)
+    let ts_bucket_secs = ingest.app_state.config.fingerprint.ts_bucket_secs;
+    let legacy_until = ingest.app_state.legacy_fingerprint_until.as_deref();
+    let event_fingerprint = fingerprint::generate(&event, ts_bucket_secs, legacy_until);
+
+    // tool_failed 분류 결과(failure_class, failure_rule)를 이벤트에 기록 (state-machine.md §4.3)
+    ingest.app_state.failure_classifier.annotate(&mut event);
+
+    // 저장 전 비밀값 마스킹 (raw, payload, thinking_text)
+    ingest.app_state.redactor.redact_event(&mut event);
+
+    // 도구 분류와 활동 요약 (§6.4). 절삭 경계에서 잘린 비밀값은 패턴에 걸리지 않으므로
+    // 마스킹된 tool_input으로 만든다
+    activity::annotate(&mut event, &ingest.app_state.config.activity);
+
+    // 도구/작업 이벤트에 실린 thinking은 원본 처리 후 thinking_updated로 이어서 처리
+    let companion = normalizer::thinking_event(&event);
+
+    if event.event_type == EventType::ThinkingUpdated
+        && !prepare_thinking_event(&ingest.app_state, &mut event)
+    {
+        return (StatusCode::OK, "thinking throttled, skipped".to_string());
+    }
+
+    // Step 3~9
+    match process_event(ingest, &event, &event_fingerprint) {
+        Ok(true) => {}
+        Ok(false) => return (StatusCode::OK, "duplicate, skipped".to_string()),
+        Err(response) => return response,
+    }
+
+    if let Some(mut thinking_event) = companion {
+        if prepare_thinking_event(&ingest.app_state, &mut thinking_event) {
+            let thinking_fingerprint =
+                fingerprint::generate(&thinking_event, ts_bucket_secs, legacy_until);
+            if let Err(response) = process_event(ingest, &thinking_event, &thinking_fingerprint) {
+                return response;
+            }
+        }
+    }
+
+    // Step 10: 200 응답
+    (StatusCode::OK, "ok".to_string())
+}
+
+/// workspace_id 정규화 + 별칭 해석 (§7.3.1). 핑거프린트/저장 전에 적용한다.
+/// 별칭 조회 실패 시 정규화된 id를 그대로 쓴다.
+fn resolve_workspace(ingest: &IngestState, event: &mut NormalizedEvent) {
     let state = &ingest.app_state;
-    let app_handle = &ingest.app_handle;
+    let canonical = workspace::canonicalize(&event.workspace_id, &event.raw, &state.config.workspace);
+    event.workspace_id = match WorkspacesRepo::new(state.db.clone()).resolve(&canonical) {
+        Ok(workspace_id) => workspace_id,
+        Err(e) => {
+            tracing::warn!("ingest: workspace alias lookup failed: {e}");
+            canonical
+        }
+    };
+}
 
-    // Step 3: 핑거프린트 중복 검사
-    let tool_name = event.payload
-        .get("tool_name")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let fingerprint = normalizer::generate_fingerprint(
-        event.session_id.as_deref().unwrap_or(""),
-        tool_name,
-        &event.ts,
-        &event.payload,
+/// schema_error 이벤트를 저장하고 mascot://error를 emit한다.
+/// 상태 전이와 에이전트 등록은 하지 않는다 (잘못된 payload로 캐릭터가 등장하지 않도록).
+fn record_schema_error(ingest: &IngestState, mut event: NormalizedEvent) -> (StatusCode, String) {
+    resolve_workspace(ingest, &mut event);
+    let state = &ingest.app_state;
+    let fingerprint = fingerprint::generate(
+        &event,
+        state.config.fingerprint.ts_bucket_secs,
+        state.legacy_fingerprint_until.as_deref(),
     );
+    state.redactor.redact_event(&mut event);
 
     let events_repo = EventsRepo::new(state.db.clone());
-
-    // Step 4: events 테이블에 INSERT (중복이면 skip)
     match events_repo.insert(&event, &fingerprint) {
-        Ok(false) => {
-            tracing::debug!("ingest: duplicate event (fingerprint={})", fingerprint);
-            return (StatusCode::OK, "duplicate, skipped".to_string());
-        }
+        Ok(false) => return (StatusCode::OK, "duplicate, skipped".to_string()),
         Ok(true) => {}
         Err(e) => {
-            tracing::error!("ingest: event insert failed: {e}");
+            tracing::error!("ingest: schema_error insert failed: {e}");
             return (StatusCode::INTERNAL_SERVER_ERROR, format!("storage error: {e}"));
         }
     }
 
-    // Step 5: 에이전트 미등록 시 자동 등록
-    let agents_repo = AgentsRepo::new(state.db.clone());
-    let state_repo = StateRepo::new(state.db.clone());
-    let is_new_agent = ensure_agent_registered(
-        &agents_repo,
-        &state_repo,
-        &event,
-        state,
-    );
+    let violations = event
+        .payload
+        .get("violations")
+        .and_then(|v| v.as_array())
+        .map(|v| {
+            v.iter()
+                .filter_map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+        .unwrap_or_default();
 
-    // Step 6: 상태 전이
-    let mut agent_state = match state_repo.get(&event.agent_id) {
-        Ok(Some(s)) => s,
-        Ok(None) => {
-            // 방금 등록된 에이전트의 초기 상태
-            AgentState {
-                agent_id: event.agent_id.clone(),
-                status: AgentStatus::Offline,
-                prev_status: None,
-                thinking_text: None,
-                current_task: None,
-                workspace_id: event.workspace_id.clone(),
-                since: event.ts.clone(),
-                last_event_ts: event.ts.clone(),
-                session_id: event.session_id.clone(),
-                peer_agent_id: None,
-                home_x: 0.0,
-            }
-        }
+    let error_payload = serde_json::json!({
+        "source": "ingest",
+        "message": format!("schema error ({}): {violations}", event.workspace_id),
+        "ts": event.ts,
+    });
+    if let Err(e) = ingest.app_handle.emit("mascot://error", &error_payload) {
+        tracing::error!("ingest: emit error failed: {e}");
+    }
+
+    (StatusCode::OK, "schema error recorded".to_string())
+}
+
+/// thinking_updated 이벤트를 말풍선 길이로 절삭하고 쓰로틀을 적용한다.
+/// 쓰로틀에 걸려 건너뛰어야 하면 false. 쓰로틀 기록은 저장이 커밋된 뒤 record_thinking이 한다.
+fn prepare_thinking_event(state: &crate::state::AppState, event: &mut NormalizedEvent) -> bool {
+    let Some(text) = event.thinking_text.as_deref() else {
+        return false;
+    };
+    let text = thinking::truncate_chars(text, state.config.display.max_bubble_chars);
+
+    let should_emit = match state.thinking_throttle.lock() {
+        Ok(throttle) => throttle.allows(
+            &event.agent_id,
+            &event.ts,
+            &text,
+            state.config.thinking.throttle_ms,
+        ),
         Err(e) => {
-            tracing::error!("ingest: state get failed: {e}");
-            return (StatusCode::INTERNAL_SERVER_ERROR, format!("state error: {e}"));
+            tracing::warn!("ingest: thinking_throttle lock poisoned, not throttling: {e}");
+            true
         }
     };
 
-    let transition_result = state_machine::on_event(
-        &event,
-        &mut agent_state,
-        &state.config.state_machine,
-        0, // TODO: consecutive failures 추적은 Phase 4에서 구현
-    );
+    event.thinking_text = Some(text);
+    should_emit
+}
 
-    // Step 7: agent_state 테이블 UPDATE
-    if let Err(e) = state_repo.upsert(&agent_state) {
-        tracing::error!("ingest: state upsert failed: {e}");
-        return (StatusCode::INTERNAL_SERVER_ERROR, format!("state error: {e}"));
+/// 커밋된 thinking_updated를 쓰로틀에 기록한다 (중복이거나 롤백된 갱신은 다음 갱신을 막지 않는다)
+fn record_thinking(state: &crate::state::AppState, event: &NormalizedEvent) {
+    let Some(text) = event.thinking_text.as_deref() else {
+        return;
+    };
+    match state.thinking_throttle.lock() {
+        Ok(mut throttle) => throttle.record(&event.agent_id, &event.ts, text),
+        Err(e) => tracing::warn!("ingest: thinking_throttle lock poisoned, not recording: {e}"),
     }
+}
 
-    // Step 8 & 9: Tauri 이벤트 emit
-    match transition_result {
-        state_machine::TransitionResult::Changed { prev_status, new_status } => {
-            // 새 에이전트 등장
-            if is_new_agent || (prev_status == AgentStatus::Offline && new_status == AgentStatus::Appearing) {
-                let slot_counts = match state.slot_counts.lock() {
-                    Ok(s) => s.clone(),
-                    Err(e) => {
-                        tracing::warn!("ingest: slot_counts lock poisoned, using defaults: {e}");
-                        SlotCounts::default()
-                    }
-                };
-                let appearance = appearance::generate_appearance(
-                    &event.agent_id,
-                    &slot_counts,
-                    &state.config.appearance,
-                );
-
-                let appeared_payload = serde_json::json!({
-                    "agent_id": event.agent_id,
-                    "display_name": event.agent_id.split('/').last().unwrap_or(&event.agent_id),
-                    "role": "worker",
-                    "employment_type": "contractor",
-                    "workspace_id": event.workspace_id,
-                    "status": new_status,
-                    "appearance": appearance,
-                    "ts": event.ts,
-                });
-
-                if let Err(e) = app_handle.emit("mascot://agent-appeared", &appeared_payload) {
-                    tracing::error!("ingest: emit agent-appeared failed: {e}");
-                }
-            }
+/// 한 트랜잭션에서 저장한 결과. 커밋된 뒤에만 알린다
+enum Stored {
+    Duplicate,
+    /// 대화 대기열에 줄을 선다 (상태는 그대로, last_event_ts만 갱신). 대기열 반영은 커밋 후
+    Queued { agent_state: AgentState, task: Option<TaskRecord> },
+    /// visit: 대상에게 바로 걸어가는 message_sent. 커밋 후 대상의 방문자로 올린다
+    Applied { applied: agents::Applied, task: Option<TaskRecord>, visit: bool },
+}
 
-            // 퇴장
-            if new_status == AgentStatus::Offline {
-                let departed_payload = serde_json::json!({
-                    "agent_id": event.agent_id,
-                    "ts": event.ts,
-                });
-                if let Err(e) = app_handle.emit("mascot://agent-departed", &departed_payload) {
-                    tracing::error!("ingest: emit agent-departed failed: {e}");
-                }
-            } else {
-                // 상태 변경
-                let update_payload = serde_json::json!({
-                    "agent_id": event.agent_id,
-                    "status": new_status,
-                    "prev_status": prev_status,
-                    "thinking_text": agent_state.thinking_text,
-                    "current_task": agent_state.current_task,
-                    "workspace_id": event.workspace_id,
-                    "peer_agent_id": agent_state.peer_agent_id,
-                    "chat_message": extract_chat_message(&event),
-                    "ts": event.ts,
-                });
-                if let Err(e) = app_handle.emit("mascot://agent-update", &update_payload) {
-                    tracing::error!("ingest: emit agent-update failed: {e}");
-                }
+/// 정규화된 이벤트 하나를 저장 → 상태 전이 → emit 한다 (Step 3~9).
+/// 저장부터 상태 갱신까지는 한 트랜잭션이라 중간에 실패하면 이벤트 행까지 롤백된다.
+/// 중복 이벤트면 Ok(false), 처리했으면 Ok(true), 실패 시 HTTP 에러 응답을 반환한다.
+fn process_event(
+    ingest: &IngestState,
+    event: &NormalizedEvent,
+    fingerprint: &Fingerprint,
+) -> Result<bool, (StatusCode, String)> {
+    let state = &ingest.app_state;
+    let app_handle = &ingest.app_handle;
+
+    // Step 3~7: 하나의 unit of work
+    let stored = state
+        .agents
+        .transaction(|tx| store_event(state, tx, event, fingerprint))
+        .map_err(|e| {
+            tracing::error!("ingest: transaction rolled back: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("storage error: {e}"))
+        })?;
+
+    // Step 8~9: 커밋된 뒤 메모리 상태(대화 대기열, thinking 쓰로틀) 반영 + emit
+    match stored {
+        Stored::Duplicate => {
+            tracing::debug!("ingest: duplicate event (fingerprint={})", fingerprint.value);
+            Ok(false)
+        }
+        Stored::Queued { agent_state, task } => {
+            emit_task_update(app_handle, task);
+            if let Some(chat_queue::Admission::Go) = join_chat_queue(state, event) {
+                // 판정과 커밋 사이에 대상이 비었다. 줄을 서지 않고 바로 걸어간다
+                agents::start_visit(state, app_handle, queued_visit(event));
+                return Ok(true);
             }
+            tracing::info!("ingest: {} queued for {:?}", event.agent_id, event.target_agent_id);
+            // 생존 감지 마감이 늦춰지고, 대기 만료 시각에 heartbeat가 깨어나야 한다
+            state.scheduler.reschedule(&agent_state, &state.config);
+            agents::emit_agent_refresh(state, app_handle, &agent_state);
+            Ok(true)
         }
-        state_machine::TransitionResult::NoOp => {
-            // 상태 변경 없음, emit 불필요
+        Stored::Applied { applied, task, visit } => {
+            emit_task_update(app_handle, task);
+            if visit {
+                if let Some(chat_queue::Admission::Queued { .. }) = join_chat_queue(state, event) {
+                    tracing::warn!(
+                        "ingest: {} was admitted to {:?} but another visitor got there first",
+                        event.agent_id, event.target_agent_id,
+                    );
+                }
+            }
+            if event.event_type == EventType::ThinkingUpdated {
+                record_thinking(state, event);
+            }
+            agents::publish(state, app_handle, applied, event);
+            Ok(true)
         }
     }
-
-    // Step 10: 200 응답
-    (StatusCode::OK, "ok".to_string())
 }
 
-/// 에이전트 미등록 시 자동 등록. 등록했으면 true 반환.
-fn ensure_agent_registered(
-    agents_repo: &AgentsRepo,
-    state_repo: &StateRepo,
-    event: &NormalizedEvent,
+/// 이벤트 INSERT → thinking 이력 → 작업 보드 → 에이전트 등록 → 상태 전이 (트랜잭션 안)
+fn store_event(
     state: &crate::state::AppState,
-) -> bool {
-    match agents_repo.get_by_id(&event.agent_id) {
-        Ok(Some(_)) => false,
-        Ok(None) => {
-            // 신규 에이전트 등록
-            let slot_counts = match state.slot_counts.lock() {
-                Ok(s) => s.clone(),
-                Err(e) => {
-                    tracing::warn!("ingest: slot_counts lock poisoned, using defaults: {e}");
-                    SlotCounts::default()
-                }
-            };
-            let appearance = appearance::generate_appearance(
+    tx: &mut AgentTx<'_>,
+    event: &NormalizedEvent,
+    fingerprint: &Fingerprint,
+) -> Result<Stored, AppError> {
+    // Step 3~4: 핑거프린트 중복 검사 + events 테이블에 INSERT (핑거프린트는 호출자가 마스킹 전에 계산)
+    if !EventsRepo::new(tx.conn()).insert(event, fingerprint)? {
+        return Ok(Stored::Duplicate);
+    }
+
+    // thinking 스니펫 이력 보관 (에이전트별 최근 history_limit개). 부가 정보라 실패해도 계속한다
+    if event.event_type == EventType::ThinkingUpdated {
+        if let Some(text) = &event.thinking_text {
+            if let Err(e) = ThinkingRepo::new(tx.conn()).push(
                 &event.agent_id,
-                &slot_counts,
-                &state.config.appearance,
-            );
-
-            let display_name = event.agent_id
-                .split('/')
-                .last()
-                .unwrap_or(&event.agent_id)
-                .to_string();
-
-            let agent = MascotAgent {
-                agent_id: event.agent_id.clone(),
-                display_name,
-                role: AgentRole::Worker,
-                employment_type: EmploymentType::Contractor,
-                workspace_id: event.workspace_id.clone(),
-                status: AgentStatus::Offline,
-                thinking_text: None,
-                current_task: None,
-                appearance,
-                last_active_ts: event.ts.clone(),
-            };
-
-            if let Err(e) = agents_repo.upsert(&agent) {
-                tracing::error!("ingest: agent upsert failed: {e}");
-                return false;
+                &event.ts,
+                text,
+                state.config.thinking.history_limit,
+            ) {
+                tracing::warn!("ingest: thinking history push failed: {e}");
             }
+        }
+    }
 
-            // 초기 상태 저장
-            let initial_state = AgentState {
-                agent_id: event.agent_id.clone(),
-                status: AgentStatus::Offline,
-                prev_status: None,
-                thinking_text: None,
-                current_task: None,
-                workspace_id: event.workspace_id.clone(),
-                since: event.ts.clone(),
-                last_event_ts: event.ts.clone(),
-                session_id: event.session_id.clone(),
-                peer_agent_id: None,
-                home_x: 0.0,
-            };
-
-            if let Err(e) = state_repo.upsert(&initial_state) {
-                tracing::error!("ingest: initial state upsert failed: {e}");
-            }
+    // 작업 보드 갱신 (TaskCreate/TaskUpdate)
+    let task = task_tracker::extract_change(event).and_then(|change| track_task(tx, &change));
 
-            tracing::info!("ingest: registered new agent: {}", event.agent_id);
-            true
+    // Step 5: 에이전트 미등록 시 자동 등록
+    if !tx.agent_exists(&event.agent_id)? {
+        register_agent(state, tx, event)?;
+    }
+
+    // Step 6: 현재 상태 조회 (메모리 + 이 트랜잭션에서 쓴 값)
+    let Some(mut agent_state) = tx.get_state(&event.agent_id)? else {
+        return Err(AppError::StateTransition(format!("no state for {}", event.agent_id)));
+    };
+
+    // 대화 대기열: 대상에게 다른 방문자가 가 있으면 walking 대신 줄을 선다 (state-machine.md §10.6)
+    let admission = admit_visit(state, event, &agent_state);
+    if let Some(chat_queue::Admission::Queued { .. }) = admission {
+        agent_state.last_event_ts = event.ts.clone();
+        agent_state.unresponsive = false;
+        tx.put_state(&agent_state)?;
+        return Ok(Stored::Queued { agent_state, task });
+    }
+
+    // Step 7: 상태 전이 → agent_state UPDATE → 전이 이력 기록
+    let applied = agents::transition(state, tx, agent_state, event)?;
+    Ok(Stored::Applied { applied, task, visit: admission.is_some() })
+}
+
+/// walking으로 전이할 message_sent가 대기열에 서야 하는지 판정만 한다 (대기열은 바꾸지 않는다).
+/// 방문이 아닌 이벤트면 None. 트랜잭션이 롤백돼도 대기열에 흔적이 남지 않도록
+/// 실제 반영은 커밋 후 join_chat_queue가 한다.
+fn admit_visit(
+    state: &crate::state::AppState,
+    event: &NormalizedEvent,
+    agent_state: &AgentState,
+) -> Option<chat_queue::Admission> {
+    let target = event.target_agent_id.as_deref()?;
+    if event.event_type != EventType::MessageSent
+        || state_machine::next_status(event, agent_state, &state.config.state_machine)
+            != Some(AgentStatus::Walking)
+    {
+        return None;
+    }
+
+    match state.chat_queue.lock() {
+        Ok(queue) => Some(queue.admit(&event.agent_id, target)),
+        Err(e) => {
+            tracing::warn!("ingest: chat_queue lock poisoned, not queueing: {e}");
+            None
         }
+    }
+}
+
+/// 커밋된 방문을 대화 대기열에 반영한다. 판정 이후 대기열이 바뀌었을 수 있으므로 실제 결과를 반환한다
+fn join_chat_queue(
+    state: &crate::state::AppState,
+    event: &NormalizedEvent,
+) -> Option<chat_queue::Admission> {
+    let visit = queued_visit(event);
+    match state.chat_queue.lock() {
+        Ok(mut queue) => {
+            Some(queue.request(&visit.visitor, &visit.target, visit.payload, visit.enqueued_ms))
+        }
+        Err(e) => {
+            tracing::warn!("ingest: chat_queue lock poisoned, not queueing: {e}");
+            None
+        }
+    }
+}
+
+fn queued_visit(event: &NormalizedEvent) -> chat_queue::QueuedVisit {
+    chat_queue::QueuedVisit {
+        visitor: event.agent_id.clone(),
+        target: event.target_agent_id.clone().unwrap_or_default(),
+        payload: event.payload.clone(),
+        enqueued_ms: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+/// 작업 변경분을 tasks 테이블에 병합한다. 병합된 작업은 커밋 후 mascot://task-update로 알린다.
+/// 작업 보드는 부가 정보이므로 실패해도 ingest는 계속 진행한다.
+fn track_task(tx: &AgentTx<'_>, change: &crate::models::task::TaskChange) -> Option<TaskRecord> {
+    let tasks_repo = TasksRepo::new(tx.conn());
+    let existing = match tasks_repo.get(&change.workspace_id, &change.task_id) {
+        Ok(existing) => existing,
         Err(e) => {
-            tracing::error!("ingest: agent lookup failed: {e}");
-            false
+            tracing::warn!("ingest: task lookup failed: {e}");
+            return None;
         }
+    };
+
+    let record = task_tracker::merge(existing, change);
+    if let Err(e) = tasks_repo.upsert(&record) {
+        tracing::warn!("ingest: task upsert failed: {e}");
+        return None;
     }
+    Some(record)
 }
 
-/// chatting 상태에서 대화 메시지 추출
-fn extract_chat_message(event: &NormalizedEvent) -> Option<String> {
-    event.payload
-        .get("message")
-        .and_then(|v| v.as_str())
-        .map(String::from)
+fn emit_task_update(app_handle: &tauri::AppHandle, task: Option<TaskRecord>) {
+    let Some(record) = task else {
+        return;
+    };
+    if let Err(e) = app_handle.emit("mascot://task-update", &record) {
+        tracing::error!("ingest: emit task-update failed: {e}");
+    }
+}
+
+/// 신규 에이전트와 초기 상태(offline)를 등록한다. 등장 emit은 이어지는 offline → appearing 전이에서 한다.
+fn register_agent(
+    state: &crate::state::AppState,
+    tx: &mut AgentTx<'_>,
+    event: &NormalizedEvent,
+) -> Result<(), AppError> {
+    let slot_counts = match state.slot_counts.lock() {
+        Ok(s) => s.clone(),
+        Err(e) => {
+            tracing::warn!("ingest: slot_counts lock poisoned, using defaults: {e}");
+            SlotCounts::default()
+        }
+    };
+    let appearance = appearance::generate_appearance(
+        &event.agent_id,
+        &slot_counts,
+        &state.config.appearance,
+    );
+
+    let display_name = event.agent_id
+        .split('/')
+        .last()
+        .unwrap_or(&event.agent_id)
+        .to_string();
+
+    let agent = MascotAgent {
+        agent_id: event.agent_id.clone(),
+        display_name,
+        role: AgentRole::Worker,
+        employment_type: EmploymentType::Contractor,
+        workspace_id: event.workspace_id.clone(),
+        status: AgentStatus::Offline,
+        thinking_text: None,
+        current_task: None,
+        activity: None,
+        activity_summary: None,
+        appearance,
+        last_active_ts: event.ts.clone(),
+    };
+
+    // 초기 상태와 함께 저장 (에이전트만 있고 상태 행이 없는 경우가 생기지 않도록 같은 트랜잭션)
+    let initial_state = AgentState {
+        agent_id: event.agent_id.clone(),
+        status: AgentStatus::Offline,
+        prev_status: None,
+        thinking_text: None,
+        current_task: None,
+        activity: None,
+        activity_summary: None,
+        consecutive_failures: 0,
+        unresponsive: false,
+        workspace_id: event.workspace_id.clone(),
+        since: event.ts.clone(),
+        last_event_ts: event.ts.clone(),
+        session_id: event.session_id.clone(),
+        peer_agent_id: None,
+        home_x: 0.0,
+    };
+
+    tx.register(&agent, &initial_state)?;
+    tracing::info!("ingest: registered new agent: {}", event.agent_id);
+    Ok(())
 }
@@ -1,2 +1,4 @@
+pub mod api;
 pub mod ingest;
+pub mod otlp;
 pub mod server;
@@ -0,0 +1,49 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use crate::http::ingest::ingest_event;
+use crate::http::server::IngestState;
+use crate::services::otlp;
+use std::collections::HashSet;
+
+/// POST /v1/traces -- OTLP/HTTP JSON 수신 (hooks-integration.md SS5.5)
+/// span에서 변환한 이벤트를 /ingest와 같은 파이프라인으로 처리한다.
+pub async fn traces_handler(
+    State(ingest): State<IngestState>,
+    Json(request): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let events = match otlp::convert(&request) {
+        Ok(events) => events,
+        Err(e) => {
+            tracing::warn!("otlp: invalid trace request: {e}");
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "code": 3, "message": e.to_string() })),
+            );
+        }
+    };
+
+    // 처리에 실패한 span은 ExportTraceServiceResponse.partialSuccess로 알린다
+    let mut rejected_spans = HashSet::new();
+    let mut last_error = None;
+    for event in events {
+        let span_id = event.raw.get("spanId").and_then(|v| v.as_str()).map(String::from);
+        let (status, message) = ingest_event(&ingest, event);
+        if !status.is_success() {
+            rejected_spans.extend(span_id);
+            last_error = Some(message);
+        }
+    }
+
+    let response = match last_error {
+        Some(error_message) => serde_json::json!({
+            "partialSuccess": {
+                "rejectedSpans": rejected_spans.len(),
+                "errorMessage": error_message,
+            }
+        }),
+        None => serde_json::json!({}),
+    };
+    (StatusCode::OK, Json(response))
+}
@@ -1,6 +1,11 @@
 use crate::config::ServerConfig;
 use crate::error::AppError;
-use crate::http::ingest::ingest_handler;
+use crate::http::api::{
+    db_stats_handler, schema_errors_handler, state_diagram_handler, tasks_handler,
+    time_in_state_handler,
+};
+use crate::http::ingest::{ingest_handler, ingest_source_handler};
+use crate::http::otlp::traces_handler;
 use crate::state::AppState;
 use axum::{
     routing::{get, post},
@@ -23,6 +28,13 @@ pub fn create_router(state: IngestState) -> Router {
     Router::new()
         .route("/health", get(health_handler))
         .route("/ingest", post(ingest_handler))
+        .route("/ingest/{source}", post(ingest_source_handler))
+        .route("/v1/traces", post(traces_handler))
+        .route("/api/tasks", get(tasks_handler))
+        .route("/api/schema-errors", get(schema_errors_handler))
+        .route("/api/db-stats", get(db_stats_handler))
+        .route("/api/time-in-state", get(time_in_state_handler))
+        .route("/api/state-diagram", get(state_diagram_handler))
         .with_state(state)
 }
 
@@ -13,6 +13,12 @@ pub use error::{AppError, ConfigError};
 pub use state::AppState;
 
 use models::agent::SlotCounts;
+use services::chat_queue::ChatQueue;
+use services::failure::FailureClassifier;
+use services::redaction::Redactor;
+use services::scheduler::{Scheduler, SystemClock};
+use services::thinking::ThinkingThrottle;
+use storage::agent_store::AgentStore;
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 use tauri::Manager;
@@ -61,21 +67,48 @@ pub fn run() {
             let db_path = db_dir.join("mascot.db");
             let db = storage::db::init_db(&db_path)
                 .map_err(|e| format!("DB init failed: {e}"))?;
-
-            // 3. AppState 생성 + managed state 등록
+            let agents = AgentStore::load(db.clone())
+                .map_err(|e| format!("agent store load failed: {e}"))?;
+            let legacy_fingerprint_until = storage::events_repo::EventsRepo::new(db.clone())
+                .legacy_fingerprint_until()
+                .map_err(|e| format!("legacy fingerprint lookup failed: {e}"))?;
+
+            // 3. redaction 규칙 컴파일 (이벤트 저장 전 비밀값 마스킹)
+            let redactor = Redactor::from_config(&config.redaction)
+                .map_err(|e| format!("redaction init failed: {e}"))?;
+            let failure_classifier = FailureClassifier::from_config(&config.state_machine)
+                .map_err(|e| format!("failure classifier init failed: {e}"))?;
+
+            // 4. AppState 생성 + managed state 등록
             let cursor_polling_active = Arc::new(AtomicBool::new(false));
             let hit_zones = Arc::new(Mutex::new(Vec::new()));
 
             let app_state = AppState {
                 db: db.clone(),
+                agents: Arc::new(agents),
                 config: Arc::new(config.clone()),
                 slot_counts: Arc::new(Mutex::new(SlotCounts::default())),
                 cursor_polling_active: cursor_polling_active.clone(),
                 hit_zones: hit_zones.clone(),
+                thinking_throttle: Arc::new(Mutex::new(ThinkingThrottle::default())),
+                redactor: Arc::new(redactor),
+                failure_classifier: Arc::new(failure_classifier),
+                chat_queue: Arc::new(Mutex::new(ChatQueue::default())),
+                scheduler: Arc::new(Scheduler::new(Arc::new(SystemClock))),
+                legacy_fingerprint_until: legacy_fingerprint_until.map(Arc::from),
             };
             app.manage(app_state.clone());
 
-            // 4. HTTP 서버 시작 (AppState + AppHandle 전달)
+            // 재시작 전에 WebView 콜백을 기다리던 일시 상태 보정 (state-machine.md §5.2)
+            match commands::agents::reconcile_states(&app_state, app.handle(), "startup") {
+                Ok(repairs) if !repairs.is_empty() => {
+                    tracing::info!("startup reconciliation repaired {} agent(s)", repairs.len());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("startup reconciliation failed: {e}"),
+            }
+
+            // 5. HTTP 서버 시작 (AppState + AppHandle 전달)
             let server_config = config.server.clone();
             let server_state = app_state.clone();
             let server_handle = app.handle().clone();
@@ -91,14 +124,20 @@ pub fn run() {
                 }
             });
 
-            // 5. Heartbeat 서비스 시작
+            // 6. Heartbeat 서비스 시작
             let heartbeat_state = app_state.clone();
             let heartbeat_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 services::heartbeat::run_heartbeat(heartbeat_state, heartbeat_handle).await;
             });
 
-            // 6. 커서 폴링 서비스 시작
+            // 7. 이벤트 보존 정리 서비스 시작
+            let retention_state = app_state.clone();
+            tauri::async_runtime::spawn(async move {
+                services::retention::run_retention(retention_state).await;
+            });
+
+            // 8. 커서 폴링 서비스 시작
             let poll_handle = app.handle().clone();
             let poll_active = cursor_polling_active.clone();
             let poll_zones = hit_zones.clone();
@@ -115,7 +154,7 @@ pub fn run() {
                 .await;
             });
 
-            // 7. 창 설정 — 전체 화면 크기 + 클릭 통과
+            // 9. 창 설정 — 전체 화면 크기 + 클릭 통과
             if let Some(window) = app.get_webview_window("main") {
                 // 모니터 크기에 맞춰 창 위치/크기 설정 (fullscreen 대신)
                 if let Ok(monitor) = window.current_monitor() {
@@ -137,7 +176,7 @@ pub fn run() {
                 }
             }
 
-            // 8. 시스템 트레이
+            // 10. 시스템 트레이
             tray::setup_tray(app).map_err(|e| e.to_string())?;
 
             Ok(())
@@ -151,6 +190,18 @@ pub fn run() {
             commands::agents::notify_chat_done,
             commands::agents::get_display_config,
             commands::agents::notify_drag_drop,
+            commands::agents::get_time_in_state,
+            commands::agents::export_state_diagram,
+            commands::agents::repair_states,
+            commands::tasks::get_tasks,
+            commands::events::get_schema_error_counts,
+            commands::maintenance::get_db_stats,
+            commands::maintenance::compact_db,
+            commands::workspaces::list_workspaces,
+            commands::workspaces::merge_workspaces,
+            commands::workspaces::rename_workspace,
+            commands::workspaces::set_workspace_alias,
+            commands::workspaces::remove_workspace_alias,
             commands::window::toggle_click_through,
             commands::window::get_cursor_pos,
             commands::window::set_cursor_polling,
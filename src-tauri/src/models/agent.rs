@@ -38,6 +38,39 @@ pub enum AgentStatus {
     Disappearing,
 }
 
+impl AgentStatus {
+    pub const ALL: [AgentStatus; 14] = [
+        AgentStatus::Offline,
+        AgentStatus::Appearing,
+        AgentStatus::Idle,
+        AgentStatus::Working,
+        AgentStatus::Thinking,
+        AgentStatus::PendingInput,
+        AgentStatus::Failed,
+        AgentStatus::Completed,
+        AgentStatus::Resting,
+        AgentStatus::Startled,
+        AgentStatus::Walking,
+        AgentStatus::Chatting,
+        AgentStatus::Returning,
+        AgentStatus::Disappearing,
+    ];
+}
+
+/// 도구 분류 (hooks-integration.md §6.4). WebView가 애니메이션/아이콘을 고르는 데 쓴다
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCategory {
+    Reading,
+    Editing,
+    Searching,
+    Running,
+    Web,
+    Delegating,
+    Planning,
+    Other,
+}
+
 /// 에이전트 상태 (state-machine.md §3)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentState {
@@ -46,6 +79,12 @@ pub struct AgentState {
     pub prev_status: Option<AgentStatus>,  // walking/returning 전의 상태 (복귀 시 복원)
     pub thinking_text: Option<String>,
     pub current_task: Option<String>,
+    pub activity: Option<ToolCategory>,
+    pub activity_summary: Option<String>,
+    /// 연속 실패 횟수 (tool_failed/task_failed에서 증가, tool_succeeded/task_completed에서 0)
+    pub consecutive_failures: u32,
+    /// 세션 이벤트가 liveness.unresponsive_after_secs 이상 끊겨 응답 없음으로 표시됨 (state-machine.md §5.1)
+    pub unresponsive: bool,
     pub workspace_id: String,
     pub since: String,
     pub last_event_ts: String,
@@ -89,6 +128,8 @@ pub struct MascotAgent {
     pub status: AgentStatus,
     pub thinking_text: Option<String>,
     pub current_task: Option<String>,
+    pub activity: Option<ToolCategory>,
+    pub activity_summary: Option<String>,
     pub appearance: AppearanceProfile,
     pub last_active_ts: String,
 }
@@ -35,13 +35,25 @@ pub enum EventType {
     ArriveAtPeer,
     ArriveAtHome,
     MessageDone,
+    // heartbeat (synthetic)
+    AgentUnresponsive,
+    TimerElapsed,
+    // 상태 보정 (synthetic)
+    StateRepaired,
 }
 
 /// 이벤트 소스
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum EventSource {
+    /// Claude Code hook
     Hook,
+    /// Codex CLI notify
+    Codex,
+    /// 범용 simple event (`agent`, `status`, `message`)
+    Simple,
+    /// OpenTelemetry span (OTLP/HTTP JSON)
+    Otlp,
     Synthetic,
 }
 
@@ -55,6 +67,18 @@ pub enum Severity {
     Error,
 }
 
+/// 도구 실패 분류 (state-machine.md §4.3)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureClass {
+    /// 복구 불가 → failed
+    Fatal,
+    /// 자동 재시도 예상 → working 유지 ("retrying" 표시)
+    Retryable,
+    /// 판정 불가 → pending_input
+    Unknown,
+}
+
 /// 이력서용 이벤트 요약 (ipc-protocol.md §3.1)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResumeEvent {
@@ -63,6 +87,42 @@ pub struct ResumeEvent {
     pub ts: String,
 }
 
+/// 워크스페이스별 schema_error 이벤트 수
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaErrorCount {
+    pub workspace_id: String,
+    pub count: u64,
+}
+
+/// 에이전트별 thinking 스니펫 이력 항목
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThinkingSnippet {
+    pub text: String,
+    pub ts: String,
+}
+
+/// 저장 전 redaction pass가 마스킹/절삭한 필드 기록
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Redaction {
+    /// "raw" | "payload" | "thinking_text"
+    pub field: String,
+    /// field 내부 JSON Pointer (thinking_text는 "")
+    pub path: String,
+    /// "key:{키 규칙}" | "pattern:{패턴 이름}" | "max_length"
+    pub rule: String,
+}
+
+/// 이벤트 중복 제거용 핑거프린트 (hooks-integration.md §7.6)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint {
+    /// 현재 스킴으로 계산한 값 (SHA-256 hex)
+    pub value: String,
+    /// 핑거프린트 스킴 버전 (events.fingerprint_version에 저장)
+    pub version: u32,
+    /// 업그레이드 이전(v1) 행과 중복 비교할 레거시 값
+    pub legacy_value: Option<String>,
+}
+
 /// 정규화된 이벤트 (hooks-integration.md §7.1)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NormalizedEvent {
@@ -82,4 +142,7 @@ pub struct NormalizedEvent {
     pub payload: serde_json::Value,
     pub thinking_text: Option<String>,
     pub raw: serde_json::Value,
+    /// 저장 전 redaction 내역 (없으면 직렬화 생략)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<Redaction>,
 }
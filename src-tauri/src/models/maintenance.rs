@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// DB 크기와 테이블별 행 수 (ipc-protocol.md §3.1 get_db_stats)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DbStats {
+    /// 메인 DB 파일 크기 (page_count × page_size). WAL 파일은 제외
+    pub size_bytes: u64,
+    /// 비어 있어 VACUUM으로 반환할 수 있는 크기
+    pub free_bytes: u64,
+    pub events: u64,
+    /// raw_json이 아직 남아 있는 이벤트 수
+    pub events_with_raw: u64,
+    pub transitions: u64,
+    pub thinking_history: u64,
+    pub tasks: u64,
+    pub oldest_event_ts: Option<String>,
+}
+
+/// 보존 정책으로 정리한 행 수
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PruneReport {
+    /// max_age_days를 넘겨 삭제한 이벤트
+    pub expired: u64,
+    /// max_events를 넘겨 삭제한 이벤트
+    pub over_limit: u64,
+    /// raw_json만 지운 이벤트
+    pub raw_stripped: u64,
+}
+
+/// `compact_db` 결과
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompactReport {
+    pub pruned: PruneReport,
+    pub before: DbStats,
+    pub after: DbStats,
+}
@@ -1,2 +1,6 @@
 pub mod agent;
 pub mod event;
+pub mod maintenance;
+pub mod task;
+pub mod workspace;
+pub mod transition;
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// 작업 상태 (TaskUpdate tool_input.status)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Pending,
+    InProgress,
+    Completed,
+    Failed,
+    Deleted,
+}
+
+impl TaskStatus {
+    /// hook tool_input의 status 문자열 파싱. 알 수 없는 값이면 None.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(Self::Pending),
+            "in_progress" => Some(Self::InProgress),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            "deleted" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+}
+
+/// 작업 상태 변경 이력 항목
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatusChange {
+    pub status: TaskStatus,
+    pub agent_id: String,
+    pub ts: String,
+}
+
+/// 작업 보드 항목. (workspace_id, task_id)로 식별한다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub workspace_id: String,
+    pub task_id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub owner_agent_id: Option<String>,
+    pub status: TaskStatus,
+    pub status_history: Vec<TaskStatusChange>,
+    pub created_ts: Option<String>,
+    pub started_ts: Option<String>,
+    pub completed_ts: Option<String>,
+    pub updated_ts: String,
+}
+
+/// TaskCreate/TaskUpdate hook에서 추출한 작업 변경분
+#[derive(Debug, Clone)]
+pub struct TaskChange {
+    pub workspace_id: String,
+    pub task_id: String,
+    pub agent_id: String,
+    pub ts: String,
+    pub is_create: bool,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub owner_agent_id: Option<String>,
+    pub status: Option<TaskStatus>,
+}
@@ -0,0 +1,200 @@
+use crate::error::AppError;
+use crate::models::agent::{AgentState, AgentStatus};
+use crate::models::event::EventType;
+use serde::de::{DeserializeOwned, Deserializer, Error as _};
+use serde::{Deserialize, Serialize};
+
+/// 전이 테이블 항목 하나 (state-machine.md §4.1, config `[state_machine] transitions`)
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct TransitionRule {
+    pub from: Pattern<AgentStatus>,
+    pub event: Pattern<EventType>,
+    pub to: TransitionTarget,
+    #[serde(default)]
+    pub guard: Option<TransitionGuard>,
+}
+
+/// 타이머 전이 규칙 하나 (state-machine.md §5, config `[state_machine] timers`).
+/// 상태에 after_secs 이상 머물면 heartbeat가 timer_elapsed 이벤트로 전이시킨다.
+/// 적용한 규칙은 timer_elapsed payload의 `timer`로 기록된다.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimerRule {
+    pub status: AgentStatus,
+    pub after_secs: u64,
+    pub to_status: TransitionTarget,
+    #[serde(default)]
+    pub guard: Option<TransitionGuard>,
+}
+
+/// 특정 값 또는 `"*"` (모든 값)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern<T> {
+    Any,
+    Is(T),
+}
+
+impl<T: PartialEq> Pattern<T> {
+    pub fn matches(&self, value: &T) -> bool {
+        match self {
+            Pattern::Any => true,
+            Pattern::Is(expected) => expected == value,
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Pattern<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s == "*" {
+            return Ok(Pattern::Any);
+        }
+        serde_json::from_value(serde_json::Value::String(s))
+            .map(Pattern::Is)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// 전이 대상. `"previous"`는 walking 전에 저장한 prev_status로 복귀 (없으면 idle)
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransitionTarget {
+    Status(AgentStatus),
+    Previous,
+}
+
+impl<'de> Deserialize<'de> for TransitionTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s == "previous" {
+            return Ok(TransitionTarget::Previous);
+        }
+        serde_json::from_value(serde_json::Value::String(s))
+            .map(TransitionTarget::Status)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for TransitionTarget {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            TransitionTarget::Status(status) => status.serialize(serializer),
+            TransitionTarget::Previous => serializer.serialize_str("previous"),
+        }
+    }
+}
+
+/// 전이 조건. 만족하지 않으면 다음 항목으로 넘어간다
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionGuard {
+    /// 현재 상태가 offline이 아님
+    Online,
+    /// current_task가 있음
+    HasTask,
+    /// current_task가 없음
+    NoTask,
+    /// thinking_text가 있는 이벤트
+    HasThinking,
+    /// tool_failed 분류가 fatal이거나 연속 실패 한도 도달
+    FailureFatal,
+    /// tool_failed 분류가 retryable이고 연속 실패 한도 미만
+    FailureRetryable,
+    /// tool_failed 분류가 unknown이고 연속 실패 한도 미만
+    FailureUnknown,
+}
+
+impl TransitionGuard {
+    /// tool_failed 이벤트에서만 의미가 있는 조건
+    pub fn is_failure_guard(&self) -> bool {
+        matches!(
+            self,
+            TransitionGuard::FailureFatal
+                | TransitionGuard::FailureRetryable
+                | TransitionGuard::FailureUnknown
+        )
+    }
+}
+
+/// agent_transitions 테이블 행. 상태가 바뀔 때마다 하나씩 기록한다
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransitionRecord {
+    pub agent_id: String,
+    pub workspace_id: String,
+    pub from_status: AgentStatus,
+    pub to_status: AgentStatus,
+    /// 전이를 일으킨 이벤트 id. 타이머 전이는 None
+    pub event_id: Option<String>,
+    /// from 상태에 들어간 시각
+    pub started_ts: String,
+    pub ts: String,
+    /// from 상태에 머문 시간 (ms)
+    pub duration_ms: i64,
+}
+
+impl TransitionRecord {
+    /// 전이 직후 상태와 이전 since로 기록을 만든다. 시각 파싱에 실패하면 duration은 0.
+    pub fn new(
+        state: &AgentState,
+        prev_status: &AgentStatus,
+        prev_since: &str,
+        event_id: Option<&str>,
+    ) -> Self {
+        let duration_ms = match (parse_ts_ms(prev_since), parse_ts_ms(&state.since)) {
+            (Some(start), Some(end)) => (end - start).max(0),
+            _ => 0,
+        };
+        Self {
+            agent_id: state.agent_id.clone(),
+            workspace_id: state.workspace_id.clone(),
+            from_status: prev_status.clone(),
+            to_status: state.status.clone(),
+            event_id: event_id.map(String::from),
+            started_ts: prev_since.to_string(),
+            ts: state.since.clone(),
+            duration_ms,
+        }
+    }
+}
+
+/// 에이전트/상태별 머문 시간 합계 (ipc-protocol.md §3.1 get_time_in_state)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeInState {
+    pub agent_id: String,
+    pub workspace_id: String,
+    pub status: AgentStatus,
+    pub total_secs: f64,
+    /// 구간과 겹친 체류 횟수 (현재 진행 중인 상태 포함)
+    pub intervals: u64,
+}
+
+/// 머문 시간 조회 조건. from/to는 RFC3339, to 생략 시 현재 시각
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimeInStateQuery {
+    pub agent_id: Option<String>,
+    pub workspace_id: Option<String>,
+    pub from: String,
+    pub to: Option<String>,
+}
+
+impl TimeInStateQuery {
+    /// (from_ms, to_ms). 파싱 실패나 from >= to면 InvalidArgument
+    pub fn range_ms(&self, now_ms: i64) -> Result<(i64, i64), AppError> {
+        let from = parse_ts_ms(&self.from)
+            .ok_or_else(|| AppError::InvalidArgument(format!("invalid from: {:?}", self.from)))?;
+        let to = match &self.to {
+            Some(to) => parse_ts_ms(to)
+                .ok_or_else(|| AppError::InvalidArgument(format!("invalid to: {to:?}")))?,
+            None => now_ms,
+        };
+        if from >= to {
+            return Err(AppError::InvalidArgument("from must be before to".into()));
+        }
+        Ok((from, to))
+    }
+}
+
+/// RFC3339 → epoch ms
+pub fn parse_ts_ms(ts: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|dt| dt.timestamp_millis())
+}
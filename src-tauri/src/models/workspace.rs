@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// 워크스페이스 목록 항목 (ipc-protocol.md §3.1 list_workspaces)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkspaceSummary {
+    pub workspace_id: String,
+    pub agent_count: u64,
+    pub event_count: u64,
+    pub last_event_ts: Option<String>,
+    /// 이 워크스페이스로 매핑되는 별칭
+    pub aliases: Vec<String>,
+}
+
+/// 워크스페이스 병합/이름 변경 결과
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkspaceMerge {
+    pub from: String,
+    pub into: String,
+    pub events_moved: u64,
+    pub agents_moved: u64,
+    pub tasks_moved: u64,
+}
@@ -0,0 +1,222 @@
+use crate::config::ActivityConfig;
+use crate::models::agent::ToolCategory;
+use crate::models::event::NormalizedEvent;
+use crate::services::normalizer_rules::glob_match;
+use crate::services::thinking::truncate_chars;
+
+/// 도구 이름 → 분류 (hooks-integration.md §6.4).
+/// 정확한 이름 매치가 glob 매치보다 우선하고, glob끼리는 분류 순서대로 평가한다.
+pub fn classify(tool_name: &str, config: &ActivityConfig) -> ToolCategory {
+    let is_glob = |pattern: &str| pattern.contains(['*', '?']);
+
+    let exact = config.categories.iter().find(|(_, patterns)| {
+        patterns.iter().any(|p| !is_glob(p) && p == tool_name)
+    });
+    let glob = || {
+        config.categories.iter().find(|(_, patterns)| {
+            patterns.iter().any(|p| is_glob(p) && glob_match(p, tool_name))
+        })
+    };
+
+    exact
+        .or_else(glob)
+        .map(|(category, _)| category.clone())
+        .unwrap_or(ToolCategory::Other)
+}
+
+/// 사람이 읽는 짧은 활동 요약 (예: "Editing lib.rs", "Running cargo test")
+pub fn summarize(category: &ToolCategory, tool_name: &str, tool_input: &serde_json::Value) -> String {
+    let input_str = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| tool_input.get(*key).and_then(|v| v.as_str()))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    let file_name = || {
+        input_str(&["file_path", "notebook_path", "path"]).map(|path| {
+            path.trim_end_matches(['/', '\\'])
+                .rsplit(['/', '\\'])
+                .next()
+                .unwrap_or(path)
+                .to_string()
+        })
+    };
+
+    match category {
+        ToolCategory::Reading => match file_name() {
+            Some(name) => format!("Reading {name}"),
+            None => "Reading".to_string(),
+        },
+        ToolCategory::Editing => match file_name() {
+            Some(name) => format!("Editing {name}"),
+            None => "Editing".to_string(),
+        },
+        ToolCategory::Searching => match input_str(&["pattern", "query"]) {
+            Some(pattern) => format!("Searching for {pattern}"),
+            None => "Searching".to_string(),
+        },
+        ToolCategory::Running => match input_str(&["command"]) {
+            Some(command) => format!("Running {}", command.lines().next().unwrap_or(command)),
+            None => "Running a command".to_string(),
+        },
+        ToolCategory::Web => {
+            if let Some(url) = input_str(&["url"]) {
+                let host = url
+                    .split("://")
+                    .nth(1)
+                    .unwrap_or(url)
+                    .split(['/', '?', '#'])
+                    .next()
+                    .unwrap_or(url);
+                format!("Browsing {host}")
+            } else if let Some(query) = input_str(&["query"]) {
+                format!("Searching the web for {query}")
+            } else {
+                "Browsing the web".to_string()
+            }
+        }
+        ToolCategory::Delegating => match input_str(&["description", "subagent_type", "recipient"]) {
+            Some(target) => format!("Delegating: {target}"),
+            None => "Delegating".to_string(),
+        },
+        ToolCategory::Planning => match input_str(&["subject", "title"]) {
+            Some(subject) => format!("Planning: {subject}"),
+            None => "Planning".to_string(),
+        },
+        ToolCategory::Other => {
+            // mcp__{server}__{tool}
+            let mut parts = tool_name.strip_prefix("mcp__").map(|rest| rest.splitn(2, "__"));
+            match parts.as_mut().and_then(|p| Some((p.next()?, p.next()?))) {
+                Some((server, tool)) => format!("Using {server}: {tool}"),
+                None => format!("Using {tool_name}"),
+            }
+        }
+    }
+}
+
+/// 도구 이벤트 payload에 `activity`와 `activity_summary`를 채운다.
+/// 상태 머신이 current_task와 함께 agent_state로 옮긴다.
+pub fn annotate(event: &mut NormalizedEvent, config: &ActivityConfig) {
+    let Some(tool_name) = event
+        .payload
+        .get("tool_name")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+    else {
+        return;
+    };
+
+    // PostToolUse payload에는 tool_input이 없으므로 raw에서 찾는다.
+    // OTLP span은 인자를 JSON 문자열로 싣는다.
+    let tool_input = match event.payload.get("tool_input").or_else(|| event.raw.get("tool_input")) {
+        Some(serde_json::Value::String(s)) => serde_json::from_str(s).unwrap_or_default(),
+        Some(value) => value.clone(),
+        None => serde_json::Value::Null,
+    };
+
+    let category = classify(&tool_name, config);
+    let summary = truncate_chars(
+        &summarize(&category, &tool_name, &tool_input),
+        config.summary_max_chars,
+    );
+    event.payload["activity"] = serde_json::json!(category);
+    event.payload["activity_summary"] = serde_json::json!(summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedactionConfig;
+    use crate::services::redaction::Redactor;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn test_config() -> ActivityConfig {
+        ActivityConfig {
+            summary_max_chars: 40,
+            categories: BTreeMap::from([
+                (ToolCategory::Reading, vec!["Read".into(), "mcp__*__get_*".into()]),
+                (ToolCategory::Editing, vec!["Edit".into(), "Write".into()]),
+                (ToolCategory::Running, vec!["Bash".into()]),
+                (ToolCategory::Web, vec!["WebFetch".into(), "mcp__github__*".into()]),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let config = test_config();
+        assert_eq!(classify("Edit", &config), ToolCategory::Editing);
+        assert_eq!(classify("Bash", &config), ToolCategory::Running);
+        assert_eq!(classify("mcp__github__create_pr", &config), ToolCategory::Web);
+        // 두 glob에 모두 걸리면 분류 순서(reading → ... → web)가 앞선 쪽
+        assert_eq!(classify("mcp__github__get_issue", &config), ToolCategory::Reading);
+        assert_eq!(classify("SomethingNew", &config), ToolCategory::Other);
+    }
+
+    #[test]
+    fn test_summarize() {
+        assert_eq!(
+            summarize(&ToolCategory::Editing, "Edit", &json!({"file_path": "/repo/src/lib.rs"})),
+            "Editing lib.rs"
+        );
+        assert_eq!(
+            summarize(&ToolCategory::Running, "Bash", &json!({"command": "cargo test\necho done"})),
+            "Running cargo test"
+        );
+        assert_eq!(
+            summarize(&ToolCategory::Web, "WebFetch", &json!({"url": "https://docs.rs/serde/latest"})),
+            "Browsing docs.rs"
+        );
+        assert_eq!(
+            summarize(&ToolCategory::Other, "mcp__jira__create_issue", &json!({})),
+            "Using jira: create_issue"
+        );
+        assert_eq!(summarize(&ToolCategory::Reading, "Read", &json!({})), "Reading");
+    }
+
+    #[test]
+    fn test_annotate_uses_raw_tool_input_and_truncates() {
+        let raw = json!({
+            "hook_type": "PostToolUse",
+            "session_id": "sess-1",
+            "tool_name": "Bash",
+            "tool_input": {"command": "cargo test --workspace --all-features -- --nocapture"},
+            "_meta": {"workspace_id": "ws", "terminal_session_id": "t", "collected_at": "2026-02-20T15:00:00Z"}
+        });
+        let mut event = crate::services::normalizer::normalize(&raw).expect("should normalize");
+        annotate(&mut event, &test_config());
+        assert_eq!(event.payload["activity"], "running");
+        let summary = event.payload["activity_summary"].as_str().expect("summary");
+        assert!(summary.starts_with("Running cargo test"));
+        assert_eq!(summary.chars().count(), 40);
+    }
+
+    #[test]
+    fn test_annotate_after_redaction_hides_cut_secret() {
+        let raw = json!({
+            "hook_type": "PreToolUse",
+            "session_id": "sess-1",
+            "tool_name": "Bash",
+            "tool_input": {"command": "curl -H x-api-key:sk-ant-REDACTED https://api"},
+            "_meta": {"workspace_id": "ws", "terminal_session_id": "t", "collected_at": "2026-02-20T15:00:00Z"}
+        });
+        let redactor = Redactor::from_config(&RedactionConfig {
+            enabled: true,
+            max_string_chars: 200,
+            keys: Vec::new(),
+            use_builtin_patterns: true,
+            patterns: Vec::new(),
+        })
+        .expect("compile");
+        let mut event = crate::services::normalizer::normalize(&raw).expect("should normalize");
+        // 40자에서 자르면 키 앞부분(14자)만 남아 기본 패턴에 걸리지 않는다. ingest처럼 마스킹 후에 채운다
+        redactor.redact_event(&mut event);
+        annotate(&mut event, &test_config());
+
+        let summary = event.payload["activity_summary"].as_str().expect("summary");
+        assert!(summary.starts_with("Running curl -H x-api-key:[REDACTED]"), "summary={summary}");
+        assert!(!summary.contains("sk-ant"), "summary={summary}");
+    }
+}
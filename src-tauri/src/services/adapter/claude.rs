@@ -0,0 +1,26 @@
+use super::SourceAdapter;
+use crate::config::NormalizerConfig;
+use crate::error::AppError;
+use crate::models::event::NormalizedEvent;
+use crate::services::normalizer;
+
+/// Claude Code hook payload (hooks-integration.md §6~§8)
+pub struct ClaudeAdapter;
+
+impl SourceAdapter for ClaudeAdapter {
+    fn name(&self) -> &'static str {
+        "claude"
+    }
+
+    fn normalize(
+        &self,
+        raw: &serde_json::Value,
+        config: &NormalizerConfig,
+    ) -> Result<NormalizedEvent, AppError> {
+        normalizer::normalize_with_rules(raw, &config.rules)
+    }
+
+    fn schema_error(&self, raw: &serde_json::Value, violations: Vec<String>) -> NormalizedEvent {
+        normalizer::schema_error_event(raw, violations)
+    }
+}
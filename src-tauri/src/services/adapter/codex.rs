@@ -0,0 +1,169 @@
+use super::{meta_str, SourceAdapter};
+use crate::config::NormalizerConfig;
+use crate::error::AppError;
+use crate::models::event::*;
+use crate::services::normalizer;
+
+/// Codex CLI `notify` payload (hooks-integration.md §5.4.1)
+/// `{ "type": "agent-turn-complete", "thread-id", "turn-id", "cwd", "input-messages", "last-assistant-message" }`
+pub struct CodexAdapter;
+
+impl SourceAdapter for CodexAdapter {
+    fn name(&self) -> &'static str {
+        "codex"
+    }
+
+    fn normalize(
+        &self,
+        raw: &serde_json::Value,
+        _config: &NormalizerConfig,
+    ) -> Result<NormalizedEvent, AppError> {
+        let notify_type = str_field(raw, "type")
+            .ok_or_else(|| AppError::Normalize("missing type field".into()))?;
+
+        let (event_type, payload, task_id) = match notify_type {
+            "agent-turn-complete" => (
+                EventType::TaskCompleted,
+                serde_json::json!({
+                    "summary": raw.get("last-assistant-message").cloned().unwrap_or_default(),
+                    "input_messages": raw.get("input-messages").cloned().unwrap_or_default(),
+                }),
+                str_field(raw, "turn-id").map(String::from),
+            ),
+            "approval-requested" => (
+                EventType::AgentBlocked,
+                serde_json::json!({
+                    "message": raw.get("message").cloned().unwrap_or_default(),
+                }),
+                None,
+            ),
+            other => {
+                return Err(AppError::Normalize(format!("unsupported codex notify type: {other}")));
+            }
+        };
+
+        let mut event = base_event(raw, event_type, Severity::Info, payload);
+        event.task_id = task_id;
+        Ok(event)
+    }
+
+    fn schema_error(&self, raw: &serde_json::Value, violations: Vec<String>) -> NormalizedEvent {
+        let notify_type = raw.get("type").cloned().unwrap_or(serde_json::Value::Null);
+        base_event(
+            raw,
+            EventType::SchemaError,
+            Severity::Warn,
+            serde_json::json!({
+                "violations": violations,
+                "type": notify_type,
+            }),
+        )
+    }
+}
+
+fn str_field<'a>(raw: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    raw.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+}
+
+/// agent_id: `_meta.agent_name` → `codex/{thread-id}` → `codex`
+/// workspace_id: `_meta.workspace_id` → cwd 마지막 경로 요소 → `unknown`
+fn base_event(
+    raw: &serde_json::Value,
+    event_type: EventType,
+    severity: Severity,
+    payload: serde_json::Value,
+) -> NormalizedEvent {
+    let thread_id = str_field(raw, "thread-id");
+    let agent_id = match (meta_str(raw, "agent_name"), thread_id) {
+        (Some(name), _) => name.to_string(),
+        (None, Some(thread)) => format!("codex/{thread}"),
+        (None, None) => "codex".to_string(),
+    };
+    let workspace_id = meta_str(raw, "workspace_id")
+        .or_else(|| {
+            str_field(raw, "cwd")
+                .and_then(|cwd| cwd.trim_end_matches(['/', '\\']).rsplit(['/', '\\']).next())
+                .filter(|s| !s.is_empty())
+        })
+        .unwrap_or("unknown");
+    let ts = meta_str(raw, "collected_at")
+        .map(String::from)
+        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+
+    NormalizedEvent {
+        id: normalizer::generate_event_id(),
+        version: "1.1".to_string(),
+        ts,
+        event_type,
+        source: EventSource::Codex,
+        workspace_id: workspace_id.to_string(),
+        terminal_session_id: meta_str(raw, "terminal_session_id")
+            .unwrap_or("unknown")
+            .to_string(),
+        run_id: None,
+        session_id: thread_id.map(String::from),
+        agent_id,
+        target_agent_id: None,
+        task_id: None,
+        severity,
+        payload,
+        thinking_text: None,
+        raw: raw.clone(),
+        redactions: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::validator;
+    use serde_json::json;
+
+    fn normalize(raw: &serde_json::Value) -> Result<NormalizedEvent, AppError> {
+        CodexAdapter.normalize(raw, &NormalizerConfig::default())
+    }
+
+    #[test]
+    fn test_codex_turn_complete() {
+        let raw = json!({
+            "type": "agent-turn-complete",
+            "thread-id": "b5f6c1c2",
+            "turn-id": "12345",
+            "cwd": "/home/me/my-project",
+            "input-messages": ["Rename `foo` to `bar`"],
+            "last-assistant-message": "Rename complete."
+        });
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::TaskCompleted);
+        assert_eq!(event.source, EventSource::Codex);
+        assert_eq!(event.agent_id, "codex/b5f6c1c2");
+        assert_eq!(event.workspace_id, "my-project");
+        assert_eq!(event.task_id.as_deref(), Some("12345"));
+        assert_eq!(event.payload["summary"], "Rename complete.");
+        assert!(validator::validate(&event).is_empty());
+    }
+
+    #[test]
+    fn test_codex_meta_overrides_identity() {
+        let raw = json!({
+            "type": "approval-requested",
+            "cwd": "/home/me/other",
+            "_meta": {
+                "workspace_id": "team-ws",
+                "agent_name": "codex-reviewer",
+                "collected_at": "2026-02-20T15:00:00Z"
+            }
+        });
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::AgentBlocked);
+        assert_eq!(event.agent_id, "codex-reviewer");
+        assert_eq!(event.workspace_id, "team-ws");
+        assert_eq!(event.ts, "2026-02-20T15:00:00Z");
+    }
+
+    #[test]
+    fn test_codex_unknown_type() {
+        assert!(normalize(&json!({"type": "something-else"})).is_err());
+        assert!(normalize(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! 에이전트 CLI별 payload → NormalizedEvent 어댑터 (hooks-integration.md §5.4)
+//! `POST /ingest/{source}`의 source로 어댑터를 고른다. `POST /ingest`는 claude.
+
+pub mod claude;
+pub mod codex;
+pub mod simple;
+
+use crate::config::NormalizerConfig;
+use crate::error::AppError;
+use crate::models::event::NormalizedEvent;
+
+pub trait SourceAdapter: Send + Sync {
+    /// 라우트 이름 (`/ingest/{name}`)
+    fn name(&self) -> &'static str;
+
+    /// 원본 payload를 정규화한다. 해석할 수 없으면 AppError::Normalize.
+    /// config는 `[normalizer]` 설정 (사용자 정의 규칙 등)
+    fn normalize(
+        &self,
+        raw: &serde_json::Value,
+        config: &NormalizerConfig,
+    ) -> Result<NormalizedEvent, AppError>;
+
+    /// 정규화 자체가 불가능한 payload를 schema_error 이벤트로 만든다 (§7.5).
+    fn schema_error(&self, raw: &serde_json::Value, violations: Vec<String>) -> NormalizedEvent;
+}
+
+static ADAPTERS: &[&dyn SourceAdapter] = &[
+    &claude::ClaudeAdapter,
+    &codex::CodexAdapter,
+    &simple::SimpleAdapter,
+];
+
+/// 이름으로 어댑터 조회. 등록되지 않은 source면 None.
+pub fn find(name: &str) -> Option<&'static dyn SourceAdapter> {
+    ADAPTERS.iter().copied().find(|adapter| adapter.name() == name)
+}
+
+/// 기본 어댑터 (`POST /ingest`)
+pub fn default_adapter() -> &'static dyn SourceAdapter {
+    &claude::ClaudeAdapter
+}
+
+/// collector가 덧붙이는 `_meta` 객체의 문자열 필드
+fn meta_str<'a>(raw: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    raw.get("_meta")
+        .and_then(|meta| meta.get(key))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agent::{AgentState, AgentStatus};
+    use crate::services::state_machine::{self, TransitionResult};
+
+    #[test]
+    fn test_find_adapter_by_name() {
+        assert_eq!(find("claude").map(|a| a.name()), Some("claude"));
+        assert_eq!(find("codex").map(|a| a.name()), Some("codex"));
+        assert_eq!(find("simple").map(|a| a.name()), Some("simple"));
+        assert!(find("unknown-cli").is_none());
+        assert_eq!(default_adapter().name(), "claude");
+    }
+
+    #[test]
+    fn test_blocked_events_move_to_pending_input() {
+        // 배포되는 config.toml의 전이 테이블로 어댑터 출력이 실제 상태를 바꾸는지 확인
+        let config: crate::config::AppConfig =
+            toml::from_str(include_str!("../../../config.toml")).expect("config.toml");
+        let cases = [
+            ("codex", serde_json::json!({"type": "approval-requested", "thread-id": "t1"})),
+            ("simple", serde_json::json!({"agent": "build-bot", "status": "waiting"})),
+        ];
+        for (source, raw) in cases {
+            let adapter = find(source).expect("adapter");
+            let event = adapter.normalize(&raw, &config.normalizer).expect("should normalize");
+            let mut state = AgentState {
+                agent_id: event.agent_id.clone(),
+                status: AgentStatus::Working,
+                prev_status: None,
+                thinking_text: None,
+                current_task: None,
+                activity: None,
+                activity_summary: None,
+                consecutive_failures: 0,
+                unresponsive: false,
+                workspace_id: event.workspace_id.clone(),
+                since: "2026-02-20T15:00:00Z".into(),
+                last_event_ts: "2026-02-20T15:00:00Z".into(),
+                session_id: None,
+                peer_agent_id: None,
+                home_x: 0.5,
+            };
+            let result = state_machine::on_event(&event, &mut state, &config.state_machine);
+            assert!(matches!(result, TransitionResult::Changed { .. }), "{source}");
+            assert_eq!(state.status, AgentStatus::PendingInput, "{source}");
+        }
+    }
+}
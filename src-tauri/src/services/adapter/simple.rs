@@ -0,0 +1,179 @@
+use super::{meta_str, SourceAdapter};
+use crate::config::NormalizerConfig;
+use crate::error::AppError;
+use crate::models::event::*;
+use crate::services::normalizer;
+
+/// 어떤 스크립트든 보낼 수 있는 범용 이벤트 (hooks-integration.md §5.4.2)
+/// `{ "agent": "build-bot", "status": "working", "message": "..." }`
+pub struct SimpleAdapter;
+
+impl SourceAdapter for SimpleAdapter {
+    fn name(&self) -> &'static str {
+        "simple"
+    }
+
+    fn normalize(
+        &self,
+        raw: &serde_json::Value,
+        _config: &NormalizerConfig,
+    ) -> Result<NormalizedEvent, AppError> {
+        let agent = str_field(raw, "agent")
+            .ok_or_else(|| AppError::Normalize("missing agent field".into()))?;
+        let status = str_field(raw, "status")
+            .ok_or_else(|| AppError::Normalize("missing status field".into()))?;
+        let message = str_field(raw, "message");
+
+        let (event_type, severity) = match status {
+            "started" => (EventType::AgentStarted, Severity::Info),
+            "working" => (EventType::TaskStarted, Severity::Info),
+            "thinking" => (EventType::ThinkingUpdated, Severity::Debug),
+            "waiting" => (EventType::AgentBlocked, Severity::Info),
+            "completed" => (EventType::TaskCompleted, Severity::Info),
+            "failed" => (EventType::TaskFailed, Severity::Error),
+            "stopped" => (EventType::AgentStopped, Severity::Info),
+            "heartbeat" => (EventType::Heartbeat, Severity::Debug),
+            "info" => (EventType::Notification, Severity::Info),
+            other => {
+                return Err(AppError::Normalize(format!("unsupported status: {other}")));
+            }
+        };
+
+        let mut payload = serde_json::json!({ "status": status });
+        if let Some(message) = message {
+            payload["message"] = serde_json::json!(message);
+            if event_type == EventType::TaskFailed {
+                payload["error_message"] = serde_json::json!(message);
+            }
+        }
+
+        let mut event = base_event(raw, agent, event_type, severity, payload);
+
+        // 작업 이벤트는 task_id가 필수(§7.5)이므로 task가 없으면 에이전트당 작업 하나로 본다
+        let is_task = matches!(
+            event.event_type,
+            EventType::TaskStarted | EventType::TaskCompleted | EventType::TaskFailed
+        );
+        event.task_id = str_field(raw, "task")
+            .or(if is_task { Some(agent) } else { None })
+            .map(String::from);
+
+        if event.event_type == EventType::ThinkingUpdated {
+            event.thinking_text = message.map(String::from);
+        }
+        Ok(event)
+    }
+
+    fn schema_error(&self, raw: &serde_json::Value, violations: Vec<String>) -> NormalizedEvent {
+        let agent = str_field(raw, "agent").unwrap_or("unknown");
+        let status = raw.get("status").cloned().unwrap_or(serde_json::Value::Null);
+        base_event(
+            raw,
+            agent,
+            EventType::SchemaError,
+            Severity::Warn,
+            serde_json::json!({
+                "violations": violations,
+                "status": status,
+            }),
+        )
+    }
+}
+
+fn str_field<'a>(raw: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    raw.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+}
+
+/// 식별자 필드를 채운 이벤트 골격. workspace/ts는 본문 필드 → `_meta` → 기본값 순.
+fn base_event(
+    raw: &serde_json::Value,
+    agent: &str,
+    event_type: EventType,
+    severity: Severity,
+    payload: serde_json::Value,
+) -> NormalizedEvent {
+    let workspace_id = str_field(raw, "workspace")
+        .or_else(|| meta_str(raw, "workspace_id"))
+        .unwrap_or("unknown");
+    let ts = str_field(raw, "ts")
+        .or_else(|| meta_str(raw, "collected_at"))
+        .map(String::from)
+        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+
+    NormalizedEvent {
+        id: normalizer::generate_event_id(),
+        version: "1.1".to_string(),
+        ts,
+        event_type,
+        source: EventSource::Simple,
+        workspace_id: workspace_id.to_string(),
+        terminal_session_id: meta_str(raw, "terminal_session_id")
+            .unwrap_or("unknown")
+            .to_string(),
+        run_id: None,
+        session_id: str_field(raw, "session").map(String::from),
+        agent_id: agent.to_string(),
+        target_agent_id: None,
+        task_id: None,
+        severity,
+        payload,
+        thinking_text: None,
+        raw: raw.clone(),
+        redactions: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::validator;
+    use serde_json::json;
+
+    fn normalize(raw: &serde_json::Value) -> Result<NormalizedEvent, AppError> {
+        SimpleAdapter.normalize(raw, &NormalizerConfig::default())
+    }
+
+    #[test]
+    fn test_simple_working_event() {
+        let raw = json!({
+            "agent": "build-bot",
+            "status": "working",
+            "message": "cargo build",
+            "workspace": "my-project",
+            "ts": "2026-02-20T15:00:00Z"
+        });
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::TaskStarted);
+        assert_eq!(event.source, EventSource::Simple);
+        assert_eq!(event.agent_id, "build-bot");
+        assert_eq!(event.workspace_id, "my-project");
+        assert_eq!(event.task_id.as_deref(), Some("build-bot"));
+        assert_eq!(event.payload["message"], "cargo build");
+        assert!(validator::validate(&event).is_empty());
+    }
+
+    #[test]
+    fn test_simple_thinking_and_failed() {
+        let raw = json!({"agent": "a", "status": "thinking", "message": "hmm"});
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::ThinkingUpdated);
+        assert_eq!(event.thinking_text.as_deref(), Some("hmm"));
+
+        let raw = json!({"agent": "a", "status": "failed", "message": "boom", "task": "t-1"});
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::TaskFailed);
+        assert_eq!(event.severity, Severity::Error);
+        assert_eq!(event.task_id.as_deref(), Some("t-1"));
+        assert_eq!(event.payload["error_message"], "boom");
+    }
+
+    #[test]
+    fn test_simple_rejects_unknown_status_and_missing_agent() {
+        assert!(normalize(&json!({"agent": "a", "status": "dancing"})).is_err());
+        assert!(normalize(&json!({"status": "working"})).is_err());
+
+        let error = SimpleAdapter.schema_error(&json!({"status": "working"}), vec!["x".into()]);
+        assert_eq!(error.event_type, EventType::SchemaError);
+        assert_eq!(error.agent_id, "unknown");
+    }
+}
@@ -0,0 +1,275 @@
+use crate::models::agent::AgentStatus;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// 대기 중인 방문 하나. 차례가 오면 같은 payload로 message_sent를 다시 처리한다
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedVisit {
+    pub visitor: String,
+    pub target: String,
+    pub payload: serde_json::Value,
+    pub enqueued_ms: i64,
+}
+
+/// message_sent 처리 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum Admission {
+    /// 바로 걸어가도 됨
+    Go,
+    /// 대기열에 들어감. position은 1부터
+    Queued { position: usize },
+}
+
+#[derive(Debug, Default)]
+struct TargetQueue {
+    /// 지금 이 대상에게 가 있는 방문자 (walking/chatting/returning)
+    active: Option<String>,
+    waiting: VecDeque<QueuedVisit>,
+}
+
+/// 대상 에이전트별 대화 대기열 (state-machine.md §10.6).
+/// 한 대상에게는 한 번에 한 방문자만 걸어가고, 나머지는 도착 순서대로 기다린다.
+/// 메모리에만 두며 재시작하면 비워진다.
+#[derive(Debug, Default)]
+pub struct ChatQueue {
+    targets: HashMap<String, TargetQueue>,
+}
+
+impl ChatQueue {
+    /// walking으로 전이하려는 message_sent. 대상에게 다른 방문자가 있으면 대기열 끝에 넣는다.
+    /// 한 방문자는 한 줄에만 선다 (다른 대상에게 다시 보내면 이전 대기는 취소).
+    pub fn request(
+        &mut self,
+        visitor: &str,
+        target: &str,
+        payload: serde_json::Value,
+        now_ms: i64,
+    ) -> Admission {
+        self.remove_waiting(visitor);
+        let queue = self.targets.entry(target.to_string()).or_default();
+        match &queue.active {
+            Some(active) if active != visitor => {
+                queue.waiting.push_back(QueuedVisit {
+                    visitor: visitor.to_string(),
+                    target: target.to_string(),
+                    payload,
+                    enqueued_ms: now_ms,
+                });
+                Admission::Queued { position: queue.waiting.len() }
+            }
+            _ => {
+                queue.active = Some(visitor.to_string());
+                Admission::Go
+            }
+        }
+    }
+
+    /// request가 돌려줄 결과를 대기열을 바꾸지 않고 미리 본다.
+    /// ingest 트랜잭션 안에서 판정하고, 커밋된 뒤에 request로 반영한다.
+    pub fn admit(&self, visitor: &str, target: &str) -> Admission {
+        let Some(queue) = self.targets.get(target) else {
+            return Admission::Go;
+        };
+        match &queue.active {
+            Some(active) if active != visitor => {
+                let ahead = queue.waiting.iter().filter(|v| v.visitor != visitor).count();
+                Admission::Queued { position: ahead + 1 }
+            }
+            _ => Admission::Go,
+        }
+    }
+
+    /// 에이전트의 상태가 바뀐 뒤 호출한다.
+    /// 방문 중이던 에이전트가 walking/chatting/returning을 벗어나면 방문을 끝내고,
+    /// 만료되지 않은 다음 대기자를 active로 올려 반환한다 (호출자가 walking으로 전이시킨다).
+    /// 퇴장하는 에이전트는 대기열에서도 뺀다.
+    pub fn on_transition(
+        &mut self,
+        agent_id: &str,
+        status: &AgentStatus,
+        now_ms: i64,
+        timeout_secs: u64,
+    ) -> Option<QueuedVisit> {
+        if matches!(status, AgentStatus::Disappearing | AgentStatus::Offline) {
+            self.remove_waiting(agent_id);
+        }
+        if is_visiting(status) {
+            return None;
+        }
+
+        let target = self
+            .targets
+            .iter()
+            .find(|(_, q)| q.active.as_deref() == Some(agent_id))
+            .map(|(target, _)| target.clone())?;
+        let queue = self.targets.get_mut(&target)?;
+        queue.active = None;
+
+        let deadline_ms = timeout_secs as i64 * 1000;
+        while let Some(next) = queue.waiting.pop_front() {
+            if now_ms - next.enqueued_ms >= deadline_ms {
+                tracing::info!(
+                    "chat_queue: visit {} → {} expired in queue",
+                    next.visitor, next.target,
+                );
+                continue;
+            }
+            queue.active = Some(next.visitor.clone());
+            return Some(next);
+        }
+        self.targets.remove(&target);
+        None
+    }
+
+    /// chat_queue_timeout_secs 이상 기다린 방문을 버리고 반환한다 (heartbeat에서 호출)
+    pub fn expire(&mut self, now_ms: i64, timeout_secs: u64) -> Vec<QueuedVisit> {
+        let deadline_ms = timeout_secs as i64 * 1000;
+        let mut expired = Vec::new();
+        for queue in self.targets.values_mut() {
+            let (stale, fresh): (VecDeque<_>, VecDeque<_>) = queue
+                .waiting
+                .drain(..)
+                .partition(|v| now_ms - v.enqueued_ms >= deadline_ms);
+            queue.waiting = fresh;
+            expired.extend(stale);
+        }
+        self.targets
+            .retain(|_, q| q.active.is_some() || !q.waiting.is_empty());
+        expired
+    }
+
+    /// 가장 먼저 만료될 대기의 만료 시각 (heartbeat가 이때 깨어나 expire를 호출한다)
+    pub fn next_expiry_ms(&self, timeout_secs: u64) -> Option<i64> {
+        self.targets
+            .values()
+            .flat_map(|q| q.waiting.iter())
+            .map(|v| v.enqueued_ms + timeout_secs as i64 * 1000)
+            .min()
+    }
+
+    /// 대기 중이면 (대상, 순번). 순번은 1부터
+    pub fn position(&self, visitor: &str) -> Option<(String, usize)> {
+        self.targets.iter().find_map(|(target, queue)| {
+            queue
+                .waiting
+                .iter()
+                .position(|v| v.visitor == visitor)
+                .map(|i| (target.clone(), i + 1))
+        })
+    }
+
+    /// 대상의 대기자 목록 (앞에서부터)
+    pub fn waiting(&self, target: &str) -> Vec<String> {
+        self.targets
+            .get(target)
+            .map(|q| q.waiting.iter().map(|v| v.visitor.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    fn remove_waiting(&mut self, visitor: &str) {
+        for queue in self.targets.values_mut() {
+            queue.waiting.retain(|v| v.visitor != visitor);
+        }
+    }
+}
+
+/// 공유 대기열에서 (대상, 순번) 조회. agent-update payload의 queued_for/queue_position에 쓴다.
+/// lock이 오염되었으면 대기 중이 아닌 것으로 본다.
+pub fn lookup(queue: &Mutex<ChatQueue>, agent_id: &str) -> Option<(String, usize)> {
+    match queue.lock() {
+        Ok(queue) => queue.position(agent_id),
+        Err(e) => {
+            tracing::warn!("chat_queue: lock poisoned: {e}");
+            None
+        }
+    }
+}
+
+/// 대상에게 가 있는 동안의 상태
+fn is_visiting(status: &AgentStatus) -> bool {
+    matches!(
+        status,
+        AgentStatus::Walking | AgentStatus::Chatting | AgentStatus::Returning
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(text: &str) -> serde_json::Value {
+        serde_json::json!({ "message": text })
+    }
+
+    #[test]
+    fn test_second_visitor_waits_until_first_returns() {
+        let mut queue = ChatQueue::default();
+        assert_eq!(queue.request("a", "lead", msg("hi"), 0), Admission::Go);
+        assert_eq!(queue.request("b", "lead", msg("yo"), 100), Admission::Queued { position: 1 });
+        assert_eq!(queue.request("c", "lead", msg("hey"), 200), Admission::Queued { position: 2 });
+        assert_eq!(queue.position("c"), Some(("lead".into(), 2)));
+
+        // 방문 중에는 다음 차례가 오지 않음
+        assert!(queue.on_transition("a", &AgentStatus::Chatting, 1_000, 10).is_none());
+        assert!(queue.on_transition("a", &AgentStatus::Returning, 2_000, 10).is_none());
+
+        let next = queue.on_transition("a", &AgentStatus::Idle, 3_000, 10).expect("next");
+        assert_eq!(next.visitor, "b");
+        assert_eq!(next.payload, msg("yo"));
+        assert_eq!(queue.position("c"), Some(("lead".into(), 1)));
+
+        // 다른 대상은 영향 없음
+        assert_eq!(queue.request("d", "other", msg(""), 3_000), Admission::Go);
+    }
+
+    #[test]
+    fn test_admit_does_not_change_queue() {
+        let mut queue = ChatQueue::default();
+        assert_eq!(queue.admit("a", "lead"), Admission::Go);
+        assert_eq!(queue.admit("a", "lead"), Admission::Go);
+        assert!(queue.targets.is_empty());
+
+        queue.request("a", "lead", msg(""), 0);
+        queue.request("b", "lead", msg(""), 0);
+        assert_eq!(queue.admit("a", "lead"), Admission::Go);
+        assert_eq!(queue.admit("b", "lead"), Admission::Queued { position: 1 });
+        assert_eq!(queue.admit("c", "lead"), Admission::Queued { position: 2 });
+        assert_eq!(queue.waiting("lead"), vec!["b"]);
+        assert_eq!(queue.request("c", "lead", msg(""), 0), Admission::Queued { position: 2 });
+    }
+
+    #[test]
+    fn test_expired_visits_are_dropped() {
+        let mut queue = ChatQueue::default();
+        queue.request("a", "lead", msg(""), 0);
+        queue.request("b", "lead", msg(""), 0);
+        queue.request("c", "lead", msg(""), 8_000);
+
+        assert_eq!(queue.next_expiry_ms(10), Some(10_000));
+        let expired = queue.expire(10_000, 10);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].visitor, "b");
+        assert_eq!(queue.waiting("lead"), vec!["c".to_string()]);
+
+        // 릴리스 시점에 이미 만료된 대기자는 건너뛴다
+        let next = queue.on_transition("a", &AgentStatus::Working, 18_000, 10);
+        assert!(next.is_none());
+        assert!(queue.waiting("lead").is_empty());
+        assert_eq!(queue.request("d", "lead", msg(""), 18_000), Admission::Go);
+    }
+
+    #[test]
+    fn test_departing_or_resending_visitor_leaves_queue() {
+        let mut queue = ChatQueue::default();
+        queue.request("a", "lead", msg(""), 0);
+        queue.request("b", "lead", msg(""), 0);
+        queue.request("c", "lead", msg(""), 0);
+
+        queue.on_transition("b", &AgentStatus::Disappearing, 1_000, 10);
+        assert_eq!(queue.waiting("lead"), vec!["c".to_string()]);
+
+        // c가 다른 대상에게 보내면 lead 대기열에서 빠짐
+        assert_eq!(queue.request("c", "other", msg(""), 1_000), Admission::Go);
+        assert!(queue.waiting("lead").is_empty());
+    }
+}
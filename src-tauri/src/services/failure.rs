@@ -0,0 +1,291 @@
+use crate::config::StateMachineConfig;
+use crate::error::{AppError, ConfigError};
+use crate::models::event::{EventType, FailureClass, NormalizedEvent};
+use crate::services::normalizer_rules::glob_match;
+use regex::Regex;
+
+/// 키워드 목록에서 만든 내장 규칙 이름 (state-machine.md §4.3)
+const FATAL_KEYWORDS_RULE: &str = "fatal_keywords";
+const RETRYABLE_KEYWORDS_RULE: &str = "retryable_keywords";
+
+/// 분류 결과. rule은 매치한 규칙 이름 (없으면 unknown 기본값)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureVerdict {
+    pub class: FailureClass,
+    pub rule: Option<String>,
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    name: String,
+    class: FailureClass,
+    tools: Vec<String>,
+    regex: Option<Regex>,
+    exit_codes: Vec<(i64, i64)>,
+}
+
+impl CompiledRule {
+    fn matches(&self, tool_name: &str, error_message: &str, exit_code: Option<i64>) -> bool {
+        if !self.tools.is_empty() && !self.tools.iter().any(|t| glob_match(t, tool_name)) {
+            return false;
+        }
+        if let Some(re) = &self.regex {
+            if !re.is_match(error_message) {
+                return false;
+            }
+        }
+        if !self.exit_codes.is_empty() {
+            let Some(code) = exit_code else {
+                return false;
+            };
+            if !self.exit_codes.iter().any(|(min, max)| (*min..=*max).contains(&code)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// tool_failed 이벤트의 fatal/retryable/unknown 분류기.
+/// config에서 한 번 컴파일하여 AppState에 공유한다.
+/// 평가 순서: `failure_rules` (위에서부터) → fatal_keywords → retryable_keywords → unknown
+#[derive(Debug)]
+pub struct FailureClassifier {
+    rules: Vec<CompiledRule>,
+}
+
+impl FailureClassifier {
+    pub fn from_config(config: &StateMachineConfig) -> Result<Self, AppError> {
+        let mut rules = Vec::new();
+
+        for rule in &config.failure_rules {
+            let field = format!("state_machine.failure_rules.{}", rule.name);
+            let regex = rule
+                .regex
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .map_err(|e| ConfigError::Validation {
+                    field: field.clone(),
+                    reason: e.to_string(),
+                })?;
+            let exit_codes = rule
+                .exit_codes
+                .iter()
+                .map(|r| {
+                    parse_exit_code_range(r).ok_or_else(|| ConfigError::Validation {
+                        field: field.clone(),
+                        reason: format!("invalid exit code range: {r:?}"),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            rules.push(CompiledRule {
+                name: rule.name.clone(),
+                class: rule.class,
+                tools: rule.tools.clone(),
+                regex,
+                exit_codes,
+            });
+        }
+
+        for (name, class, keywords) in [
+            (FATAL_KEYWORDS_RULE, FailureClass::Fatal, &config.fatal_keywords),
+            (RETRYABLE_KEYWORDS_RULE, FailureClass::Retryable, &config.retryable_keywords),
+        ] {
+            if let Some(regex) = keyword_regex(keywords)? {
+                rules.push(CompiledRule {
+                    name: name.to_string(),
+                    class,
+                    tools: Vec::new(),
+                    regex: Some(regex),
+                    exit_codes: Vec::new(),
+                });
+            }
+        }
+
+        Ok(Self { rules })
+    }
+
+    pub fn classify(&self, event: &NormalizedEvent) -> FailureVerdict {
+        let payload = &event.payload;
+        let tool_name = payload.get("tool_name").and_then(|v| v.as_str()).unwrap_or("");
+        let error_message = payload.get("error_message").and_then(|v| v.as_str()).unwrap_or("");
+        let exit_code = payload.get("exit_code").and_then(|v| v.as_i64());
+
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(tool_name, error_message, exit_code))
+            .map(|rule| FailureVerdict {
+                class: rule.class,
+                rule: Some(rule.name.clone()),
+            })
+            .unwrap_or(FailureVerdict {
+                class: FailureClass::Unknown,
+                rule: None,
+            })
+    }
+
+    /// tool_failed 이벤트의 payload에 failure_class / failure_rule을 기록한다.
+    /// 상태 머신은 이 값으로 전이를 결정하고, 이벤트와 함께 저장되어 이력에서 확인할 수 있다.
+    pub fn annotate(&self, event: &mut NormalizedEvent) {
+        if event.event_type != EventType::ToolFailed {
+            return;
+        }
+        let verdict = self.classify(event);
+        event.payload["failure_class"] = serde_json::json!(verdict.class);
+        event.payload["failure_rule"] = serde_json::json!(verdict.rule);
+    }
+}
+
+/// `"127"` 또는 `"126-127"` 형태의 종료 코드 범위. 음수 단일 값(`"-1"`)도 허용한다.
+pub fn parse_exit_code_range(range: &str) -> Option<(i64, i64)> {
+    let range = range.trim();
+    if let Ok(code) = range.parse::<i64>() {
+        return Some((code, code));
+    }
+    let (min, max) = range.split_once('-')?;
+    let (min, max) = (min.trim().parse::<i64>().ok()?, max.trim().parse::<i64>().ok()?);
+    (min <= max).then_some((min, max))
+}
+
+/// 키워드 목록 → 대소문자 무시 부분 문자열 매치 정규식. 빈 목록이면 None
+fn keyword_regex(keywords: &[String]) -> Result<Option<Regex>, AppError> {
+    if keywords.is_empty() {
+        return Ok(None);
+    }
+    let alternation = keywords
+        .iter()
+        .map(|k| regex::escape(k))
+        .collect::<Vec<_>>()
+        .join("|");
+    let regex = Regex::new(&format!("(?i){alternation}")).map_err(|e| ConfigError::Validation {
+        field: "state_machine.keywords".into(),
+        reason: e.to_string(),
+    })?;
+    Ok(Some(regex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FailureRule;
+    use crate::models::event::{EventSource, Severity};
+
+    fn config(failure_rules: Vec<FailureRule>) -> StateMachineConfig {
+        StateMachineConfig {
+            fatal_keywords: vec!["permission denied".into(), "ENOENT".into()],
+            retryable_keywords: vec!["timeout".into(), "rate limit".into()],
+            fatal_consecutive_failures: 3,
+            transitions: Vec::new(),
+            timers: Vec::new(),
+            failure_rules,
+        }
+    }
+
+    fn failed(tool_name: &str, error_message: &str, exit_code: i64) -> NormalizedEvent {
+        NormalizedEvent {
+            id: "evt-1".into(),
+            version: "1.1".into(),
+            ts: "2026-02-20T15:01:00Z".into(),
+            event_type: EventType::ToolFailed,
+            source: EventSource::Hook,
+            workspace_id: "ws".into(),
+            terminal_session_id: "term-1".into(),
+            run_id: None,
+            session_id: None,
+            agent_id: "ws/leader".into(),
+            target_agent_id: None,
+            task_id: None,
+            severity: Severity::Error,
+            payload: serde_json::json!({
+                "tool_name": tool_name,
+                "error_message": error_message,
+                "exit_code": exit_code,
+            }),
+            thinking_text: None,
+            raw: serde_json::json!({}),
+            redactions: Vec::new(),
+        }
+    }
+
+    fn rule(name: &str, class: FailureClass) -> FailureRule {
+        FailureRule {
+            name: name.into(),
+            class,
+            tools: Vec::new(),
+            regex: None,
+            exit_codes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_keywords_classify_case_insensitive() {
+        let classifier = FailureClassifier::from_config(&config(Vec::new())).expect("compile");
+
+        let verdict = classifier.classify(&failed("Read", "Permission Denied: /etc/shadow", 1));
+        assert_eq!(verdict.class, FailureClass::Fatal);
+        assert_eq!(verdict.rule.as_deref(), Some(FATAL_KEYWORDS_RULE));
+
+        let verdict = classifier.classify(&failed("WebFetch", "request TIMEOUT after 30s", 1));
+        assert_eq!(verdict.class, FailureClass::Retryable);
+        assert_eq!(verdict.rule.as_deref(), Some(RETRYABLE_KEYWORDS_RULE));
+
+        let verdict = classifier.classify(&failed("Bash", "assertion failed", 1));
+        assert_eq!(verdict, FailureVerdict { class: FailureClass::Unknown, rule: None });
+    }
+
+    #[test]
+    fn test_rules_take_precedence_and_filter_by_tool_and_exit_code() {
+        let mut not_found = rule("bash_not_found", FailureClass::Fatal);
+        not_found.tools = vec!["Bash".into()];
+        not_found.exit_codes = vec!["126-127".into()];
+        let mut flaky = rule("flaky_tests", FailureClass::Retryable);
+        flaky.tools = vec!["Bash".into()];
+        flaky.regex = Some(r"(?i)flaky|connection reset".into());
+        let mut mcp_timeout = rule("mcp_timeout_is_unknown", FailureClass::Unknown);
+        mcp_timeout.tools = vec!["mcp__*".into()];
+        mcp_timeout.regex = Some("timeout".into());
+        let classifier = FailureClassifier::from_config(&config(vec![not_found, flaky, mcp_timeout]))
+            .expect("compile");
+
+        let verdict = classifier.classify(&failed("Bash", "pytets: command not found", 127));
+        assert_eq!(verdict.rule.as_deref(), Some("bash_not_found"));
+        assert_eq!(verdict.class, FailureClass::Fatal);
+
+        // 다른 도구에는 적용되지 않음
+        let verdict = classifier.classify(&failed("Read", "no such tool", 127));
+        assert_eq!(verdict.class, FailureClass::Unknown);
+
+        let verdict = classifier.classify(&failed("Bash", "Connection reset by peer", 1));
+        assert_eq!(verdict.rule.as_deref(), Some("flaky_tests"));
+
+        // 사용자 규칙이 retryable 키워드보다 먼저
+        let verdict = classifier.classify(&failed("mcp__jira__create_issue", "timeout", 1));
+        assert_eq!(verdict.class, FailureClass::Unknown);
+        assert_eq!(verdict.rule.as_deref(), Some("mcp_timeout_is_unknown"));
+    }
+
+    #[test]
+    fn test_annotate_records_class_and_rule() {
+        let classifier = FailureClassifier::from_config(&config(Vec::new())).expect("compile");
+        let mut event = failed("Read", "ENOENT: no such file", 1);
+        classifier.annotate(&mut event);
+        assert_eq!(event.payload["failure_class"], "fatal");
+        assert_eq!(event.payload["failure_rule"], FATAL_KEYWORDS_RULE);
+
+        let mut event = failed("Bash", "exit status 2", 2);
+        classifier.annotate(&mut event);
+        assert_eq!(event.payload["failure_class"], "unknown");
+        assert!(event.payload["failure_rule"].is_null());
+    }
+
+    #[test]
+    fn test_parse_exit_code_range() {
+        assert_eq!(parse_exit_code_range("127"), Some((127, 127)));
+        assert_eq!(parse_exit_code_range(" 126 - 127 "), Some((126, 127)));
+        assert_eq!(parse_exit_code_range("-1"), Some((-1, -1)));
+        assert_eq!(parse_exit_code_range("130-128"), None);
+        assert_eq!(parse_exit_code_range("abc"), None);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::models::event::{EventType, Fingerprint, NormalizedEvent};
+use sha2::{Digest, Sha256};
+use std::hash::{Hash, Hasher};
+
+/// 현재 핑거프린트 스킴 버전.
+/// v1: DefaultHasher(session_id + tool_name + ts 1초 절삭 + 정규화 payload) — Rust 릴리스 간 출력이 보장되지 않음
+/// v2: SHA-256(canonical JSON { session_id, tool_name, event_type, ts_bucket, raw - _meta.collected_at })
+pub const FINGERPRINT_VERSION: u32 = 2;
+
+/// 핑거프린트 생성 (hooks-integration.md §7.6)
+/// 마스킹 전 원본(raw) 입력으로 계산해야 redaction 설정과 무관하게 같은 값이 나온다.
+/// raw가 비어 있는 내부 생성 이벤트(thinking companion 등)는 payload와 thinking_text를 대신 사용한다.
+/// `_meta.collected_at`은 전달할 때마다 바뀌므로 해시에서 빼고 ts_bucket으로만 반영한다.
+/// legacy_until은 DB에 남은 가장 최근 v1 행의 ts다. 이벤트 ts가 그 이후면 v1 값을 계산하지 않는다.
+pub fn generate(
+    event: &NormalizedEvent,
+    ts_bucket_secs: u64,
+    legacy_until: Option<&str>,
+) -> Fingerprint {
+    let tool_name = event
+        .payload
+        .get("tool_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    let body = if is_empty(&event.raw) {
+        serde_json::json!({
+            "payload": event.payload,
+            "thinking_text": event.thinking_text,
+        })
+    } else {
+        without_collected_at(&event.raw)
+    };
+
+    let input = serde_json::json!({
+        "session_id": event.session_id,
+        "tool_name": tool_name,
+        "event_type": event.event_type,
+        "ts_bucket": ts_bucket(&event.ts, ts_bucket_secs),
+        "raw": body,
+    });
+
+    let mut canonical = String::new();
+    write_canonical_json(&input, &mut canonical);
+    let digest = Sha256::digest(canonical.as_bytes());
+    let value = digest.iter().map(|b| format!("{b:02x}")).collect::<String>();
+
+    Fingerprint {
+        value,
+        version: FINGERPRINT_VERSION,
+        legacy_value: legacy_until
+            .filter(|until| !is_after(&event.ts, until))
+            .map(|_| legacy_v1(event)),
+    }
+}
+
+/// ts 버킷 키. ts_bucket_secs가 0이면 ts 원문을 그대로 쓰고,
+/// 그 외에는 epoch 초를 버킷 크기로 내림한 값을 쓴다. 파싱 실패 시 ts 원문.
+fn ts_bucket(ts: &str, ts_bucket_secs: u64) -> serde_json::Value {
+    if ts_bucket_secs == 0 {
+        return serde_json::Value::String(ts.to_string());
+    }
+    match chrono::DateTime::parse_from_rfc3339(ts) {
+        Ok(dt) => {
+            let secs = ts_bucket_secs as i64;
+            serde_json::Value::from(dt.timestamp().div_euclid(secs) * secs)
+        }
+        Err(_) => serde_json::Value::String(ts.to_string()),
+    }
+}
+
+/// 재전송/중복 발화마다 값이 달라지는 `_meta.collected_at`을 뺀 raw 사본
+fn without_collected_at(raw: &serde_json::Value) -> serde_json::Value {
+    let mut body = raw.clone();
+    if let Some(meta) = body.get_mut("_meta").and_then(|m| m.as_object_mut()) {
+        meta.remove("collected_at");
+    }
+    body
+}
+
+/// v1 ts 버킷(1초 절삭) 기준으로 ts가 until보다 뒤인지. 파싱 실패 시 false (v1 비교 유지)
+fn is_after(ts: &str, until: &str) -> bool {
+    match (
+        chrono::DateTime::parse_from_rfc3339(ts),
+        chrono::DateTime::parse_from_rfc3339(until),
+    ) {
+        (Ok(ts), Ok(until)) => ts.timestamp() > until.timestamp(),
+        _ => false,
+    }
+}
+
+fn is_empty(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// 키를 정렬하고 공백 없이 직렬화한 canonical JSON.
+/// serde_json의 Map 순서(preserve_order feature 여부)에 의존하지 않는다.
+fn write_canonical_json(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(&map[key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// v1 핑거프린트. 업그레이드 이전에 저장된 행과의 중복 비교에만 사용한다.
+/// v1 행이 남아 있는 동안만 필요하며, 다음 릴리스에서 events.fingerprint_version이 없는 행과 함께 제거한다.
+fn legacy_v1(event: &NormalizedEvent) -> String {
+    let session_id = event.session_id.as_deref().unwrap_or("");
+    let (tool_name, payload) = if event.event_type == EventType::SchemaError {
+        ("", &event.raw)
+    } else {
+        let tool_name = event
+            .payload
+            .get("tool_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        (tool_name, &event.payload)
+    };
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    session_id.hash(&mut hasher);
+    tool_name.hash(&mut hasher);
+    let ts_bucket = if event.ts.len() >= 19 { &event.ts[..19] } else { &event.ts };
+    ts_bucket.hash(&mut hasher);
+    payload.to_string().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::event::{EventSource, Severity};
+    use crate::services::normalizer;
+    use crate::storage::db::init_db_in_memory;
+    use crate::storage::events_repo::EventsRepo;
+    use serde_json::json;
+
+    fn raw(session_id: &str, collected_at: &str) -> serde_json::Value {
+        json!({
+            "hook_type": "PreToolUse",
+            "session_id": session_id,
+            "tool_name": "Read",
+            "tool_input": {"file_path": "/tmp/a.rs", "limit": 10},
+            "_meta": {
+                "workspace_id": "ws",
+                "terminal_session_id": "term-1",
+                "collected_at": collected_at
+            }
+        })
+    }
+
+    fn fingerprint(raw: &serde_json::Value, ts_bucket_secs: u64) -> Fingerprint {
+        let event = normalizer::normalize(raw).expect("should normalize");
+        generate(&event, ts_bucket_secs, None)
+    }
+
+    #[test]
+    fn test_fingerprint_deterministic() {
+        let fp1 = fingerprint(&raw("sess-1", "2026-02-20T15:00:00Z"), 1);
+        let fp2 = fingerprint(&raw("sess-1", "2026-02-20T15:00:00Z"), 1);
+        assert_eq!(fp1, fp2);
+        assert_eq!(fp1.version, FINGERPRINT_VERSION);
+        assert_eq!(fp1.value.len(), 64);
+    }
+
+    #[test]
+    fn test_fingerprint_known_value() {
+        // 스킴이 바뀌면 이 값이 바뀐다. 의도한 변경이면 FINGERPRINT_VERSION도 올릴 것.
+        let event = NormalizedEvent {
+            id: "evt-1".into(),
+            version: "1.1".into(),
+            ts: "2026-02-20T15:00:00.400Z".into(),
+            event_type: EventType::ToolStarted,
+            source: EventSource::Hook,
+            workspace_id: "ws".into(),
+            terminal_session_id: "term-1".into(),
+            run_id: None,
+            session_id: Some("sess-1".into()),
+            agent_id: "ws/leader".into(),
+            target_agent_id: None,
+            task_id: None,
+            severity: Severity::Info,
+            payload: json!({"tool_name": "Read"}),
+            thinking_text: None,
+            raw: json!({"b": [1, {"y": true, "x": null}], "a": "한글"}),
+            redactions: Vec::new(),
+        };
+        let mut canonical = String::new();
+        write_canonical_json(&event.raw, &mut canonical);
+        assert_eq!(canonical, r#"{"a":"한글","b":[1,{"x":null,"y":true}]}"#);
+
+        let fp = generate(&event, 1, None);
+        assert_eq!(
+            fp.value,
+            "39858be4ef600d2edb07f9f2a19d512fa423271986c8e5bf58a8f85036fd234b"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_key_order_independent() {
+        let a: serde_json::Value =
+            serde_json::from_str(r#"{"x": 1, "y": {"p": 1, "q": 2}}"#).expect("json");
+        let b: serde_json::Value =
+            serde_json::from_str(r#"{"y": {"q": 2, "p": 1}, "x": 1}"#).expect("json");
+        let (mut ca, mut cb) = (String::new(), String::new());
+        write_canonical_json(&a, &mut ca);
+        write_canonical_json(&b, &mut cb);
+        assert_eq!(ca, cb);
+    }
+
+    #[test]
+    fn test_fingerprint_different_inputs() {
+        let fp1 = fingerprint(&raw("sess-1", "2026-02-20T15:00:00Z"), 1);
+        let fp2 = fingerprint(&raw("sess-2", "2026-02-20T15:00:00Z"), 1);
+        assert_ne!(fp1.value, fp2.value);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_collected_at() {
+        // 같은 hook이 두 번 전달되면 collected_at만 다르다
+        let fp1 = fingerprint(&raw("sess-1", "2026-02-20T15:00:00.100Z"), 1);
+        let fp2 = fingerprint(&raw("sess-1", "2026-02-20T15:00:00.900Z"), 1);
+        assert_eq!(fp1.value, fp2.value);
+
+        let db = init_db_in_memory().expect("db init");
+        let repo = EventsRepo::new(db.clone());
+        for collected_at in ["2026-02-20T15:00:00.100Z", "2026-02-20T15:00:00.900Z"] {
+            let event =
+                normalizer::normalize(&raw("sess-1", collected_at)).expect("should normalize");
+            repo.insert(&event, &generate(&event, 1, None)).expect("insert");
+        }
+        let count: i64 = db
+            .lock()
+            .expect("lock")
+            .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(count, 1);
+
+        // 버킷이 다르면 별개 이벤트
+        let fp3 = fingerprint(&raw("sess-1", "2026-02-20T15:00:01.100Z"), 1);
+        assert_ne!(fp1.value, fp3.value);
+    }
+
+    #[test]
+    fn test_legacy_value_only_before_upgrade() {
+        let event = normalizer::normalize(&raw("sess-1", "2026-02-20T15:00:00.400Z"))
+            .expect("should normalize");
+        assert!(generate(&event, 1, None).legacy_value.is_none());
+        // 같은 초에 저장된 v1 행이 있으면 비교 대상
+        assert!(generate(&event, 1, Some("2026-02-20T15:00:00Z")).legacy_value.is_some());
+        assert!(generate(&event, 1, Some("2026-02-20T16:00:00Z")).legacy_value.is_some());
+        assert!(generate(&event, 1, Some("2026-02-20T14:59:59Z")).legacy_value.is_none());
+    }
+
+    #[test]
+    fn test_fingerprint_ts_bucket() {
+        assert_eq!(
+            ts_bucket("2026-02-20T15:00:01Z", 5),
+            ts_bucket("2026-02-20T15:00:04.900Z", 5)
+        );
+        assert_ne!(
+            ts_bucket("2026-02-20T15:00:04Z", 5),
+            ts_bucket("2026-02-20T15:00:05Z", 5)
+        );
+        assert_ne!(
+            ts_bucket("2026-02-20T15:00:00.100Z", 0),
+            ts_bucket("2026-02-20T15:00:00.200Z", 0)
+        );
+        assert_eq!(ts_bucket("not-a-ts", 5), json!("not-a-ts"));
+    }
+}
@@ -1,106 +1,201 @@
+use crate::commands::agents;
+use crate::config::LivenessConfig;
 use crate::state::AppState;
-use crate::models::agent::AgentStatus;
-use crate::storage::state_repo::StateRepo;
-use tauri::Emitter;
+use crate::models::agent::AgentState;
+use crate::models::event::{EventType, Severity};
+use crate::services::scheduler::DeadlineOutcome;
+use crate::services::state_machine;
 
 /// Heartbeat 서비스 메인 루프 (state-machine.md §5)
-/// config.heartbeat.interval_secs 간격으로 에이전트 상태를 검사하여 타이머 전이 수행.
+/// 시작할 때 한 번 모든 에이전트의 마감을 잡고, 이후에는 가장 이른 마감(또는 대화 대기 만료)까지 잠든다.
+/// 깨어나면 마감된 에이전트만 메모리 사본에서 읽어 생존 감지/타이머 전이를 한다.
+/// 타이머 전이는 timer_elapsed synthetic 이벤트로 기록하고 일반 이벤트와 같은 경로로 처리한다.
 pub async fn run_heartbeat(state: AppState, app_handle: tauri::AppHandle) {
-    let interval_secs = state.config.heartbeat.interval_secs;
-    let timer_config = &state.config.state_machine.timer_transitions;
+    let scheduler = state.scheduler.clone();
 
-    let idle_to_resting = timer_config.idle_to_resting_secs;
-    let completed_to_disappear = timer_config.completed_to_disappear_secs;
-    let chat_timeout = timer_config.chat_timeout_secs;
+    match state.agents.all_states() {
+        Ok(agents) => {
+            for agent in &agents {
+                scheduler.reschedule(agent, &state.config);
+            }
+        }
+        Err(e) => tracing::error!("heartbeat: failed to get agent states: {e}"),
+    }
 
     tracing::info!(
-        "heartbeat service started (interval={}s, idle→rest={}s, completed→disappear={}s, chat_timeout={}s)",
-        interval_secs, idle_to_resting, completed_to_disappear, chat_timeout,
+        "heartbeat service started (timers={}, scheduled agents={})",
+        state.config.state_machine.timers.len(),
+        scheduler.scheduled(),
     );
 
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
-
     loop {
-        interval.tick().await;
-
-        let now = chrono::Utc::now();
-        let state_repo = StateRepo::new(state.db.clone());
-
-        let agents = match state_repo.get_all() {
-            Ok(agents) => agents,
+        let timeout_secs = state.config.movement.chat_queue_timeout_secs;
+        let chat_expiry = match state.chat_queue.lock() {
+            Ok(queue) => queue.next_expiry_ms(timeout_secs),
             Err(e) => {
-                tracing::error!("heartbeat: failed to get agent states: {e}");
-                continue;
+                tracing::warn!("heartbeat: chat_queue lock poisoned: {e}");
+                None
             }
         };
+        let wake_at = [scheduler.next_deadline(), chat_expiry].into_iter().flatten().min();
+        scheduler.wait_until(wake_at).await;
 
-        for mut agent in agents {
-            let elapsed_secs = match parse_elapsed_secs(&agent.since, &now) {
-                Some(secs) => secs,
-                None => {
-                    tracing::warn!(
-                        "heartbeat: failed to parse since={} for agent={}",
-                        agent.since, agent.agent_id,
-                    );
+        let due = scheduler.pop_due();
+        let now = scheduler.now();
+        expire_chat_queue(&state, &app_handle, now.timestamp_millis());
+
+        for agent_id in due {
+            let agent = match state.agents.get_state(&agent_id) {
+                Ok(Some(agent)) => agent,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::error!("heartbeat: failed to get agent state {agent_id}: {e}");
                     continue;
                 }
             };
+            let outcome = on_deadline(&state, &app_handle, &agent, &now);
+            scheduler.settle(&agent, &state.config, outcome, now.timestamp_millis());
+        }
+    }
+}
 
-            let (should_transition, new_status) = match agent.status {
-                AgentStatus::Idle if elapsed_secs >= idle_to_resting => {
-                    (true, AgentStatus::Resting)
-                }
-                AgentStatus::Completed if elapsed_secs >= completed_to_disappear => {
-                    (true, AgentStatus::Disappearing)
-                }
-                AgentStatus::Chatting if elapsed_secs >= chat_timeout => {
-                    (true, AgentStatus::Returning)
-                }
-                _ => (false, agent.status.clone()),
-            };
+/// 마감된 에이전트의 생존 감지(§5.1) → 타이머 규칙(§5) 순으로 검사한다
+fn on_deadline(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    agent: &AgentState,
+    now: &chrono::DateTime<chrono::Utc>,
+) -> DeadlineOutcome {
+    // 세션이 Stop 없이 끊긴 에이전트 (§5.1)
+    if let Some(silent_secs) = parse_elapsed_secs(&agent.last_event_ts, now) {
+        if let Some(action) = liveness_action(agent, silent_secs, &state.config.liveness) {
+            return handle_liveness(state, app_handle, agent.clone(), action, silent_secs);
+        }
+    }
 
-            if should_transition {
-                let prev_status = agent.status.clone();
-                let ts = now.to_rfc3339();
+    let Some(elapsed_secs) = parse_elapsed_secs(&agent.since, now) else {
+        tracing::warn!(
+            "heartbeat: failed to parse since={} for agent={}",
+            agent.since, agent.agent_id,
+        );
+        return DeadlineOutcome::Skipped;
+    };
 
-                agent.status = new_status.clone();
-                agent.since = ts.clone();
-                agent.last_event_ts = ts.clone();
+    let payload = serde_json::json!({ "elapsed_secs": elapsed_secs });
+    let mut event = agents::synthetic_event(agent, "heartbeat", EventType::TimerElapsed, payload);
+    let Some(timer) = state_machine::find_timer(agent, &event, &state.config.state_machine) else {
+        return DeadlineOutcome::Skipped;
+    };
 
-                if let Err(e) = state_repo.upsert(&agent) {
-                    tracing::error!(
-                        "heartbeat: failed to update state for agent={}: {e}",
-                        agent.agent_id,
-                    );
-                    continue;
-                }
+    tracing::info!(
+        "heartbeat: timer transition agent={} {:?} → {:?} after {elapsed_secs}s",
+        agent.agent_id, agent.status, timer.to_status,
+    );
+    // 어떤 규칙으로 전이했는지 이벤트 이력에 남긴다
+    event.payload["timer"] = serde_json::to_value(timer).unwrap_or_default();
+    match agents::record_and_apply(state, app_handle, agent.clone(), &event) {
+        Ok(_) => DeadlineOutcome::Applied,
+        Err(e) => {
+            tracing::error!("heartbeat: timer transition failed, retrying: {e}");
+            DeadlineOutcome::Failed
+        }
+    }
+}
 
-                tracing::info!(
-                    "heartbeat: timer transition agent={} {:?} → {:?}",
-                    agent.agent_id, prev_status, new_status,
-                );
-
-                // Tauri 이벤트 emit (ipc-protocol.md §2.2)
-                let update_payload = serde_json::json!({
-                    "agent_id": agent.agent_id,
-                    "status": new_status,
-                    "prev_status": prev_status,
-                    "thinking_text": agent.thinking_text,
-                    "current_task": agent.current_task,
-                    "workspace_id": agent.workspace_id,
-                    "peer_agent_id": agent.peer_agent_id,
-                    "chat_message": null,
-                    "ts": agent.since,
-                });
-
-                if let Err(e) = app_handle.emit("mascot://agent-update", &update_payload) {
-                    tracing::error!("heartbeat: failed to emit agent-update: {e}");
-                }
-            }
+/// 생존 감지 결과 (state-machine.md §5.1)
+#[derive(Debug, PartialEq)]
+enum LivenessAction {
+    /// 응답 없음 표시 (agent_unresponsive)
+    MarkUnresponsive,
+    /// 퇴장 (agent_stopped)
+    Disappear,
+}
+
+/// 감시 상태에서 세션 이벤트가 끊긴 시간으로 할 일을 정한다. 응답 없음 표시는 한 번만 한다.
+fn liveness_action(
+    agent: &AgentState,
+    silent_secs: u64,
+    config: &LivenessConfig,
+) -> Option<LivenessAction> {
+    if !config.statuses.contains(&agent.status) {
+        return None;
+    }
+    if silent_secs >= config.disappear_after_secs {
+        Some(LivenessAction::Disappear)
+    } else if silent_secs >= config.unresponsive_after_secs && !agent.unresponsive {
+        Some(LivenessAction::MarkUnresponsive)
+    } else {
+        None
+    }
+}
+
+/// 이유를 담은 synthetic 이벤트를 기록하고 일반 이벤트와 같은 경로로 처리한다
+fn handle_liveness(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    agent: AgentState,
+    action: LivenessAction,
+    silent_secs: u64,
+) -> DeadlineOutcome {
+    let (event_type, reason) = match action {
+        LivenessAction::MarkUnresponsive => (EventType::AgentUnresponsive, "no_events"),
+        LivenessAction::Disappear => (EventType::AgentStopped, "unresponsive_timeout"),
+    };
+    tracing::warn!(
+        "heartbeat: agent={} silent for {silent_secs}s in {:?} → {:?}",
+        agent.agent_id, agent.status, event_type,
+    );
+
+    let payload = serde_json::json!({
+        "reason": reason,
+        "silent_secs": silent_secs,
+        "last_event_ts": agent.last_event_ts,
+        "status": agent.status,
+    });
+    let mut event = agents::synthetic_event(&agent, "heartbeat", event_type, payload);
+    event.severity = Severity::Warn;
+
+    match agents::record_and_apply(state, app_handle, agent, &event) {
+        Ok(_) => DeadlineOutcome::Applied,
+        Err(e) => {
+            tracing::error!("heartbeat: liveness transition failed, retrying: {e}");
+            DeadlineOutcome::Failed
         }
     }
 }
 
+/// chat_queue_timeout_secs를 넘긴 대화 대기를 버리고 (state-machine.md §10.6),
+/// 버려진 방문자와 같은 줄의 남은 대기자에게 바뀐 순번을 알린다.
+fn expire_chat_queue(state: &AppState, app_handle: &tauri::AppHandle, now_ms: i64) {
+    let timeout_secs = state.config.movement.chat_queue_timeout_secs;
+    let expired = match state.chat_queue.lock() {
+        Ok(mut queue) => queue.expire(now_ms, timeout_secs),
+        Err(e) => {
+            tracing::warn!("heartbeat: chat_queue lock poisoned: {e}");
+            return;
+        }
+    };
+
+    let mut targets: Vec<&str> = Vec::new();
+    for visit in &expired {
+        tracing::info!(
+            "heartbeat: dropped chat visit {} → {} after {timeout_secs}s in queue",
+            visit.visitor, visit.target,
+        );
+        match state.agents.get_state(&visit.visitor) {
+            Ok(Some(agent)) => agents::emit_agent_refresh(state, app_handle, &agent),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("heartbeat: state get failed for {}: {e}", visit.visitor),
+        }
+        if !targets.contains(&visit.target.as_str()) {
+            targets.push(&visit.target);
+        }
+    }
+    for target in targets {
+        agents::emit_queue_positions(state, app_handle, target);
+    }
+}
+
 /// ISO-8601 타임스탬프에서 현재까지 경과 초 계산
 fn parse_elapsed_secs(since: &str, now: &chrono::DateTime<chrono::Utc>) -> Option<u64> {
     let since_dt = chrono::DateTime::parse_from_rfc3339(since).ok()?;
@@ -111,6 +206,7 @@ fn parse_elapsed_secs(since: &str, now: &chrono::DateTime<chrono::Utc>) -> Optio
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::agent::AgentStatus;
 
     #[test]
     fn test_parse_elapsed_secs() {
@@ -133,4 +229,50 @@ mod tests {
         let elapsed = parse_elapsed_secs(&future, &now).expect("should parse");
         assert_eq!(elapsed, 0, "future timestamp should clamp to 0");
     }
+
+    fn liveness() -> LivenessConfig {
+        LivenessConfig {
+            unresponsive_after_secs: 300,
+            disappear_after_secs: 1800,
+            statuses: vec![AgentStatus::Working, AgentStatus::Thinking],
+        }
+    }
+
+    fn agent(status: AgentStatus, unresponsive: bool) -> AgentState {
+        AgentState {
+            agent_id: "ws/worker".into(),
+            status,
+            prev_status: None,
+            thinking_text: None,
+            current_task: None,
+            activity: None,
+            activity_summary: None,
+            consecutive_failures: 0,
+            unresponsive,
+            workspace_id: "ws".into(),
+            since: "2026-02-20T15:00:00Z".into(),
+            last_event_ts: "2026-02-20T15:00:00Z".into(),
+            session_id: None,
+            peer_agent_id: None,
+            home_x: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_liveness_action() {
+        let config = liveness();
+        let working = agent(AgentStatus::Working, false);
+        assert_eq!(liveness_action(&working, 299, &config), None);
+        assert_eq!(liveness_action(&working, 300, &config), Some(LivenessAction::MarkUnresponsive));
+        assert_eq!(liveness_action(&working, 1800, &config), Some(LivenessAction::Disappear));
+
+        // 이미 표시했으면 퇴장 시점까지 다시 하지 않음
+        let marked = agent(AgentStatus::Thinking, true);
+        assert_eq!(liveness_action(&marked, 600, &config), None);
+        assert_eq!(liveness_action(&marked, 1800, &config), Some(LivenessAction::Disappear));
+
+        // 사용자를 기다리는 상태는 감시하지 않음
+        let idle = agent(AgentStatus::Idle, false);
+        assert_eq!(liveness_action(&idle, 10_000, &config), None);
+    }
 }
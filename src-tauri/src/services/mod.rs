@@ -1,5 +1,21 @@
+pub mod activity;
+pub mod adapter;
 pub mod appearance;
+pub mod chat_queue;
 pub mod cursor_poll;
+pub mod failure;
+pub mod fingerprint;
 pub mod heartbeat;
 pub mod normalizer;
+pub mod normalizer_rules;
+pub mod otlp;
+pub mod reconcile;
+pub mod retention;
+pub mod scheduler;
+pub mod redaction;
+pub mod state_diagram;
 pub mod state_machine;
+pub mod task_tracker;
+pub mod thinking;
+pub mod validator;
+pub mod workspace;
@@ -1,36 +1,18 @@
+use crate::config::NormalizerRule;
 use crate::error::AppError;
 use crate::models::event::*;
-use std::hash::{Hash, Hasher};
+use crate::services::normalizer_rules;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 static EVENT_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 /// 전역 고유 이벤트 ID 생성
-fn generate_event_id() -> String {
+pub(crate) fn generate_event_id() -> String {
     let ts = chrono::Utc::now().format("%Y%m%d%H%M%S");
     let seq = EVENT_COUNTER.fetch_add(1, Ordering::Relaxed);
     format!("evt_{ts}_{seq:06}")
 }
 
-/// 핑거프린트 생성 (hooks-integration.md §7.6)
-/// fingerprint = hash(session_id + tool_name + ts_bucket + payload_hash)
-pub fn generate_fingerprint(
-    session_id: &str,
-    tool_name: &str,
-    ts: &str,
-    payload: &serde_json::Value,
-) -> String {
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    session_id.hash(&mut hasher);
-    tool_name.hash(&mut hasher);
-    // ts_bucket: 1초 단위 절삭 (최소 19자: "2026-02-20T15:00:00")
-    let ts_bucket = if ts.len() >= 19 { &ts[..19] } else { ts };
-    ts_bucket.hash(&mut hasher);
-    let payload_str = payload.to_string();
-    payload_str.hash(&mut hasher);
-    format!("{:016x}", hasher.finish())
-}
-
 /// agent_id 도출 (hooks-integration.md §7.3)
 pub fn derive_agent_id(
     team_name: Option<&str>,
@@ -47,11 +29,62 @@ pub fn derive_agent_id(
 
 /// hook payload → NormalizedEvent 변환 (hooks-integration.md §5.3~§7)
 pub fn normalize(raw: &serde_json::Value) -> Result<NormalizedEvent, AppError> {
+    normalize_with_rules(raw, &[])
+}
+
+/// 사용자 정의 규칙(§6.3)을 먼저 평가하고, 매치되는 규칙이 없으면 내장 매핑으로 변환한다.
+pub fn normalize_with_rules(
+    raw: &serde_json::Value,
+    rules: &[NormalizerRule],
+) -> Result<NormalizedEvent, AppError> {
     let hook_type = raw
         .get("hook_type")
         .and_then(|v| v.as_str())
         .ok_or_else(|| AppError::Normalize("missing hook_type field".into()))?;
 
+    if let Some(matched) = normalizer_rules::apply(rules, hook_type, raw) {
+        return Ok(build_event(
+            raw,
+            matched.event_type,
+            matched.severity,
+            matched.payload,
+            None,
+            matched.task_id,
+        ));
+    }
+
+    let (event_type, severity, payload, target_agent_id, task_id) =
+        map_hook_type(hook_type, raw)?;
+
+    Ok(build_event(raw, event_type, severity, payload, target_agent_id, task_id))
+}
+
+/// 정규화 자체가 불가능한 payload(hook_type 누락/미지원 등)를 schema_error 이벤트로 만든다.
+/// 식별자는 raw에서 얻을 수 있는 만큼 채운다.
+pub fn schema_error_event(raw: &serde_json::Value, violations: Vec<String>) -> NormalizedEvent {
+    let hook_type = raw.get("hook_type").cloned().unwrap_or(serde_json::Value::Null);
+    build_event(
+        raw,
+        EventType::SchemaError,
+        Severity::Warn,
+        serde_json::json!({
+            "violations": violations,
+            "hook_type": hook_type,
+        }),
+        None,
+        None,
+    )
+}
+
+/// raw payload의 _meta와 식별자 필드(§7.3)로 NormalizedEvent를 구성한다.
+fn build_event(
+    raw: &serde_json::Value,
+    event_type: EventType,
+    severity: Severity,
+    payload: serde_json::Value,
+    target_agent_id: Option<String>,
+    task_id: Option<String>,
+) -> NormalizedEvent {
     let meta = raw.get("_meta").cloned().unwrap_or_else(|| serde_json::json!({}));
     let workspace_id = meta
         .get("workspace_id")
@@ -73,16 +106,7 @@ pub fn normalize(raw: &serde_json::Value) -> Result<NormalizedEvent, AppError> {
 
     let agent_id = derive_agent_id(team_name, agent_name, session_id);
 
-    let (event_type, severity, payload, target_agent_id, task_id) =
-        map_hook_type(hook_type, raw)?;
-
-    let _tool_name = raw
-        .get("tool_name")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let _fingerprint_payload = raw.get("tool_input").unwrap_or(&serde_json::json!({}));
-
-    Ok(NormalizedEvent {
+    NormalizedEvent {
         id: generate_event_id(),
         version: "1.1".to_string(),
         ts,
@@ -99,7 +123,8 @@ pub fn normalize(raw: &serde_json::Value) -> Result<NormalizedEvent, AppError> {
         payload,
         thinking_text: extract_thinking(raw),
         raw: raw.clone(),
-    })
+        redactions: Vec::new(),
+    }
 }
 
 /// hook_type → (EventType, Severity, payload, target_agent_id, task_id) 매핑
@@ -142,6 +167,14 @@ fn map_hook_type(
         )),
         "PreToolUse" => normalize_pre_tool_use(raw),
         "PostToolUse" => normalize_post_tool_use(raw),
+        // thinking만 담긴 Notification은 단독 thinking_updated 이벤트로 처리
+        "Notification" if is_thinking_only_notification(raw) => Ok((
+            EventType::ThinkingUpdated,
+            Severity::Debug,
+            serde_json::json!({}),
+            None,
+            None,
+        )),
         "Notification" => Ok((
             EventType::Notification,
             match raw.get("level").and_then(|v| v.as_str()) {
@@ -171,16 +204,14 @@ fn normalize_pre_tool_use(
     let tool_input = raw.get("tool_input").cloned().unwrap_or_else(|| serde_json::json!({}));
 
     let (event_type, task_id) = match tool_name {
-        "TaskCreate" => (EventType::TaskCreated, None),
+        "TaskCreate" => (EventType::TaskCreated, extract_task_id(tool_name, raw)),
         "TaskUpdate" => {
             let status = tool_input.get("status").and_then(|v| v.as_str());
-            let tid = tool_input
-                .get("taskId")
-                .and_then(|v| v.as_str())
-                .map(String::from);
+            let tid = extract_task_id(tool_name, raw);
             match status {
                 Some("completed") => (EventType::TaskCompleted, tid),
                 Some("in_progress") => (EventType::TaskStarted, tid),
+                Some("failed") => (EventType::TaskFailed, tid),
                 _ => (EventType::TaskProgress, tid),
             }
         }
@@ -221,10 +252,13 @@ fn normalize_post_tool_use(
             serde_json::json!({
                 "tool_name": tool_name,
                 "error_message": error_msg,
-                "exit_code": raw.get("exit_code").and_then(|v| v.as_i64()),
+                "exit_code": raw
+                    .get("exit_code")
+                    .or_else(|| raw.pointer("/tool_response/exit_code"))
+                    .and_then(|v| v.as_i64()),
             }),
             None,
-            None,
+            extract_task_id(tool_name, raw),
         ))
     } else {
         Ok((
@@ -234,19 +268,102 @@ fn normalize_post_tool_use(
                 "tool_name": tool_name,
             }),
             None,
-            None,
+            extract_task_id(tool_name, raw),
         ))
     }
 }
 
+/// TaskCreate/TaskUpdate의 task id 추출.
+/// TaskUpdate는 tool_input.taskId에 있고, TaskCreate는 PreToolUse 시점에는 id가 없으므로
+/// PostToolUse의 tool_response(또는 tool_result)에서 찾는다.
+fn extract_task_id(tool_name: &str, raw: &serde_json::Value) -> Option<String> {
+    if tool_name != "TaskCreate" && tool_name != "TaskUpdate" {
+        return None;
+    }
+
+    let from_input = raw
+        .get("tool_input")
+        .and_then(|input| input.get("taskId").or_else(|| input.get("id")))
+        .and_then(json_id_to_string);
+    if from_input.is_some() {
+        return from_input;
+    }
+
+    let response = raw.get("tool_response").or_else(|| raw.get("tool_result"))?;
+    if let Some(text) = response.as_str() {
+        // 예: "Task #3 created successfully"
+        let digits: String = text
+            .split_once('#')?
+            .1
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        return (!digits.is_empty()).then_some(digits);
+    }
+    response
+        .pointer("/task/id")
+        .or_else(|| response.get("taskId"))
+        .or_else(|| response.get("id"))
+        .and_then(json_id_to_string)
+}
+
+/// 문자열 또는 숫자로 온 id를 문자열로 변환
+fn json_id_to_string(v: &serde_json::Value) -> Option<String> {
+    match v {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
 /// thinking/extended_thinking 텍스트 추출 (hooks-integration.md §6.2)
+/// 공백뿐인 텍스트는 없는 것으로 취급한다.
 fn extract_thinking(raw: &serde_json::Value) -> Option<String> {
     raw.get("thinking")
         .and_then(|v| v.as_str())
         .or_else(|| raw.get("extended_thinking").and_then(|v| v.as_str()))
+        .filter(|s| !s.trim().is_empty())
         .map(String::from)
 }
 
+/// message 없이 thinking 텍스트만 담긴 Notification인지 판정
+fn is_thinking_only_notification(raw: &serde_json::Value) -> bool {
+    let message = raw.get("message").and_then(|v| v.as_str()).unwrap_or("");
+    message.trim().is_empty() && extract_thinking(raw).is_some()
+}
+
+/// 도구/작업 이벤트에 thinking 텍스트가 실려 왔을 때 동반 thinking_updated 이벤트를 만든다.
+/// 원본 이벤트 처리 직후 같은 ts로 처리되며, payload에 원본 이벤트 id를 남긴다.
+/// 원본이 이미 thinking_updated이거나 thinking 텍스트가 없으면 None.
+pub fn thinking_event(primary: &NormalizedEvent) -> Option<NormalizedEvent> {
+    if primary.event_type == EventType::ThinkingUpdated {
+        return None;
+    }
+    let thinking_text = primary.thinking_text.clone()?;
+
+    Some(NormalizedEvent {
+        id: generate_event_id(),
+        version: primary.version.clone(),
+        ts: primary.ts.clone(),
+        event_type: EventType::ThinkingUpdated,
+        source: primary.source.clone(),
+        workspace_id: primary.workspace_id.clone(),
+        terminal_session_id: primary.terminal_session_id.clone(),
+        run_id: primary.run_id.clone(),
+        session_id: primary.session_id.clone(),
+        agent_id: primary.agent_id.clone(),
+        target_agent_id: None,
+        task_id: primary.task_id.clone(),
+        severity: Severity::Debug,
+        payload: serde_json::json!({
+            "source_event_id": primary.id,
+        }),
+        thinking_text: Some(thinking_text),
+        raw: serde_json::json!({}),
+        redactions: Vec::new(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,6 +447,50 @@ mod tests {
         assert_eq!(event.event_type, EventType::TaskCompleted);
     }
 
+    #[test]
+    fn test_normalize_pre_tool_use_task_update_failed() {
+        let raw = json!({
+            "hook_type": "PreToolUse",
+            "session_id": "sess-1",
+            "tool_name": "TaskUpdate",
+            "tool_input": {"taskId": "7", "status": "failed"},
+            "_meta": {
+                "workspace_id": "my-project",
+                "terminal_session_id": "term-1",
+                "collected_at": "2026-02-20T15:00:00Z"
+            }
+        });
+
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::TaskFailed);
+        assert_eq!(event.task_id.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn test_normalize_post_tool_use_task_create_extracts_id() {
+        let raw = json!({
+            "hook_type": "PostToolUse",
+            "session_id": "sess-1",
+            "tool_name": "TaskCreate",
+            "tool_input": {"subject": "Write docs"},
+            "tool_response": {"task": {"id": 12, "subject": "Write docs"}},
+            "_meta": {
+                "workspace_id": "my-project",
+                "terminal_session_id": "term-1",
+                "collected_at": "2026-02-20T15:00:00Z"
+            }
+        });
+
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::ToolSucceeded);
+        assert_eq!(event.task_id.as_deref(), Some("12"));
+
+        let mut text_raw = raw.clone();
+        text_raw["tool_response"] = json!("Task #4 created successfully: Write docs");
+        let event = normalize(&text_raw).expect("should normalize");
+        assert_eq!(event.task_id.as_deref(), Some("4"));
+    }
+
     #[test]
     fn test_normalize_post_tool_use_success() {
         let raw = json!({
@@ -385,24 +546,108 @@ mod tests {
     }
 
     #[test]
-    fn test_normalize_missing_hook_type() {
-        let raw = json!({"some": "data"});
-        let result = normalize(&raw);
-        assert!(result.is_err());
+    fn test_normalize_thinking_only_notification() {
+        let raw = json!({
+            "hook_type": "Notification",
+            "session_id": "sess-1",
+            "thinking": "let me check the tests first",
+            "_meta": {
+                "workspace_id": "my-project",
+                "terminal_session_id": "term-1",
+                "collected_at": "2026-02-20T15:00:00Z"
+            }
+        });
+
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::ThinkingUpdated);
+        assert_eq!(event.thinking_text.as_deref(), Some("let me check the tests first"));
+        assert!(thinking_event(&event).is_none());
+    }
+
+    #[test]
+    fn test_thinking_event_alongside_tool_event() {
+        let raw = json!({
+            "hook_type": "PostToolUse",
+            "session_id": "sess-1",
+            "tool_name": "Read",
+            "extended_thinking": "the config looks wrong",
+            "_meta": {
+                "workspace_id": "my-project",
+                "terminal_session_id": "term-1",
+                "collected_at": "2026-02-20T15:00:00Z"
+            }
+        });
+
+        let event = normalize(&raw).expect("should normalize");
+        assert_eq!(event.event_type, EventType::ToolSucceeded);
+
+        let thinking = thinking_event(&event).expect("should derive thinking event");
+        assert_eq!(thinking.event_type, EventType::ThinkingUpdated);
+        assert_eq!(thinking.agent_id, event.agent_id);
+        assert_eq!(thinking.ts, event.ts);
+        assert_eq!(thinking.thinking_text.as_deref(), Some("the config looks wrong"));
+        assert_eq!(thinking.payload["source_event_id"], json!(event.id));
+    }
+
+    #[test]
+    fn test_no_thinking_event_without_text() {
+        let raw = json!({
+            "hook_type": "PostToolUse",
+            "session_id": "sess-1",
+            "tool_name": "Read",
+            "thinking": "   ",
+            "_meta": {
+                "workspace_id": "my-project",
+                "terminal_session_id": "term-1",
+                "collected_at": "2026-02-20T15:00:00Z"
+            }
+        });
+
+        let event = normalize(&raw).expect("should normalize");
+        assert!(event.thinking_text.is_none());
+        assert!(thinking_event(&event).is_none());
     }
 
     #[test]
-    fn test_fingerprint_deterministic() {
-        let fp1 = generate_fingerprint("sess-1", "Read", "2026-02-20T15:00:00Z", &json!({}));
-        let fp2 = generate_fingerprint("sess-1", "Read", "2026-02-20T15:00:00Z", &json!({}));
-        assert_eq!(fp1, fp2);
+    fn test_rules_evaluated_before_builtin_mapping() {
+        let rule = NormalizerRule {
+            name: "lint".into(),
+            hook_type: "PreToolUse".into(),
+            tool_name: Some("Bash".into()),
+            conditions: [("/command".to_string(), json!("cargo clippy"))].into(),
+            event_type: EventType::TaskProgress,
+            severity: None,
+            task_id: Some("/session_id".into()),
+            payload: [("command".to_string(), "/tool_input/command".to_string())].into(),
+        };
+        let raw = |command: &str| {
+            json!({
+                "hook_type": "PreToolUse",
+                "session_id": "sess-1",
+                "tool_name": "Bash",
+                "tool_input": {"command": command},
+                "_meta": {"workspace_id": "ws", "terminal_session_id": "t", "collected_at": "2026-02-20T15:00:00Z"}
+            })
+        };
+
+        let event = normalize_with_rules(&raw("cargo clippy"), std::slice::from_ref(&rule))
+            .expect("should normalize");
+        assert_eq!(event.event_type, EventType::TaskProgress);
+        assert_eq!(event.task_id.as_deref(), Some("sess-1"));
+        assert_eq!(event.payload["command"], "cargo clippy");
+        assert_eq!(event.payload["rule"], "lint");
+        assert_eq!(event.agent_id, "sess-1");
+
+        // 규칙에 걸리지 않으면 내장 매핑
+        let event = normalize_with_rules(&raw("ls"), &[rule]).expect("should normalize");
+        assert_eq!(event.event_type, EventType::ToolStarted);
     }
 
     #[test]
-    fn test_fingerprint_different_inputs() {
-        let fp1 = generate_fingerprint("sess-1", "Read", "2026-02-20T15:00:00Z", &json!({}));
-        let fp2 = generate_fingerprint("sess-2", "Read", "2026-02-20T15:00:00Z", &json!({}));
-        assert_ne!(fp1, fp2);
+    fn test_normalize_missing_hook_type() {
+        let raw = json!({"some": "data"});
+        let result = normalize(&raw);
+        assert!(result.is_err());
     }
 
     #[test]
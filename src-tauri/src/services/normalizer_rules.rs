@@ -0,0 +1,141 @@
+use crate::config::NormalizerRule;
+use crate::models::event::{EventType, Severity};
+
+/// 규칙 매치 결과: (EventType, Severity, payload, task_id)
+pub struct RuleMatch {
+    pub event_type: EventType,
+    pub severity: Severity,
+    pub payload: serde_json::Value,
+    pub task_id: Option<String>,
+}
+
+/// `[[normalizer.rules]]`를 위에서부터 평가하여 처음 매치된 규칙의 결과를 반환한다 (hooks-integration.md §6.3).
+/// 매치되는 규칙이 없으면 None (내장 매핑으로 진행).
+pub fn apply(rules: &[NormalizerRule], hook_type: &str, raw: &serde_json::Value) -> Option<RuleMatch> {
+    let tool_name = raw.get("tool_name").and_then(|v| v.as_str()).unwrap_or("");
+    let tool_input = raw.get("tool_input").unwrap_or(&serde_json::Value::Null);
+
+    let rule = rules.iter().find(|rule| {
+        rule.hook_type == hook_type
+            && rule
+                .tool_name
+                .as_deref()
+                .is_none_or(|pattern| glob_match(pattern, tool_name))
+            && rule
+                .conditions
+                .iter()
+                .all(|(pointer, expected)| tool_input.pointer(pointer) == Some(expected))
+    })?;
+
+    let mut payload = serde_json::json!({
+        "tool_name": tool_name,
+        "rule": rule.name,
+    });
+    for (key, pointer) in &rule.payload {
+        payload[key] = raw.pointer(pointer).cloned().unwrap_or(serde_json::Value::Null);
+    }
+
+    let task_id = rule
+        .task_id
+        .as_deref()
+        .and_then(|pointer| raw.pointer(pointer))
+        .and_then(|v| match v {
+            serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        });
+
+    Some(RuleMatch {
+        event_type: rule.event_type.clone(),
+        severity: rule.severity.clone().unwrap_or(Severity::Info),
+        payload,
+        task_id,
+    })
+}
+
+/// `*`(0자 이상), `?`(1자) 와일드카드 매치. 문자(char) 단위.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // 직전 `*`가 한 글자 더 먹도록 되감기
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn deploy_rule() -> NormalizerRule {
+        NormalizerRule {
+            name: "deploy".into(),
+            hook_type: "PreToolUse".into(),
+            tool_name: Some("mcp__deploy__*".into()),
+            conditions: BTreeMap::from([("/env".to_string(), json!("production"))]),
+            event_type: EventType::TaskStarted,
+            severity: Some(Severity::Warn),
+            task_id: Some("/tool_input/deployment_id".into()),
+            payload: BTreeMap::from([("environment".to_string(), "/tool_input/env".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("mcp__deploy__*", "mcp__deploy__start"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("Bash", "Bash"));
+        assert!(glob_match("mcp__*__run?", "mcp__ci__run1"));
+        assert!(glob_match("*a*b", "xxaxxb"));
+        assert!(!glob_match("mcp__deploy__*", "mcp__build__start"));
+        assert!(!glob_match("Bash", "BashOutput"));
+        assert!(!glob_match("run?", "run"));
+    }
+
+    #[test]
+    fn test_apply_matching_rule() {
+        let raw = json!({
+            "hook_type": "PreToolUse",
+            "tool_name": "mcp__deploy__start",
+            "tool_input": {"env": "production", "deployment_id": 42}
+        });
+        let matched = apply(&[deploy_rule()], "PreToolUse", &raw).expect("should match");
+        assert_eq!(matched.event_type, EventType::TaskStarted);
+        assert_eq!(matched.severity, Severity::Warn);
+        assert_eq!(matched.task_id.as_deref(), Some("42"));
+        assert_eq!(matched.payload["environment"], "production");
+        assert_eq!(matched.payload["tool_name"], "mcp__deploy__start");
+        assert_eq!(matched.payload["rule"], "deploy");
+    }
+
+    #[test]
+    fn test_apply_no_match() {
+        let raw = json!({
+            "tool_name": "mcp__deploy__start",
+            "tool_input": {"env": "staging"}
+        });
+        // 조건 불일치
+        assert!(apply(&[deploy_rule()], "PreToolUse", &raw).is_none());
+        // hook_type 불일치
+        let raw = json!({"tool_name": "mcp__deploy__start", "tool_input": {"env": "production"}});
+        assert!(apply(&[deploy_rule()], "PostToolUse", &raw).is_none());
+    }
+}
@@ -0,0 +1,276 @@
+//! OTLP/HTTP JSON trace → NormalizedEvent 변환 (hooks-integration.md §5.5)
+//! OpenTelemetry GenAI semantic conventions의 `gen_ai.operation.name`으로 span 종류를 판별한다.
+
+use crate::error::AppError;
+use crate::models::event::*;
+use crate::services::normalizer;
+
+/// ExportTraceServiceRequest 본문을 이벤트 목록으로 변환한다 (ts 오름차순).
+/// 도구/에이전트 span이 아닌 span은 건너뛴다. resourceSpans가 없으면 AppError::Normalize.
+pub fn convert(request: &serde_json::Value) -> Result<Vec<NormalizedEvent>, AppError> {
+    let resource_spans = request
+        .get("resourceSpans")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| AppError::Normalize("missing resourceSpans".into()))?;
+
+    let mut events = Vec::new();
+    for resource_span in resource_spans {
+        let resource_attrs = resource_span
+            .pointer("/resource/attributes")
+            .unwrap_or(&serde_json::Value::Null);
+        let scope_spans = resource_span
+            .get("scopeSpans")
+            .and_then(|v| v.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for span in scope_spans
+            .iter()
+            .filter_map(|scope| scope.get("spans").and_then(|v| v.as_array()))
+            .flatten()
+        {
+            events.extend(span_events(resource_attrs, span));
+        }
+    }
+
+    events.sort_by(|a, b| a.ts.cmp(&b.ts));
+    Ok(events)
+}
+
+/// span 하나 → 시작/종료 이벤트 쌍
+fn span_events(resource_attrs: &serde_json::Value, span: &serde_json::Value) -> Vec<NormalizedEvent> {
+    let attrs = span.get("attributes").unwrap_or(&serde_json::Value::Null);
+    let span_name = span.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let operation = attr_str(attrs, "gen_ai.operation.name")
+        .or_else(|| span_name.split_whitespace().next().map(String::from))
+        .unwrap_or_default();
+
+    let (Some(start_ts), Some(end_ts)) = (
+        unix_nano_to_rfc3339(span.get("startTimeUnixNano")),
+        unix_nano_to_rfc3339(span.get("endTimeUnixNano")),
+    ) else {
+        return Vec::new();
+    };
+    let is_error = is_error_status(span.get("status"));
+
+    let builder = EventBuilder {
+        resource_attrs,
+        attrs,
+        span,
+    };
+
+    match operation.as_str() {
+        "execute_tool" => {
+            let tool_name = attr_str(attrs, "gen_ai.tool.name").unwrap_or_else(|| {
+                span_name.trim_start_matches("execute_tool").trim().to_string()
+            });
+            let mut payload = serde_json::json!({
+                "tool_name": tool_name,
+                "span_name": span_name,
+            });
+            if let Some(arguments) = attr_str(attrs, "gen_ai.tool.call.arguments") {
+                payload["tool_input"] = serde_json::json!(arguments);
+            }
+            let started = builder.build(EventType::ToolStarted, Severity::Info, payload.clone(), start_ts);
+
+            let ended = if is_error {
+                payload["exit_code"] = serde_json::json!(attr_i64(attrs, "process.exit.code").unwrap_or(1));
+                payload["error_message"] = serde_json::json!(span
+                    .pointer("/status/message")
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .or_else(|| attr_str(attrs, "error.type"))
+                    .unwrap_or_else(|| "span status error".to_string()));
+                builder.build(EventType::ToolFailed, Severity::Error, payload, end_ts)
+            } else {
+                builder.build(EventType::ToolSucceeded, Severity::Info, payload, end_ts)
+            };
+            vec![started, ended]
+        }
+        "invoke_agent" | "create_agent" => {
+            let payload = serde_json::json!({ "span_name": span_name });
+            let severity = if is_error { Severity::Error } else { Severity::Info };
+            vec![
+                builder.build(EventType::AgentStarted, Severity::Info, payload.clone(), start_ts),
+                builder.build(EventType::AgentStopped, severity, payload, end_ts),
+            ]
+        }
+        _ => Vec::new(),
+    }
+}
+
+struct EventBuilder<'a> {
+    resource_attrs: &'a serde_json::Value,
+    attrs: &'a serde_json::Value,
+    span: &'a serde_json::Value,
+}
+
+impl EventBuilder<'_> {
+    /// 식별자 매핑:
+    /// - workspace_id: resource `mascot.workspace_id` → `service.namespace` → `unknown`
+    /// - agent_id: resource `mascot.agent_id` → span `gen_ai.agent.name` → resource `service.name`
+    /// - terminal_session_id: resource `service.instance.id` → `otlp`
+    /// - session_id: traceId
+    fn build(
+        &self,
+        event_type: EventType,
+        severity: Severity,
+        payload: serde_json::Value,
+        ts: String,
+    ) -> NormalizedEvent {
+        let workspace_id = attr_str(self.resource_attrs, "mascot.workspace_id")
+            .or_else(|| attr_str(self.resource_attrs, "service.namespace"))
+            .unwrap_or_else(|| "unknown".to_string());
+        let agent_id = attr_str(self.resource_attrs, "mascot.agent_id")
+            .or_else(|| attr_str(self.attrs, "gen_ai.agent.name"))
+            .or_else(|| attr_str(self.resource_attrs, "service.name"))
+            .unwrap_or_else(|| "unknown".to_string());
+        let terminal_session_id = attr_str(self.resource_attrs, "service.instance.id")
+            .unwrap_or_else(|| "otlp".to_string());
+        let trace_id = self
+            .span
+            .get("traceId")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+
+        NormalizedEvent {
+            id: normalizer::generate_event_id(),
+            version: "1.1".to_string(),
+            ts,
+            event_type,
+            source: EventSource::Otlp,
+            workspace_id,
+            terminal_session_id,
+            run_id: None,
+            session_id: trace_id,
+            agent_id,
+            target_agent_id: None,
+            task_id: None,
+            severity,
+            payload,
+            thinking_text: None,
+            raw: self.span.clone(),
+            redactions: Vec::new(),
+        }
+    }
+}
+
+/// OTLP KeyValue 목록에서 값을 문자열로 읽는다 (stringValue/intValue/doubleValue/boolValue)
+fn attr_str(attrs: &serde_json::Value, key: &str) -> Option<String> {
+    let value = find_attr(attrs, key)?;
+    if let Some(s) = value.get("stringValue").and_then(|v| v.as_str()) {
+        return Some(s.to_string()).filter(|s| !s.is_empty());
+    }
+    ["intValue", "doubleValue", "boolValue"]
+        .iter()
+        .find_map(|k| value.get(*k))
+        .map(|v| match v {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+}
+
+/// intValue는 JSON 인코딩에서 문자열 또는 숫자로 올 수 있다
+fn attr_i64(attrs: &serde_json::Value, key: &str) -> Option<i64> {
+    let value = find_attr(attrs, key)?.get("intValue")?;
+    value
+        .as_i64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+fn find_attr<'a>(attrs: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    attrs
+        .as_array()?
+        .iter()
+        .find(|kv| kv.get("key").and_then(|k| k.as_str()) == Some(key))?
+        .get("value")
+}
+
+/// fixed64 나노초(문자열 또는 숫자) → RFC3339 UTC (밀리초)
+fn unix_nano_to_rfc3339(value: Option<&serde_json::Value>) -> Option<String> {
+    let nanos: u64 = match value? {
+        serde_json::Value::String(s) => s.parse().ok()?,
+        serde_json::Value::Number(n) => n.as_u64()?,
+        _ => return None,
+    };
+    let secs = i64::try_from(nanos / 1_000_000_000).ok()?;
+    let dt = chrono::DateTime::from_timestamp(secs, (nanos % 1_000_000_000) as u32)?;
+    Some(dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+}
+
+/// status.code: 2 또는 "STATUS_CODE_ERROR"
+fn is_error_status(status: Option<&serde_json::Value>) -> bool {
+    match status.and_then(|s| s.get("code")) {
+        Some(serde_json::Value::Number(n)) => n.as_u64() == Some(2),
+        Some(serde_json::Value::String(s)) => s == "STATUS_CODE_ERROR",
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::validator;
+
+    fn fixture(json: &str) -> serde_json::Value {
+        serde_json::from_str(json).expect("fixture should be valid JSON")
+    }
+
+    #[test]
+    fn test_convert_tool_spans_fixture() {
+        let request = fixture(include_str!("../../tests/fixtures/otlp/tool_spans.json"));
+        let events = convert(&request).expect("should convert");
+
+        let types: Vec<_> = events.iter().map(|e| e.event_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                EventType::AgentStarted,
+                EventType::ToolStarted,
+                EventType::ToolSucceeded,
+                EventType::ToolStarted,
+                EventType::ToolFailed,
+                EventType::AgentStopped,
+            ]
+        );
+
+        for event in &events {
+            assert_eq!(event.source, EventSource::Otlp);
+            assert_eq!(event.workspace_id, "research-team");
+            assert_eq!(event.agent_id, "researcher");
+            assert_eq!(event.terminal_session_id, "host-1");
+            assert_eq!(event.session_id.as_deref(), Some("5b8efff798038103d269b633813fc60c"));
+            assert!(validator::validate(event).is_empty(), "{:?}", validator::validate(event));
+        }
+
+        assert_eq!(events[0].ts, "2026-02-20T15:00:00.000Z");
+        assert_eq!(events[1].payload["tool_name"], "web_search");
+        assert_eq!(events[2].ts, "2026-02-20T15:00:02.500Z");
+        assert_eq!(events[3].payload["tool_name"], "run_shell");
+        assert_eq!(events[4].payload["exit_code"], 127);
+        assert_eq!(events[4].payload["error_message"], "command not found: pytets");
+    }
+
+    #[test]
+    fn test_convert_service_name_fallback_fixture() {
+        let request = fixture(include_str!("../../tests/fixtures/otlp/service_name_only.json"));
+        let events = convert(&request).expect("should convert");
+
+        // chat span은 건너뛰고 도구 span만 변환
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].agent_id, "support-bot");
+        assert_eq!(events[0].workspace_id, "unknown");
+        assert_eq!(events[0].payload["tool_name"], "lookup_order");
+        assert_eq!(events[1].event_type, EventType::ToolFailed);
+        assert_eq!(events[1].payload["exit_code"], 1);
+        assert_eq!(events[1].payload["error_message"], "TimeoutError");
+    }
+
+    #[test]
+    fn test_convert_rejects_missing_resource_spans() {
+        assert!(convert(&serde_json::json!({})).is_err());
+        let empty = convert(&serde_json::json!({"resourceSpans": []})).expect("should convert");
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::models::agent::{AgentState, AgentStatus};
+use crate::models::event::EventType;
+use crate::services::state_machine::TRANSIENT_STATES;
+use serde::Serialize;
+
+/// 에이전트 하나의 상태 보정 (state-machine.md §5.2)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Repair {
+    pub agent_id: String,
+    pub from_status: AgentStatus,
+    pub to_status: AgentStatus,
+    /// 지운 대화 상대 (없었으면 None)
+    pub cleared_peer: Option<String>,
+}
+
+/// WebView 콜백을 기다리는 일시 상태나 남아 있는 대화 상대를 보정한다.
+/// last_event는 세션에서 온 마지막 이벤트 (synthetic/heartbeat 제외). 보정할 것이 없으면 None.
+pub fn plan(agent: &AgentState, last_event: Option<&EventType>) -> Option<Repair> {
+    let to_status = match agent.status {
+        // 퇴장 중이었으면 퇴장을 마친 것으로 본다
+        AgentStatus::Disappearing => AgentStatus::Offline,
+        // 대화 중이었으면 walking 전 상태로 돌아간다
+        AgentStatus::Walking | AgentStatus::Chatting | AgentStatus::Returning => agent
+            .prev_status
+            .clone()
+            .filter(|s| !TRANSIENT_STATES.contains(s))
+            .unwrap_or_else(|| settled_status(last_event)),
+        AgentStatus::Appearing | AgentStatus::Startled => settled_status(last_event),
+        _ => agent.status.clone(),
+    };
+
+    // 대화 상대는 walking/chatting/returning 동안에만 의미가 있다
+    let cleared_peer = agent.peer_agent_id.clone();
+    if to_status == agent.status && cleared_peer.is_none() {
+        return None;
+    }
+
+    Some(Repair {
+        agent_id: agent.agent_id.clone(),
+        from_status: agent.status.clone(),
+        to_status,
+        cleared_peer,
+    })
+}
+
+/// 마지막 세션 이벤트로 추정한 안정 상태
+fn settled_status(last_event: Option<&EventType>) -> AgentStatus {
+    match last_event {
+        Some(EventType::AgentStopped) => AgentStatus::Offline,
+        Some(
+            EventType::TaskStarted
+            | EventType::TaskProgress
+            | EventType::ToolStarted
+            | EventType::ToolSucceeded
+            | EventType::ToolFailed
+            | EventType::ThinkingUpdated,
+        ) => AgentStatus::Working,
+        Some(EventType::TaskCompleted) => AgentStatus::Completed,
+        Some(EventType::TaskFailed) => AgentStatus::Failed,
+        Some(EventType::AgentBlocked) => AgentStatus::PendingInput,
+        _ => AgentStatus::Idle,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(status: AgentStatus) -> AgentState {
+        AgentState {
+            agent_id: "ws/worker".into(),
+            status,
+            prev_status: None,
+            thinking_text: None,
+            current_task: None,
+            activity: None,
+            activity_summary: None,
+            consecutive_failures: 0,
+            unresponsive: false,
+            workspace_id: "ws".into(),
+            since: "2026-02-20T15:00:00Z".into(),
+            last_event_ts: "2026-02-20T15:00:00Z".into(),
+            session_id: None,
+            peer_agent_id: None,
+            home_x: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_transient_states_settle_by_last_event() {
+        let appearing = agent(AgentStatus::Appearing);
+        assert_eq!(plan(&appearing, Some(&EventType::AgentStarted)).unwrap().to_status, AgentStatus::Idle);
+        assert_eq!(plan(&appearing, Some(&EventType::ToolStarted)).unwrap().to_status, AgentStatus::Working);
+
+        let startled = agent(AgentStatus::Startled);
+        assert_eq!(plan(&startled, Some(&EventType::TaskStarted)).unwrap().to_status, AgentStatus::Working);
+        assert_eq!(plan(&startled, None).unwrap().to_status, AgentStatus::Idle);
+
+        // 퇴장 중이면 마지막 이벤트와 무관하게 offline
+        let disappearing = agent(AgentStatus::Disappearing);
+        assert_eq!(plan(&disappearing, Some(&EventType::ToolStarted)).unwrap().to_status, AgentStatus::Offline);
+    }
+
+    #[test]
+    fn test_visit_returns_to_previous_and_clears_peer() {
+        let mut chatting = agent(AgentStatus::Chatting);
+        chatting.prev_status = Some(AgentStatus::Working);
+        chatting.peer_agent_id = Some("ws/lead".into());
+        let repair = plan(&chatting, Some(&EventType::MessageSent)).expect("repair");
+        assert_eq!(repair.to_status, AgentStatus::Working);
+        assert_eq!(repair.cleared_peer.as_deref(), Some("ws/lead"));
+
+        // 상태는 정상이어도 남은 대화 상대는 지운다
+        let mut idle = agent(AgentStatus::Idle);
+        assert_eq!(plan(&idle, None), None);
+        idle.peer_agent_id = Some("ws/lead".into());
+        let repair = plan(&idle, None).expect("repair");
+        assert_eq!(repair.to_status, AgentStatus::Idle);
+        assert_eq!(repair.cleared_peer.as_deref(), Some("ws/lead"));
+    }
+}
@@ -0,0 +1,167 @@
+use crate::config::RetentionConfig;
+use crate::error::AppError;
+use crate::models::maintenance::{CompactReport, PruneReport};
+use crate::state::AppState;
+use crate::storage::db::DbPool;
+use crate::storage::events_repo::EventsRepo;
+use crate::storage::maintenance_repo::MaintenanceRepo;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// 보존 정리 서비스 메인 루프. 시작 직후 한 번, 이후 prune_interval_secs마다
+/// 보존 정책을 적용하고 incremental VACUUM으로 빈 페이지를 반환한다.
+pub async fn run_retention(state: AppState) {
+    let config = &state.config.retention;
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(config.prune_interval_secs));
+    tracing::info!(
+        "retention service started (max_age_days={}, max_events={}, raw_json_max_age_days={})",
+        config.max_age_days, config.max_events, config.raw_json_max_age_days,
+    );
+
+    loop {
+        interval.tick().await;
+        match prune(&state.db, config, Utc::now()) {
+            Ok(report) if report != PruneReport::default() => {
+                tracing::info!(
+                    "retention: expired={} over_limit={} raw_stripped={}",
+                    report.expired, report.over_limit, report.raw_stripped,
+                );
+                if let Err(e) = MaintenanceRepo::new(state.db.clone()).incremental_vacuum() {
+                    tracing::warn!("retention: incremental vacuum failed: {e}");
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("retention: prune failed: {e}"),
+        }
+    }
+}
+
+/// 보존 정책 적용: 기간 초과 삭제 → 행 수 초과 삭제 → 오래된 raw_json 제거.
+/// 배치 단위로 나눠 실행해 긴 삭제 중에도 ingest가 DB 락을 얻을 수 있다.
+pub fn prune(db: &DbPool, config: &RetentionConfig, now: DateTime<Utc>) -> Result<PruneReport, AppError> {
+    let repo = EventsRepo::new(db.clone());
+    let batch = config.prune_batch_size;
+    let expire_before = cutoff(now, config.max_age_days);
+    let strip_before = cutoff(now, config.raw_json_max_age_days);
+
+    Ok(PruneReport {
+        expired: drain(batch, || repo.delete_before(&expire_before, batch))?,
+        over_limit: drain(batch, || repo.delete_beyond(config.max_events, batch))?,
+        raw_stripped: drain(batch, || repo.strip_raw_before(&strip_before, batch))?,
+    })
+}
+
+/// 수동 compact: 보존 정책을 바로 적용하고 전체 VACUUM으로 파일을 다시 쓴다
+pub fn compact(db: &DbPool, config: &RetentionConfig, now: DateTime<Utc>) -> Result<CompactReport, AppError> {
+    let maintenance = MaintenanceRepo::new(db.clone());
+    let before = maintenance.stats()?;
+    let pruned = prune(db, config, now)?;
+    maintenance.vacuum()?;
+    let after = maintenance.stats()?;
+    tracing::info!(
+        "retention: compacted {} → {} bytes (pruned {pruned:?})",
+        before.size_bytes, after.size_bytes,
+    );
+    Ok(CompactReport { pruned, before, after })
+}
+
+/// 한 배치가 꽉 차지 않을 때까지 반복하고 처리한 행 수를 합산한다
+fn drain(batch: usize, mut step: impl FnMut() -> Result<u64, AppError>) -> Result<u64, AppError> {
+    let mut total = 0;
+    loop {
+        let n = step()?;
+        total += n;
+        if n < batch as u64 {
+            return Ok(total);
+        }
+    }
+}
+
+/// events.ts와 같은 형식의 기준 시각
+fn cutoff(now: DateTime<Utc>, days: u32) -> String {
+    (now - chrono::Duration::days(i64::from(days))).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::event::*;
+    use crate::storage::db::init_db_in_memory;
+
+    fn insert(repo: &EventsRepo, id: &str, ts: &str) {
+        let event = NormalizedEvent {
+            id: id.to_string(),
+            version: "1.1".to_string(),
+            ts: ts.to_string(),
+            event_type: EventType::ToolStarted,
+            source: EventSource::Hook,
+            workspace_id: "ws".to_string(),
+            terminal_session_id: "term".to_string(),
+            run_id: None,
+            session_id: None,
+            agent_id: "ws/worker".to_string(),
+            target_agent_id: None,
+            task_id: None,
+            severity: Severity::Info,
+            payload: serde_json::json!({}),
+            thinking_text: None,
+            raw: serde_json::json!({"tool_input": "x".repeat(100)}),
+            redactions: Vec::new(),
+        };
+        let fingerprint = Fingerprint { value: format!("fp-{id}"), version: 2, legacy_value: None };
+        assert!(repo.insert(&event, &fingerprint).expect("insert"));
+    }
+
+    fn config(max_events: u64) -> RetentionConfig {
+        RetentionConfig {
+            max_age_days: 30,
+            max_events,
+            raw_json_max_age_days: 3,
+            prune_interval_secs: 3600,
+            prune_batch_size: 2,
+        }
+    }
+
+    #[test]
+    fn test_prune_applies_age_count_and_raw_json_retention() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = EventsRepo::new(db.clone());
+        let now = DateTime::parse_from_rfc3339("2026-03-31T12:00:00Z").expect("ts").with_timezone(&Utc);
+        // 40일 전 3개, 10일 전 3개, 1시간 전 2개
+        for i in 0..3 {
+            insert(&repo, &format!("old-{i}"), &format!("2026-02-19T12:00:0{i}Z"));
+            insert(&repo, &format!("mid-{i}"), &format!("2026-03-21T12:00:0{i}+00:00"));
+        }
+        insert(&repo, "new-0", "2026-03-31T11:00:00Z");
+        insert(&repo, "new-1", "2026-03-31T11:00:01Z");
+
+        let report = prune(&db, &config(4), now).expect("prune");
+        // 배치(2)보다 많아도 끝까지 지운다
+        assert_eq!(report, PruneReport { expired: 3, over_limit: 1, raw_stripped: 2 });
+
+        let stats = MaintenanceRepo::new(db.clone()).stats().expect("stats");
+        assert_eq!(stats.events, 4);
+        assert_eq!(stats.events_with_raw, 2, "1시간 전 이벤트만 raw_json이 남는다");
+        assert_eq!(stats.oldest_event_ts.as_deref(), Some("2026-03-21T12:00:01+00:00"));
+
+        // 다시 돌려도 할 일이 없다
+        assert_eq!(prune(&db, &config(4), now).expect("prune"), PruneReport::default());
+    }
+
+    #[test]
+    fn test_compact_reports_sizes() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = EventsRepo::new(db.clone());
+        for i in 0..50 {
+            insert(&repo, &format!("evt-{i:02}"), &format!("2026-01-01T00:00:{i:02}Z"));
+        }
+        let now = DateTime::parse_from_rfc3339("2026-03-31T12:00:00Z").expect("ts").with_timezone(&Utc);
+
+        let report = compact(&db, &config(100), now).expect("compact");
+        assert_eq!(report.pruned.expired, 50);
+        assert_eq!(report.before.events, 50);
+        assert_eq!(report.after.events, 0);
+        assert!(report.after.size_bytes <= report.before.size_bytes);
+        assert_eq!(report.after.free_bytes, 0);
+    }
+}
@@ -0,0 +1,377 @@
+use crate::config::AppConfig;
+use crate::models::agent::AgentState;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// 마감 처리에 실패한 에이전트를 다시 꺼내기까지 기다리는 시간
+const RETRY_AFTER_MS: i64 = 1_000;
+
+/// 마감된 에이전트를 처리한 결과
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeadlineOutcome {
+    /// 이벤트를 적용함. 다음 마감은 publish가 잡는다
+    Applied,
+    /// 적용할 규칙이 없음 (guard 불만족 등)
+    Skipped,
+    /// 기록/적용에 실패함. 같은 마감을 잠시 뒤 다시 시도한다
+    Failed,
+}
+
+/// 현재 시각. 테스트에서는 직접 움직이는 시계를 주입한다
+pub trait Clock: Send + Sync {
+    fn now(&self) -> chrono::DateTime<chrono::Utc>;
+}
+
+/// 실제 시스템 시계
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now()
+    }
+}
+
+/// (마감 epoch ms, agent_id) 최소 힙. 에이전트마다 마감은 하나이며,
+/// 다시 잡으면 이전 항목은 꺼낼 때 버린다 (지연 삭제).
+#[derive(Debug, Default)]
+pub struct DeadlineQueue {
+    heap: BinaryHeap<Reverse<(i64, String)>>,
+    deadlines: HashMap<String, i64>,
+}
+
+impl DeadlineQueue {
+    /// 에이전트의 마감을 바꾼다. None이면 마감 없음 (대기 중인 타이머 취소)
+    pub fn schedule(&mut self, agent_id: &str, deadline_ms: Option<i64>) {
+        match deadline_ms {
+            Some(deadline_ms) => {
+                self.deadlines.insert(agent_id.to_string(), deadline_ms);
+                self.heap.push(Reverse((deadline_ms, agent_id.to_string())));
+            }
+            None => {
+                self.deadlines.remove(agent_id);
+            }
+        }
+        // 이벤트가 잦은 에이전트의 버려진 항목이 쌓이지 않도록 가끔 다시 만든다
+        if self.heap.len() > 64 && self.heap.len() > self.deadlines.len() * 4 {
+            self.heap = self
+                .deadlines
+                .iter()
+                .map(|(agent_id, deadline_ms)| Reverse((*deadline_ms, agent_id.clone())))
+                .collect();
+        }
+    }
+
+    /// 가장 이른 마감
+    pub fn next_deadline(&mut self) -> Option<i64> {
+        self.discard_stale();
+        self.heap.peek().map(|Reverse((deadline_ms, _))| *deadline_ms)
+    }
+
+    /// now_ms까지 마감된 에이전트를 마감 순서대로 꺼낸다 (꺼낸 에이전트는 마감 없음)
+    pub fn pop_due(&mut self, now_ms: i64) -> Vec<String> {
+        let mut due = Vec::new();
+        while self.next_deadline().is_some_and(|deadline_ms| deadline_ms <= now_ms) {
+            if let Some(Reverse((_, agent_id))) = self.heap.pop() {
+                self.deadlines.remove(&agent_id);
+                due.push(agent_id);
+            }
+        }
+        due
+    }
+
+    /// 마감이 잡힌 에이전트 수
+    pub fn scheduled(&self) -> usize {
+        self.deadlines.len()
+    }
+
+    fn discard_stale(&mut self) {
+        while let Some(Reverse((deadline_ms, agent_id))) = self.heap.peek() {
+            if self.deadlines.get(agent_id) == Some(deadline_ms) {
+                break;
+            }
+            self.heap.pop();
+        }
+    }
+}
+
+/// 타이머 규칙(state-machine.md §5)과 생존 감지(§5.1)의 마감 스케줄러.
+/// 전이/이벤트 처리 후 reschedule로 에이전트의 다음 마감을 잡고,
+/// heartbeat는 가장 이른 마감까지 잠들었다가 마감된 에이전트만 DB에서 읽는다.
+pub struct Scheduler {
+    clock: Arc<dyn Clock>,
+    queue: Mutex<DeadlineQueue>,
+    wake: Notify,
+}
+
+impl Scheduler {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            queue: Mutex::new(DeadlineQueue::default()),
+            wake: Notify::new(),
+        }
+    }
+
+    pub fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.clock.now()
+    }
+
+    /// 상태가 저장된 뒤 호출한다. 이미 지난 마감이면 바로 만료된다.
+    pub fn reschedule(&self, agent: &AgentState, config: &AppConfig) {
+        self.schedule(&agent.agent_id, next_deadline(agent, config, None));
+    }
+
+    /// 마감됐지만 적용할 규칙이 없던 에이전트 (guard 불만족 등). now 이후의 다음 마감을 잡는다
+    pub fn defer(&self, agent: &AgentState, config: &AppConfig, now_ms: i64) {
+        self.schedule(&agent.agent_id, next_deadline(agent, config, Some(now_ms)));
+    }
+
+    /// 마감 처리 결과로 다음 마감을 잡는다. 꺼낸 마감은 큐에 없으므로 실패하면 다시 넣는다
+    pub fn settle(
+        &self,
+        agent: &AgentState,
+        config: &AppConfig,
+        outcome: DeadlineOutcome,
+        now_ms: i64,
+    ) {
+        match outcome {
+            DeadlineOutcome::Applied => {}
+            DeadlineOutcome::Skipped => self.defer(agent, config, now_ms),
+            DeadlineOutcome::Failed => self.schedule(&agent.agent_id, Some(now_ms + RETRY_AFTER_MS)),
+        }
+    }
+
+    /// 지금까지 마감된 에이전트
+    pub fn pop_due(&self) -> Vec<String> {
+        let now_ms = self.clock.now().timestamp_millis();
+        match self.queue.lock() {
+            Ok(mut queue) => queue.pop_due(now_ms),
+            Err(e) => {
+                tracing::warn!("scheduler: lock poisoned: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// 가장 이른 마감
+    pub fn next_deadline(&self) -> Option<i64> {
+        match self.queue.lock() {
+            Ok(mut queue) => queue.next_deadline(),
+            Err(e) => {
+                tracing::warn!("scheduler: lock poisoned: {e}");
+                None
+            }
+        }
+    }
+
+    /// 마감이 잡힌 에이전트 수
+    pub fn scheduled(&self) -> usize {
+        self.queue.lock().map(|queue| queue.scheduled()).unwrap_or_default()
+    }
+
+    /// deadline_ms까지 (없으면 다음 reschedule까지) 기다린다. 마감이 바뀌면 일찍 깨어난다.
+    /// 대화 대기열에 줄을 선 에이전트도 reschedule되므로 새 대기 만료 시각을 놓치지 않는다.
+    pub async fn wait_until(&self, deadline_ms: Option<i64>) {
+        match deadline_ms {
+            Some(deadline_ms) => {
+                let wait_ms = (deadline_ms - self.clock.now().timestamp_millis()).max(0) as u64;
+                tokio::select! {
+                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(wait_ms)) => {}
+                    _ = self.wake.notified() => {}
+                }
+            }
+            None => self.wake.notified().await,
+        }
+    }
+
+    fn schedule(&self, agent_id: &str, deadline_ms: Option<i64>) {
+        match self.queue.lock() {
+            Ok(mut queue) => queue.schedule(agent_id, deadline_ms),
+            Err(e) => {
+                tracing::warn!("scheduler: lock poisoned: {e}");
+                return;
+            }
+        }
+        self.wake.notify_one();
+    }
+}
+
+/// 에이전트의 다음 마감 (epoch ms). 현재 상태의 타이머 규칙과 감시 상태의 생존 감지 중 가장 이른 것.
+/// after_ms가 있으면 그보다 늦은 마감만 본다.
+pub fn next_deadline(agent: &AgentState, config: &AppConfig, after_ms: Option<i64>) -> Option<i64> {
+    let mut deadlines = Vec::new();
+
+    if let Some(since_ms) = parse_ms(&agent.since) {
+        deadlines.extend(
+            config
+                .state_machine
+                .timers
+                .iter()
+                .filter(|timer| timer.status == agent.status)
+                .map(|timer| since_ms + timer.after_secs as i64 * 1000),
+        );
+    }
+
+    let liveness = &config.liveness;
+    if liveness.statuses.contains(&agent.status) {
+        if let Some(last_ms) = parse_ms(&agent.last_event_ts) {
+            if !agent.unresponsive {
+                deadlines.push(last_ms + liveness.unresponsive_after_secs as i64 * 1000);
+            }
+            deadlines.push(last_ms + liveness.disappear_after_secs as i64 * 1000);
+        }
+    }
+
+    deadlines
+        .into_iter()
+        .filter(|deadline_ms| after_ms.is_none_or(|after_ms| *deadline_ms > after_ms))
+        .min()
+}
+
+/// 마감이 실제 경과 시간보다 이르지 않도록 ms 미만은 올린다
+fn parse_ms(ts: &str) -> Option<i64> {
+    let dt = chrono::DateTime::parse_from_rfc3339(ts).ok()?;
+    let round_up = dt.timestamp_subsec_nanos() % 1_000_000 != 0;
+    Some(dt.timestamp_millis() + i64::from(round_up))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agent::AgentStatus;
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    /// 테스트에서 직접 움직이는 시계
+    struct ManualClock(AtomicI64);
+
+    impl ManualClock {
+        fn at(ts: &str) -> Arc<Self> {
+            Arc::new(Self(AtomicI64::new(parse_ms(ts).expect("ts"))))
+        }
+
+        fn advance_secs(&self, secs: i64) {
+            self.0.fetch_add(secs * 1000, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> chrono::DateTime<chrono::Utc> {
+            chrono::DateTime::from_timestamp_millis(self.0.load(Ordering::SeqCst)).expect("ms")
+        }
+    }
+
+    fn config() -> AppConfig {
+        toml::from_str(include_str!("../../config.toml")).expect("config.toml")
+    }
+
+    fn agent(agent_id: &str, status: AgentStatus) -> AgentState {
+        AgentState {
+            agent_id: agent_id.into(),
+            status,
+            prev_status: None,
+            thinking_text: None,
+            current_task: None,
+            activity: None,
+            activity_summary: None,
+            consecutive_failures: 0,
+            unresponsive: false,
+            workspace_id: "ws".into(),
+            since: "2026-02-20T15:00:00Z".into(),
+            last_event_ts: "2026-02-20T15:00:00Z".into(),
+            session_id: None,
+            peer_agent_id: None,
+            home_x: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_deadline_queue_pops_in_order_and_drops_rescheduled() {
+        let mut queue = DeadlineQueue::default();
+        queue.schedule("a", Some(300));
+        queue.schedule("b", Some(100));
+        queue.schedule("c", Some(200));
+        // a를 앞당기고 c는 취소
+        queue.schedule("a", Some(50));
+        queue.schedule("c", None);
+
+        assert_eq!(queue.next_deadline(), Some(50));
+        assert_eq!(queue.pop_due(99), vec!["a".to_string()]);
+        assert_eq!(queue.pop_due(1_000), vec!["b".to_string()]);
+        assert_eq!(queue.next_deadline(), None);
+        assert_eq!(queue.scheduled(), 0);
+    }
+
+    #[test]
+    fn test_next_deadline_uses_timers_and_liveness() {
+        let config = config();
+        let since_ms = parse_ms("2026-02-20T15:00:00Z").unwrap();
+
+        // idle 120초 타이머
+        let idle = agent("idle", AgentStatus::Idle);
+        assert_eq!(next_deadline(&idle, &config, None), Some(since_ms + 120_000));
+        // startled는 30초 규칙 두 개, 이후로는 없음
+        let startled = agent("startled", AgentStatus::Startled);
+        assert_eq!(next_deadline(&startled, &config, None), Some(since_ms + 30_000));
+        assert_eq!(next_deadline(&startled, &config, Some(since_ms + 30_000)), None);
+
+        // working은 타이머가 없고 생존 감지만: 응답 없음 표시 후에는 퇴장 마감
+        let mut working = agent("working", AgentStatus::Working);
+        assert_eq!(next_deadline(&working, &config, None), Some(since_ms + 300_000));
+        working.unresponsive = true;
+        assert_eq!(next_deadline(&working, &config, None), Some(since_ms + 1_800_000));
+
+        let offline = agent("offline", AgentStatus::Offline);
+        assert_eq!(next_deadline(&offline, &config, None), None);
+    }
+
+    #[test]
+    fn test_scheduler_fires_on_time_with_injected_clock() {
+        let config = config();
+        let clock = ManualClock::at("2026-02-20T15:00:00Z");
+        let scheduler = Scheduler::new(clock.clone());
+
+        scheduler.reschedule(&agent("chat", AgentStatus::Chatting), &config);
+        scheduler.reschedule(&agent("idle", AgentStatus::Idle), &config);
+        assert_eq!(scheduler.scheduled(), 2);
+
+        clock.advance_secs(4);
+        assert!(scheduler.pop_due().is_empty());
+        clock.advance_secs(1);
+        assert_eq!(scheduler.pop_due(), vec!["chat".to_string()]);
+
+        // idle이 그 사이 working으로 바뀌면 idle 타이머 대신 생존 감지 마감
+        let mut working = agent("idle", AgentStatus::Working);
+        working.since = "2026-02-20T15:01:00Z".into();
+        working.last_event_ts = "2026-02-20T15:01:00Z".into();
+        scheduler.reschedule(&working, &config);
+        clock.advance_secs(200);
+        assert!(scheduler.pop_due().is_empty());
+        clock.advance_secs(155);
+        assert_eq!(scheduler.pop_due(), vec!["idle".to_string()]);
+    }
+
+    #[test]
+    fn test_failed_deadline_is_retried() {
+        let config = config();
+        let clock = ManualClock::at("2026-02-20T15:00:00Z");
+        let scheduler = Scheduler::new(clock.clone());
+        let startled = agent("startled", AgentStatus::Startled);
+        scheduler.reschedule(&startled, &config);
+
+        // 30초 타이머 적용이 한 번 실패하면 1초 뒤 다시 꺼낸다
+        clock.advance_secs(30);
+        assert_eq!(scheduler.pop_due(), vec!["startled".to_string()]);
+        let now_ms = scheduler.now().timestamp_millis();
+        scheduler.settle(&startled, &config, DeadlineOutcome::Failed, now_ms);
+        assert!(scheduler.pop_due().is_empty());
+        clock.advance_secs(1);
+        assert_eq!(scheduler.pop_due(), vec!["startled".to_string()]);
+
+        // 적용할 규칙이 없을 때만 지난 마감을 건너뛴다 (startled는 30초 뒤 마감이 없음)
+        let now_ms = scheduler.now().timestamp_millis();
+        scheduler.settle(&startled, &config, DeadlineOutcome::Skipped, now_ms);
+        assert_eq!(scheduler.scheduled(), 0);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::config::StateMachineConfig;
+use crate::models::agent::AgentStatus;
+use crate::models::transition::{Pattern, TransitionGuard, TransitionTarget};
+use serde::Deserialize;
+
+/// 다이어그램 출력 형식
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagramFormat {
+    Mermaid,
+    Dot,
+}
+
+/// 상태 간 간선 하나. 같은 (from, to, timer) 쌍의 이벤트는 한 간선에 모은다
+#[derive(Debug, PartialEq)]
+struct Edge {
+    from: AgentStatus,
+    to: AgentStatus,
+    labels: Vec<String>,
+    timer: bool,
+}
+
+/// 전이 테이블 + 타이머 전이로 다이어그램을 만든다 (state-machine.md §4.1).
+/// docs의 다이어그램은 이 출력으로 다시 생성한다.
+pub fn render(config: &StateMachineConfig, format: DiagramFormat) -> String {
+    let edges = collect_edges(config);
+    match format {
+        DiagramFormat::Mermaid => mermaid(&edges),
+        DiagramFormat::Dot => dot(&edges),
+    }
+}
+
+fn collect_edges(config: &StateMachineConfig) -> Vec<Edge> {
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut add = |from: &AgentStatus, to: &AgentStatus, label: String, timer: bool| {
+        match edges
+            .iter_mut()
+            .find(|e| e.from == *from && e.to == *to && e.timer == timer)
+        {
+            Some(edge) => {
+                if !edge.labels.contains(&label) {
+                    edge.labels.push(label);
+                }
+            }
+            None => edges.push(Edge {
+                from: from.clone(),
+                to: to.clone(),
+                labels: vec![label],
+                timer,
+            }),
+        }
+    };
+
+    // "previous"로 복귀할 수 있는 상태 = walking으로 들어오는 전이의 출발 상태
+    let walking_sources: Vec<AgentStatus> = config
+        .transitions
+        .iter()
+        .filter(|r| r.to == TransitionTarget::Status(AgentStatus::Walking))
+        .flat_map(|r| expand(&r.from, r.guard))
+        .collect();
+
+    for rule in &config.transitions {
+        let event = match &rule.event {
+            Pattern::Any => "*".to_string(),
+            Pattern::Is(event_type) => snake(event_type),
+        };
+        let label = match rule.guard {
+            // online은 "*" 전개에서 offline을 빼는 것으로 표현된다
+            Some(guard) if guard != TransitionGuard::Online => format!("{event} [{}]", snake(&guard)),
+            _ => event,
+        };
+        for from in expand(&rule.from, rule.guard) {
+            match &rule.to {
+                TransitionTarget::Status(to) => add(&from, to, label.clone(), false),
+                TransitionTarget::Previous => {
+                    for to in &walking_sources {
+                        add(&from, to, format!("{label} (previous)"), false);
+                    }
+                }
+            }
+        }
+    }
+
+    for timer in &config.timers {
+        let label = match timer.guard {
+            Some(guard) => format!("timer {}s [{}]", timer.after_secs, snake(&guard)),
+            None => format!("timer {}s", timer.after_secs),
+        };
+        match &timer.to_status {
+            TransitionTarget::Status(to) => add(&timer.status, to, label, true),
+            TransitionTarget::Previous => {
+                for to in &walking_sources {
+                    add(&timer.status, to, format!("{label} (previous)"), true);
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+/// from 패턴을 실제 상태 목록으로 전개
+fn expand(from: &Pattern<AgentStatus>, guard: Option<TransitionGuard>) -> Vec<AgentStatus> {
+    match from {
+        Pattern::Is(status) => vec![status.clone()],
+        Pattern::Any => AgentStatus::ALL
+            .iter()
+            .filter(|s| guard != Some(TransitionGuard::Online) || **s != AgentStatus::Offline)
+            .cloned()
+            .collect(),
+    }
+}
+
+fn snake<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+fn mermaid(edges: &[Edge]) -> String {
+    let mut out = String::from("stateDiagram-v2\n    [*] --> offline\n");
+    for edge in edges {
+        out.push_str(&format!(
+            "    {} --> {}: {}\n",
+            snake(&edge.from),
+            snake(&edge.to),
+            edge.labels.join(", ")
+        ));
+    }
+    out
+}
+
+fn dot(edges: &[Edge]) -> String {
+    let mut out = String::from(
+        "digraph agent_state {\n    rankdir=LR;\n    node [shape=box, style=rounded];\n    offline [peripheries=2];\n",
+    );
+    for edge in edges {
+        let label = edge.labels.join("\\n").replace('"', "\\\"");
+        let style = if edge.timer { ", style=dashed" } else { "" };
+        out.push_str(&format!(
+            "    {} -> {} [label=\"{label}\"{style}];\n",
+            snake(&edge.from),
+            snake(&edge.to),
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StateMachineConfig {
+        let config: crate::config::AppConfig =
+            toml::from_str(include_str!("../../config.toml")).expect("config.toml");
+        config.state_machine
+    }
+
+    #[test]
+    fn test_mermaid_includes_table_guards_timers_and_previous() {
+        let out = render(&config(), DiagramFormat::Mermaid);
+        assert!(out.starts_with("stateDiagram-v2\n"));
+        assert!(out.contains("    idle --> working: task_started, tool_started\n"));
+        assert!(out.contains("    startled --> working: startled_done [has_task]\n"));
+        assert!(out.contains("    startled --> idle: startled_done\n"));
+        assert!(out.contains("    returning --> working: arrive_at_home (previous)\n"));
+        assert!(out.contains("    idle --> resting: timer 120s\n"));
+        assert!(out.contains("    startled --> working: timer 30s [has_task]\n"));
+        assert!(out.contains("    returning --> idle: timer 60s (previous)\n"));
+        assert!(out.contains("    working --> disappearing: agent_stopped\n"));
+        assert!(!out.contains("offline --> disappearing"));
+    }
+
+    #[test]
+    fn test_dot_is_well_formed() {
+        let out = render(&config(), DiagramFormat::Dot);
+        assert!(out.starts_with("digraph agent_state {\n"));
+        assert!(out.ends_with("}\n"));
+        assert!(out.contains(
+            "    chatting -> returning [label=\"timer 5s\", style=dashed];\n"
+        ));
+        assert!(out.contains("    working -> failed [label=\"task_failed\\ntool_failed [failure_fatal]\"];\n"));
+    }
+
+    #[test]
+    fn test_docs_diagram_in_sync() {
+        // docs/mascot-state-machine.md의 다이어그램은 이 출력으로 다시 생성한다 (§4.1)
+        let docs = include_str!("../../../docs/mascot-state-machine.md").replace("\r\n", "\n");
+        let expected = format!("```mermaid\n{}```", render(&config(), DiagramFormat::Mermaid));
+        assert!(
+            docs.contains(&expected),
+            "state diagram in docs is stale; regenerate with GET /api/state-diagram?format=mermaid:\n{expected}"
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use crate::config::StateMachineConfig;
 use crate::models::agent::{AgentState, AgentStatus};
-use crate::models::event::{EventType, NormalizedEvent};
+use crate::models::event::{EventSource, EventType, FailureClass, NormalizedEvent};
+use crate::models::transition::{
+    Pattern, TimerRule, TransitionGuard, TransitionRule, TransitionTarget,
+};
 
 /// 전이 결과
 #[derive(Debug)]
@@ -9,174 +12,341 @@ pub enum TransitionResult {
     Changed {
         prev_status: AgentStatus,
         new_status: AgentStatus,
+        /// 이전 상태에 들어간 시각 (전이 전 since)
+        prev_since: String,
     },
-    /// 상태 변경 없음 (매트릭스에 없는 조합 or heartbeat)
+    /// 같은 상태로의 전이. 상태와 since는 그대로 두고 표시 정보(활동 요약 등)만 바뀜
+    Updated,
+    /// 상태 변경 없음 (전이 테이블에 없는 조합 or heartbeat)
     NoOp,
 }
 
+/// 애니메이션/이동 중인 일시 상태. 반드시 빠져나가는 전이가 있어야 한다
+pub(crate) const TRANSIENT_STATES: &[AgentStatus] = &[
+    AgentStatus::Appearing,
+    AgentStatus::Startled,
+    AgentStatus::Walking,
+    AgentStatus::Chatting,
+    AgentStatus::Returning,
+    AgentStatus::Disappearing,
+];
+
 /// 이벤트를 처리하여 에이전트 상태를 전이한다 (state-machine.md §4)
+/// 전이는 config의 전이 테이블에서 처음 매치한 항목으로 결정한다.
+/// timer_elapsed는 전이 테이블 대신 타이머 규칙으로 결정한다 (§5).
 pub fn on_event(
     event: &NormalizedEvent,
     state: &mut AgentState,
     config: &StateMachineConfig,
-    consecutive_failures: u32,
 ) -> TransitionResult {
     let prev = state.status.clone();
 
-    // last_event_ts는 항상 갱신
-    state.last_event_ts = event.ts.clone();
+    // 세션에서 온 이벤트만 생존 신호로 본다 (§5.1). synthetic 이벤트는 last_event_ts를 바꾸지 않는다
+    if event.source != EventSource::Synthetic {
+        state.last_event_ts = event.ts.clone();
+        state.unresponsive = false;
+    }
 
     // Heartbeat: 타임스탬프만 갱신, 상태 불변
     if event.event_type == EventType::Heartbeat {
         return TransitionResult::NoOp;
     }
 
-    // 전역: agent_stopped → disappearing (모든 상태에서, offline 제외)
-    if event.event_type == EventType::AgentStopped && prev != AgentStatus::Offline {
-        return apply_transition(state, AgentStatus::Disappearing, &event.ts, &prev);
+    // 응답 없음 표시만 하고 상태는 그대로 (퇴장은 이후 agent_stopped로)
+    if event.event_type == EventType::AgentUnresponsive {
+        state.unresponsive = true;
+        return TransitionResult::NoOp;
     }
 
-    // Offline 에이전트에게 활동 이벤트가 오면 → Appearing
-    // AgentStarted뿐 아니라 어떤 hook type이든 첫 이벤트로 등장 트리거
-    // (Heartbeat과 AgentStopped는 이미 위에서 처리됨)
-    if prev == AgentStatus::Offline {
-        return apply_transition(state, AgentStatus::Appearing, &event.ts, &prev);
+    // 상태 보정 (§5.2): 전이 테이블과 무관하게 payload.to_status로 옮기고 대화 상대를 지운다
+    if event.event_type == EventType::StateRepaired {
+        state.peer_agent_id = None;
+        state.prev_status = None;
+        let next = event
+            .payload
+            .get("to_status")
+            .and_then(|v| serde_json::from_value::<AgentStatus>(v.clone()).ok());
+        return match next {
+            Some(next) if next != prev => apply_transition(state, next, &event.ts, &prev),
+            _ => TransitionResult::NoOp,
+        };
     }
 
-    // 전이 매트릭스 조회
-    if let Some(next) = find_transition(&prev, &event.event_type, event, config, consecutive_failures) {
-        // 특수 처리: walking 전 prev_status 저장
-        if next == AgentStatus::Walking {
-            state.prev_status = Some(prev.clone());
-            state.peer_agent_id = event.target_agent_id.clone();
+    // 연속 실패 카운터: 현재 이벤트까지 포함한 값으로 실패를 분류한다 (§4.3)
+    match event.event_type {
+        EventType::ToolFailed | EventType::TaskFailed => {
+            state.consecutive_failures = state.consecutive_failures.saturating_add(1);
         }
+        EventType::ToolSucceeded | EventType::TaskCompleted => state.consecutive_failures = 0,
+        _ => {}
+    }
 
-        // thinking 텍스트 갱신
-        if event.thinking_text.is_some() {
-            state.thinking_text = event.thinking_text.clone();
+    let Some(target) = resolve(state, event, config) else {
+        tracing::debug!(
+            "transition_ignored: agent={} {:?} + {:?}",
+            state.agent_id,
+            prev,
+            event.event_type,
+        );
+        return TransitionResult::NoOp;
+    };
+
+    let next = match target {
+        TransitionTarget::Status(status) => status,
+        // walking 전 상태로 복귀
+        TransitionTarget::Previous => {
+            let next = state.prev_status.take().unwrap_or(AgentStatus::Idle);
+            state.peer_agent_id = None;
+            next
         }
+    };
 
-        // 작업 요약 갱신
-        if let Some(tool_name) = event.payload.get("tool_name").and_then(|v| v.as_str()) {
-            state.current_task = Some(tool_name.to_string());
-        }
+    // 특수 처리: walking 전 prev_status 저장
+    if next == AgentStatus::Walking {
+        state.prev_status = Some(prev.clone());
+        state.peer_agent_id = event.target_agent_id.clone();
+    }
 
-        return apply_transition(state, next, &event.ts, &prev);
+    // thinking 텍스트 갱신
+    if event.thinking_text.is_some() {
+        state.thinking_text = event.thinking_text.clone();
     }
 
-    // 특수: startled + startled_done (조건부 전이)
-    if prev == AgentStatus::Startled && event.event_type == EventType::StartledDone {
-        let next = if state.current_task.is_some() {
-            AgentStatus::Working
-        } else {
-            AgentStatus::Idle
-        };
-        return apply_transition(state, next, &event.ts, &prev);
+    // 작업 요약 갱신 (activity는 ingest의 activity::annotate가 payload에 채운다)
+    if let Some(tool_name) = event.payload.get("tool_name").and_then(|v| v.as_str()) {
+        state.current_task = Some(tool_name.to_string());
+        state.activity = event
+            .payload
+            .get("activity")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+        state.activity_summary = event
+            .payload
+            .get("activity_summary")
+            .and_then(|v| v.as_str())
+            .map(String::from);
     }
 
-    // 특수: returning + arrive_at_home (prev_status 복원)
-    if prev == AgentStatus::Returning && event.event_type == EventType::ArriveAtHome {
-        let next = state.prev_status.clone().unwrap_or(AgentStatus::Idle);
-        state.prev_status = None;
-        state.peer_agent_id = None;
-        return apply_transition(state, next, &event.ts, &prev);
+    // 재시도 가능한 실패: working을 유지하고 "retrying" 표시
+    if event.event_type == EventType::ToolFailed && next == AgentStatus::Working {
+        let summary = state.activity_summary.as_deref().or(state.current_task.as_deref());
+        state.activity_summary = Some(match summary {
+            Some(summary) => format!("Retrying: {summary}"),
+            None => "Retrying".to_string(),
+        });
     }
 
-    // no-op
-    tracing::debug!(
-        "transition_ignored: agent={} {:?} + {:?}",
-        state.agent_id,
-        prev,
-        event.event_type,
-    );
-    TransitionResult::NoOp
+    // 같은 상태로의 전이 (working + tool_started, 재시도 등): 도구 호출마다 since가 초기화되고
+    // 전이 이력이 쌓이지 않도록 전이로 치지 않는다
+    if next == prev {
+        return TransitionResult::Updated;
+    }
+
+    apply_transition(state, next, &event.ts, &prev)
 }
 
-/// 전이 매트릭스 (state-machine.md §4.1)
-fn find_transition(
-    current: &AgentStatus,
-    event_type: &EventType,
+/// 전이 테이블에서 (현재 상태, 이벤트, guard)가 처음 매치하는 항목 (state-machine.md §4.1)
+/// 상태를 바꾸지 않고 이 이벤트로 전이할 상태만 계산한다 (매치 없으면 None).
+/// 대화 대기열처럼 전이 전에 끼어들어야 하는 곳에서 쓴다.
+pub fn next_status(
     event: &NormalizedEvent,
+    state: &AgentState,
     config: &StateMachineConfig,
-    consecutive_failures: u32,
 ) -> Option<AgentStatus> {
-    use AgentStatus::*;
-    use EventType::*;
-
-    match (current, event_type) {
-        // === 기본 전이 ===
-        (Offline, AgentStarted) => Some(Appearing),
-        (Appearing, AppearDone) => Some(Idle),
-
-        (Idle, TaskStarted) => Some(Working),
-        (Idle, ToolStarted) => Some(Working),
-        (Idle, MessageSent) => Some(Walking),
-
-        (Working, ThinkingUpdated) => Some(Thinking),
-        (Working, TaskCompleted) => Some(Completed),
-        (Working, TaskFailed) => Some(Failed),
-        (Working, ToolFailed) => Some(classify_failure(event, config, consecutive_failures)),
-        (Working, ToolStarted) => Some(Working),
-        (Working, ToolSucceeded) => Some(Working),
-        (Working, MessageSent) => Some(Walking),
-
-        (Thinking, ToolStarted) => Some(Working),
-        (Thinking, TaskCompleted) => Some(Completed),
-        (Thinking, TaskFailed) => Some(Failed),
-        (Thinking, ThinkingUpdated) => Some(Thinking),
-
-        (PendingInput, AgentUnblocked) => Some(Working),
-        (PendingInput, TaskStarted) => Some(Working),
-
-        (Failed, AgentUnblocked) => Some(Working),
-        (Failed, TaskStarted) => Some(Working),
-
-        (Completed, TaskStarted) => Some(Working),
-
-        (Disappearing, DisappearDone) => Some(Offline),
+    if event.event_type == EventType::Heartbeat {
+        return None;
+    }
+    resolve(state, event, config).map(|target| match target {
+        TransitionTarget::Status(status) => status,
+        TransitionTarget::Previous => state.prev_status.clone().unwrap_or(AgentStatus::Idle),
+    })
+}
 
-        // === 졸기 / 깨어남 ===
-        (Resting, TaskStarted) => Some(Startled),
-        (Resting, MessageReceived) => Some(Startled),
-        (Resting, MessageSent) => Some(Startled),
+/// 이벤트의 전이 대상. timer_elapsed는 타이머 규칙, 나머지는 전이 테이블에서 찾는다
+fn resolve(
+    state: &AgentState,
+    event: &NormalizedEvent,
+    config: &StateMachineConfig,
+) -> Option<TransitionTarget> {
+    if event.event_type == EventType::TimerElapsed {
+        return find_timer(state, event, config).map(|timer| timer.to_status.clone());
+    }
+    find_transition(state, event, config).map(|rule| rule.to.clone())
+}
 
-        // startled_done과 arrive_at_home은 on_event()에서 특수 처리
+/// 현재 상태의 타이머 규칙 중 payload.elapsed_secs 기준으로 만료되고 guard를 만족하는 첫 항목 (§5).
+/// heartbeat가 적용할 규칙을 이벤트 payload에 남길 때도 쓴다.
+pub fn find_timer<'a>(
+    state: &AgentState,
+    event: &NormalizedEvent,
+    config: &'a StateMachineConfig,
+) -> Option<&'a TimerRule> {
+    let elapsed_secs = event.payload.get("elapsed_secs").and_then(|v| v.as_u64())?;
+    config.timers.iter().find(|timer| {
+        timer.status == state.status
+            && elapsed_secs >= timer.after_secs
+            && timer
+                .guard
+                .is_none_or(|guard| guard_passes(guard, state, event, config))
+    })
+}
 
-        // === 대화 ===
-        (Walking, ArriveAtPeer) => Some(Chatting),
-        (Chatting, MessageDone) => Some(Returning),
+fn find_transition<'a>(
+    state: &AgentState,
+    event: &NormalizedEvent,
+    config: &'a StateMachineConfig,
+) -> Option<&'a TransitionRule> {
+    config.transitions.iter().find(|rule| {
+        rule.from.matches(&state.status)
+            && rule.event.matches(&event.event_type)
+            && rule
+                .guard
+                .is_none_or(|guard| guard_passes(guard, state, event, config))
+    })
+}
 
-        _ => None,
+fn guard_passes(
+    guard: TransitionGuard,
+    state: &AgentState,
+    event: &NormalizedEvent,
+    config: &StateMachineConfig,
+) -> bool {
+    match guard {
+        TransitionGuard::Online => state.status != AgentStatus::Offline,
+        TransitionGuard::HasTask => state.current_task.is_some(),
+        TransitionGuard::NoTask => state.current_task.is_none(),
+        TransitionGuard::HasThinking => event.thinking_text.is_some(),
+        TransitionGuard::FailureFatal => {
+            classify_failure(event, config, state.consecutive_failures) == FailureClass::Fatal
+        }
+        TransitionGuard::FailureRetryable => {
+            classify_failure(event, config, state.consecutive_failures) == FailureClass::Retryable
+        }
+        TransitionGuard::FailureUnknown => {
+            classify_failure(event, config, state.consecutive_failures) == FailureClass::Unknown
+        }
     }
 }
 
 /// 치명/재시도 실패 분류 (state-machine.md §4.3)
+/// 분류는 ingest의 FailureClassifier가 payload.failure_class에 기록한다. 없으면 unknown.
+/// 연속 실패 횟수가 한도에 도달하면 분류와 무관하게 fatal.
 fn classify_failure(
     event: &NormalizedEvent,
     config: &StateMachineConfig,
     consecutive_failures: u32,
-) -> AgentStatus {
-    let error_message = event
+) -> FailureClass {
+    let class = event
         .payload
-        .get("error_message")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
+        .get("failure_class")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or(FailureClass::Unknown);
 
-    let lower_msg = error_message.to_lowercase();
+    if consecutive_failures >= config.fatal_consecutive_failures {
+        return FailureClass::Fatal;
+    }
+    class
+}
 
-    // 치명적 키워드 검사
-    for keyword in &config.fatal_keywords {
-        if lower_msg.contains(&keyword.to_lowercase()) {
-            return AgentStatus::Failed;
+/// 전이 테이블 검증 (시작 시 config 로드에서 호출). 문제가 있으면 설명을 반환한다.
+/// - 비어 있음, tool_failed 외 이벤트의 failure guard
+/// - 앞선 guard 없는 항목에 가려져 절대 매치되지 않는 항목
+/// - 0초, 자기 자신으로 가는, 앞선 규칙에 가려진 타이머 규칙
+/// - offline에서 도달할 수 없는 상태 (타이머 전이 포함)
+/// - 빠져나가는 전이가 없는 일시 상태 (전역 `"*"` 항목은 제외)
+pub fn validate_transitions(config: &StateMachineConfig) -> Result<(), String> {
+    let rules = &config.transitions;
+    let timers = &config.timers;
+    if rules.is_empty() {
+        return Err("transition table is empty".into());
+    }
+
+    for (i, rule) in rules.iter().enumerate() {
+        if rule.guard.is_some_and(|g| g.is_failure_guard())
+            && rule.event != Pattern::Is(EventType::ToolFailed)
+        {
+            return Err(format!("[{i}]: failure guards require event = \"tool_failed\""));
+        }
+        let shadowed_by = rules[..i].iter().position(|earlier| {
+            earlier.guard.is_none()
+                && covers(&earlier.from, &rule.from)
+                && covers(&earlier.event, &rule.event)
+        });
+        if let Some(j) = shadowed_by {
+            return Err(format!("[{i}]: never matches, shadowed by [{j}]"));
         }
     }
 
-    // 연속 실패 횟수 초과
-    if consecutive_failures >= config.fatal_consecutive_failures {
-        return AgentStatus::Failed;
+    for (i, timer) in timers.iter().enumerate() {
+        if timer.after_secs == 0 {
+            return Err(format!("timers[{i}]: after_secs must be > 0"));
+        }
+        if timer.guard.is_some_and(|g| g.is_failure_guard()) {
+            return Err(format!("timers[{i}]: failure guards are not allowed on timers"));
+        }
+        if timer.to_status == TransitionTarget::Status(timer.status.clone()) {
+            return Err(format!("timers[{i}]: transitions to its own status"));
+        }
+        // 앞선 guard 없는 규칙이 같은 상태에서 먼저(또는 같이) 만료되면 절대 적용되지 않는다
+        let shadowed_by = timers[..i].iter().position(|earlier| {
+            earlier.guard.is_none()
+                && earlier.status == timer.status
+                && earlier.after_secs <= timer.after_secs
+        });
+        if let Some(j) = shadowed_by {
+            return Err(format!("timers[{i}]: never fires, shadowed by timers[{j}]"));
+        }
+    }
+
+    // offline에서 시작하는 도달 가능성 (prev_status 복귀는 이미 방문한 상태로만 간다)
+    let mut reachable = vec![AgentStatus::Offline];
+    let mut queue = vec![AgentStatus::Offline];
+    while let Some(status) = queue.pop() {
+        let rule_targets = rules.iter().filter_map(|rule| match &rule.to {
+            TransitionTarget::Status(to) if rule.from.matches(&status) => Some(to.clone()),
+            _ => None,
+        });
+        let timer_targets = timers.iter().filter_map(|timer| match &timer.to_status {
+            TransitionTarget::Status(to) if timer.status == status => Some(to.clone()),
+            _ => None,
+        });
+        for next in rule_targets.chain(timer_targets).collect::<Vec<_>>() {
+            if !reachable.contains(&next) {
+                reachable.push(next.clone());
+                queue.push(next);
+            }
+        }
+    }
+    let unreachable: Vec<_> = AgentStatus::ALL
+        .iter()
+        .filter(|s| !reachable.contains(s))
+        .collect();
+    if !unreachable.is_empty() {
+        return Err(format!("unreachable states: {unreachable:?}"));
+    }
+
+    for status in TRANSIENT_STATES {
+        let has_rule_exit = rules.iter().any(|rule| {
+            rule.from == Pattern::Is(status.clone())
+                && rule.to != TransitionTarget::Status(status.clone())
+        });
+        let has_timer_exit = timers.iter().any(|timer| {
+            timer.status == *status && timer.to_status != TransitionTarget::Status(status.clone())
+        });
+        if !has_rule_exit && !has_timer_exit {
+            return Err(format!("transient state {status:?} has no exit transition"));
+        }
     }
 
-    AgentStatus::PendingInput
+    Ok(())
+}
+
+/// earlier 패턴이 later 패턴이 매치하는 모든 값을 매치하면 true
+fn covers<T: PartialEq>(earlier: &Pattern<T>, later: &Pattern<T>) -> bool {
+    match (earlier, later) {
+        (Pattern::Any, _) => true,
+        (Pattern::Is(a), Pattern::Is(b)) => a == b,
+        (Pattern::Is(_), Pattern::Any) => false,
+    }
 }
 
 /// 상태 전이 적용
@@ -187,43 +357,42 @@ fn apply_transition(
     prev: &AgentStatus,
 ) -> TransitionResult {
     let prev_clone = prev.clone();
+    let prev_since = std::mem::replace(&mut state.since, ts.to_string());
     state.status = next_status.clone();
-    state.since = ts.to_string();
 
     TransitionResult::Changed {
         prev_status: prev_clone,
         new_status: next_status,
+        prev_since,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::TimerTransitionsConfig;
     use crate::models::agent::*;
     use crate::models::event::*;
+    use crate::services::failure::FailureClassifier;
 
+    /// 배포되는 config.toml의 전이 테이블/키워드를 그대로 사용
     fn test_config() -> StateMachineConfig {
-        StateMachineConfig {
-            fatal_keywords: vec![
-                "permission denied".into(),
-                "not found".into(),
-                "ENOENT".into(),
-                "EACCES".into(),
-            ],
-            retryable_keywords: vec![
-                "timeout".into(),
-                "EAGAIN".into(),
-                "rate limit".into(),
-                "ECONNREFUSED".into(),
-            ],
-            fatal_consecutive_failures: 3,
-            timer_transitions: TimerTransitionsConfig {
-                idle_to_resting_secs: 120,
-                completed_to_disappear_secs: 60,
-                chat_timeout_secs: 5,
-            },
-        }
+        let config: crate::config::AppConfig =
+            toml::from_str(include_str!("../../config.toml")).expect("config.toml");
+        config.state_machine
+    }
+
+    /// ingest처럼 FailureClassifier로 분류를 기록한 tool_failed 이벤트
+    fn make_failure(tool_name: &str, error_message: &str) -> NormalizedEvent {
+        let mut event = make_event(EventType::ToolFailed);
+        event.payload = serde_json::json!({
+            "tool_name": tool_name,
+            "error_message": error_message,
+            "exit_code": 1,
+        });
+        FailureClassifier::from_config(&test_config())
+            .expect("classifier")
+            .annotate(&mut event);
+        event
     }
 
     fn make_state(status: AgentStatus) -> AgentState {
@@ -233,6 +402,10 @@ mod tests {
             prev_status: None,
             thinking_text: None,
             current_task: None,
+            activity: None,
+            activity_summary: None,
+            consecutive_failures: 0,
+            unresponsive: false,
             workspace_id: "test".into(),
             since: "2026-02-20T15:00:00Z".into(),
             last_event_ts: "2026-02-20T15:00:00Z".into(),
@@ -260,6 +433,7 @@ mod tests {
             payload: serde_json::json!({}),
             thinking_text: None,
             raw: serde_json::json!({}),
+            redactions: Vec::new(),
         }
     }
 
@@ -269,7 +443,7 @@ mod tests {
     fn test_offline_to_appearing() {
         let mut state = make_state(AgentStatus::Offline);
         let event = make_event(EventType::AgentStarted);
-        let result = on_event(&event, &mut state, &test_config(), 0);
+        let result = on_event(&event, &mut state, &test_config());
         assert!(matches!(result, TransitionResult::Changed { .. }));
         assert_eq!(state.status, AgentStatus::Appearing);
     }
@@ -278,7 +452,7 @@ mod tests {
     fn test_appearing_to_idle() {
         let mut state = make_state(AgentStatus::Appearing);
         let event = make_event(EventType::AppearDone);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Idle);
     }
 
@@ -286,7 +460,7 @@ mod tests {
     fn test_idle_to_working() {
         let mut state = make_state(AgentStatus::Idle);
         let event = make_event(EventType::TaskStarted);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Working);
     }
 
@@ -295,7 +469,7 @@ mod tests {
         let mut state = make_state(AgentStatus::Working);
         let mut event = make_event(EventType::ThinkingUpdated);
         event.thinking_text = Some("hmm...".into());
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Thinking);
         assert_eq!(state.thinking_text.as_deref(), Some("hmm..."));
     }
@@ -304,43 +478,57 @@ mod tests {
     fn test_working_to_completed() {
         let mut state = make_state(AgentStatus::Working);
         let event = make_event(EventType::TaskCompleted);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Completed);
     }
 
     #[test]
     fn test_working_to_failed_fatal_keyword() {
         let mut state = make_state(AgentStatus::Working);
-        let mut event = make_event(EventType::ToolFailed);
-        event.payload = serde_json::json!({"error_message": "permission denied"});
-        on_event(&event, &mut state, &test_config(), 0);
+        let event = make_failure("Read", "permission denied");
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Failed);
     }
 
     #[test]
-    fn test_working_to_pending_input_retryable() {
+    fn test_working_retryable_stays_working() {
+        let mut state = make_state(AgentStatus::Working);
+        let event = make_failure("WebFetch", "timeout occurred");
+        let result = on_event(&event, &mut state, &test_config());
+        assert!(matches!(result, TransitionResult::Updated));
+        assert_eq!(state.status, AgentStatus::Working);
+        assert_eq!(state.activity_summary.as_deref(), Some("Retrying: WebFetch"));
+        // 재시도는 working 체류를 끊지 않는다
+        assert_eq!(state.since, "2026-02-20T15:00:00Z");
+        assert_eq!(state.last_event_ts, "2026-02-20T15:01:00Z");
+    }
+
+    #[test]
+    fn test_working_unannotated_failure_is_unknown() {
+        // failure_class가 없으면 unknown으로 취급
         let mut state = make_state(AgentStatus::Working);
         let mut event = make_event(EventType::ToolFailed);
         event.payload = serde_json::json!({"error_message": "timeout occurred"});
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::PendingInput);
     }
 
     #[test]
     fn test_working_to_failed_consecutive() {
         let mut state = make_state(AgentStatus::Working);
-        let mut event = make_event(EventType::ToolFailed);
-        event.payload = serde_json::json!({"error_message": "some unknown error"});
-        on_event(&event, &mut state, &test_config(), 3);
+        let event = make_failure("Bash", "some unknown error");
+        state.consecutive_failures = 2;
+        on_event(&event, &mut state, &test_config());
+        assert_eq!(state.consecutive_failures, 3);
         assert_eq!(state.status, AgentStatus::Failed);
     }
 
     #[test]
     fn test_working_to_pending_input_unknown() {
         let mut state = make_state(AgentStatus::Working);
-        let mut event = make_event(EventType::ToolFailed);
-        event.payload = serde_json::json!({"error_message": "some unknown error"});
-        on_event(&event, &mut state, &test_config(), 1);
+        let event = make_failure("Bash", "some unknown error");
+        on_event(&event, &mut state, &test_config());
+        assert_eq!(state.consecutive_failures, 1);
         assert_eq!(state.status, AgentStatus::PendingInput);
     }
 
@@ -355,7 +543,7 @@ mod tests {
         ] {
             let mut state = make_state(status);
             let event = make_event(EventType::AgentStopped);
-            on_event(&event, &mut state, &test_config(), 0);
+            on_event(&event, &mut state, &test_config());
             assert_eq!(state.status, AgentStatus::Disappearing);
         }
     }
@@ -364,7 +552,7 @@ mod tests {
     fn test_disappearing_to_offline() {
         let mut state = make_state(AgentStatus::Disappearing);
         let event = make_event(EventType::DisappearDone);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Offline);
     }
 
@@ -374,7 +562,7 @@ mod tests {
     fn test_resting_task_started_startled() {
         let mut state = make_state(AgentStatus::Resting);
         let event = make_event(EventType::TaskStarted);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Startled);
     }
 
@@ -383,7 +571,7 @@ mod tests {
         let mut state = make_state(AgentStatus::Startled);
         state.current_task = Some("do something".into());
         let event = make_event(EventType::StartledDone);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Working);
     }
 
@@ -392,7 +580,7 @@ mod tests {
         let mut state = make_state(AgentStatus::Startled);
         state.current_task = None;
         let event = make_event(EventType::StartledDone);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Idle);
     }
 
@@ -403,7 +591,7 @@ mod tests {
         let mut state = make_state(AgentStatus::Idle);
         let mut event = make_event(EventType::MessageSent);
         event.target_agent_id = Some("other-agent".into());
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Walking);
         assert_eq!(state.prev_status, Some(AgentStatus::Idle));
         assert_eq!(state.peer_agent_id.as_deref(), Some("other-agent"));
@@ -413,7 +601,7 @@ mod tests {
     fn test_walking_arrive_at_peer_to_chatting() {
         let mut state = make_state(AgentStatus::Walking);
         let event = make_event(EventType::ArriveAtPeer);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Chatting);
     }
 
@@ -421,7 +609,7 @@ mod tests {
     fn test_chatting_message_done_to_returning() {
         let mut state = make_state(AgentStatus::Chatting);
         let event = make_event(EventType::MessageDone);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Returning);
     }
 
@@ -430,7 +618,7 @@ mod tests {
         let mut state = make_state(AgentStatus::Returning);
         state.prev_status = Some(AgentStatus::Working);
         let event = make_event(EventType::ArriveAtHome);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Working);
         assert!(state.prev_status.is_none());
         assert!(state.peer_agent_id.is_none());
@@ -449,29 +637,270 @@ mod tests {
         ] {
             let mut state = make_state(AgentStatus::Offline);
             let event = make_event(event_type);
-            let result = on_event(&event, &mut state, &test_config(), 0);
+            let result = on_event(&event, &mut state, &test_config());
             assert!(matches!(result, TransitionResult::Changed { .. }));
             assert_eq!(state.status, AgentStatus::Appearing);
         }
     }
 
+    #[test]
+    fn test_self_transition_is_update() {
+        let mut state = make_state(AgentStatus::Working);
+        let mut event = make_event(EventType::ToolStarted);
+        event.payload = serde_json::json!({"tool_name": "Read"});
+        let result = on_event(&event, &mut state, &test_config());
+        assert!(matches!(result, TransitionResult::Updated));
+        assert_eq!(state.current_task.as_deref(), Some("Read"));
+        assert_eq!(state.since, "2026-02-20T15:00:00Z");
+    }
+
+    #[test]
+    fn test_changed_reports_prev_since() {
+        let mut state = make_state(AgentStatus::Idle);
+        let result = on_event(&make_event(EventType::TaskStarted), &mut state, &test_config());
+        match result {
+            TransitionResult::Changed { prev_status, prev_since, .. } => {
+                assert_eq!(prev_status, AgentStatus::Idle);
+                assert_eq!(prev_since, "2026-02-20T15:00:00Z");
+            }
+            TransitionResult::Updated | TransitionResult::NoOp => panic!("expected transition"),
+        }
+        assert_eq!(state.since, "2026-02-20T15:01:00Z");
+    }
+
+    // === 전이 테이블 ===
+
+    fn rule(from: &str, event: &str, to: &str) -> TransitionRule {
+        toml::from_str(&format!("from = {from:?}\nevent = {event:?}\nto = {to:?}\n"))
+            .expect("rule")
+    }
+
+    fn without(from: AgentStatus, event: EventType) -> StateMachineConfig {
+        let mut config = test_config();
+        config
+            .transitions
+            .retain(|r| !(r.from == Pattern::Is(from.clone()) && r.event == Pattern::Is(event.clone())));
+        config
+    }
+
+    #[test]
+    fn test_default_transition_table_is_valid() {
+        assert_eq!(validate_transitions(&test_config()), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_rejects_unreachable_state() {
+        let config = without(AgentStatus::Walking, EventType::ArriveAtPeer);
+        let err = validate_transitions(&config).expect_err("chatting unreachable");
+        assert!(err.contains("Chatting"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_rejects_transient_state_without_exit() {
+        let mut config = without(AgentStatus::Startled, EventType::StartledDone);
+        config.timers.retain(|t| t.status != AgentStatus::Startled);
+        let err = validate_transitions(&config).expect_err("startled stuck");
+        assert!(err.contains("Startled"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_rejects_shadowed_entry_and_misplaced_guard() {
+        let mut config = test_config();
+        config.transitions.insert(2, rule("working", "*", "working"));
+        let err = validate_transitions(&config).expect_err("shadowed");
+        assert!(err.contains("shadowed by [2]"), "{err}");
+
+        let mut config = test_config();
+        let mut guarded = rule("failed", "task_started", "working");
+        guarded.guard = Some(TransitionGuard::FailureFatal);
+        config.transitions.insert(0, guarded);
+        assert!(validate_transitions(&config).is_err());
+    }
+
+    // === 타이머 전이 ===
+
+    fn timer_event(elapsed_secs: u64) -> NormalizedEvent {
+        let mut event = make_event(EventType::TimerElapsed);
+        event.source = EventSource::Synthetic;
+        event.payload = serde_json::json!({ "elapsed_secs": elapsed_secs });
+        event
+    }
+
+    #[test]
+    fn test_timer_elapsed_applies_first_due_rule() {
+        let config = test_config();
+
+        // 아직 만료 전
+        let mut state = make_state(AgentStatus::Startled);
+        assert!(matches!(on_event(&timer_event(10), &mut state, &config), TransitionResult::NoOp));
+
+        // guard has_task
+        state.current_task = Some("Bash".into());
+        on_event(&timer_event(30), &mut state, &config);
+        assert_eq!(state.status, AgentStatus::Working);
+
+        let mut state = make_state(AgentStatus::Startled);
+        on_event(&timer_event(45), &mut state, &config);
+        assert_eq!(state.status, AgentStatus::Idle);
+
+        // returning watchdog은 walking 전 상태로 복귀
+        let mut state = make_state(AgentStatus::Returning);
+        state.prev_status = Some(AgentStatus::Working);
+        state.peer_agent_id = Some("other-agent".into());
+        on_event(&timer_event(60), &mut state, &config);
+        assert_eq!(state.status, AgentStatus::Working);
+        assert!(state.peer_agent_id.is_none());
+
+        // 타이머가 없는 상태
+        let mut state = make_state(AgentStatus::Working);
+        assert!(matches!(on_event(&timer_event(10_000), &mut state, &config), TransitionResult::NoOp));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_timers() {
+        let timer = |toml: &str| -> TimerRule { toml::from_str(toml).expect("timer") };
+
+        let mut config = test_config();
+        config.timers.push(timer("status = \"idle\"\nafter_secs = 600\nto_status = \"disappearing\"\n"));
+        let err = validate_transitions(&config).expect_err("shadowed");
+        assert!(err.contains("never fires"), "{err}");
+
+        let mut config = test_config();
+        config.timers.insert(0, timer("status = \"failed\"\nafter_secs = 0\nto_status = \"resting\"\n"));
+        assert!(validate_transitions(&config).is_err());
+
+        let mut config = test_config();
+        config.timers.insert(0, timer("status = \"idle\"\nafter_secs = 60\nto_status = \"idle\"\n"));
+        assert!(validate_transitions(&config).is_err());
+    }
+
+    #[test]
+    fn test_custom_transition_table() {
+        // 기본 테이블에 없는 thinking + tool_succeeded → working
+        let mut config = test_config();
+        let mut state = make_state(AgentStatus::Thinking);
+        let event = make_event(EventType::ToolSucceeded);
+        assert!(matches!(on_event(&event, &mut state, &config), TransitionResult::NoOp));
+
+        config.transitions.insert(0, rule("thinking", "tool_succeeded", "working"));
+        assert_eq!(validate_transitions(&config), Ok(()));
+        on_event(&event, &mut state, &config);
+        assert_eq!(state.status, AgentStatus::Working);
+    }
+
+    // === 연속 실패 ===
+
+    #[test]
+    fn test_consecutive_failures_escalate_to_failed() {
+        // 판정 불가 실패 → pending_input → 사용자 재개를 반복하다가 3번째 실패에서 failed
+        let mut state = make_state(AgentStatus::Working);
+        let failed = make_failure("Bash", "some unknown error");
+        let unblocked = make_event(EventType::AgentUnblocked);
+
+        for expected in 1..=2 {
+            on_event(&failed, &mut state, &test_config());
+            assert_eq!(state.status, AgentStatus::PendingInput);
+            assert_eq!(state.consecutive_failures, expected);
+            on_event(&unblocked, &mut state, &test_config());
+            assert_eq!(state.status, AgentStatus::Working);
+        }
+
+        on_event(&failed, &mut state, &test_config());
+        assert_eq!(state.consecutive_failures, 3);
+        assert_eq!(state.status, AgentStatus::Failed);
+    }
+
+    #[test]
+    fn test_consecutive_retryable_failures_escalate_to_failed() {
+        let mut state = make_state(AgentStatus::Working);
+        let failed = make_failure("WebFetch", "rate limit exceeded");
+        for _ in 0..2 {
+            on_event(&failed, &mut state, &test_config());
+            assert_eq!(state.status, AgentStatus::Working);
+        }
+        on_event(&failed, &mut state, &test_config());
+        assert_eq!(state.status, AgentStatus::Failed);
+    }
+
+    #[test]
+    fn test_consecutive_failures_reset_on_success() {
+        for reset_type in [EventType::ToolSucceeded, EventType::TaskCompleted] {
+            let mut state = make_state(AgentStatus::Working);
+            state.consecutive_failures = 2;
+            on_event(&make_event(EventType::Heartbeat), &mut state, &test_config());
+            assert_eq!(state.consecutive_failures, 2);
+
+            on_event(&make_event(reset_type), &mut state, &test_config());
+            assert_eq!(state.consecutive_failures, 0);
+        }
+    }
+
+    #[test]
+    fn test_consecutive_failures_counted_without_transition() {
+        // task_failed는 idle에서 전이가 없어도 카운트된다
+        let mut state = make_state(AgentStatus::Idle);
+        let result = on_event(&make_event(EventType::TaskFailed), &mut state, &test_config());
+        assert!(matches!(result, TransitionResult::NoOp));
+        assert_eq!(state.consecutive_failures, 1);
+    }
+
     #[test]
     fn test_heartbeat_updates_timestamp_only() {
         let mut state = make_state(AgentStatus::Working);
         let event = make_event(EventType::Heartbeat);
-        let result = on_event(&event, &mut state, &test_config(), 0);
+        let result = on_event(&event, &mut state, &test_config());
         assert!(matches!(result, TransitionResult::NoOp));
         assert_eq!(state.status, AgentStatus::Working);
         assert_eq!(state.last_event_ts, "2026-02-20T15:01:00Z");
     }
 
+    #[test]
+    fn test_unresponsive_marked_by_synthetic_and_cleared_by_session_event() {
+        let mut state = make_state(AgentStatus::Working);
+        let mut event = make_event(EventType::AgentUnresponsive);
+        event.source = EventSource::Synthetic;
+        event.ts = "2026-02-20T15:30:00Z".into();
+        let result = on_event(&event, &mut state, &test_config());
+        assert!(matches!(result, TransitionResult::NoOp));
+        assert!(state.unresponsive);
+        assert_eq!(state.status, AgentStatus::Working);
+        // synthetic 이벤트는 생존 신호가 아님
+        assert_eq!(state.last_event_ts, "2026-02-20T15:00:00Z");
+
+        let event = make_event(EventType::ToolSucceeded);
+        on_event(&event, &mut state, &test_config());
+        assert!(!state.unresponsive);
+        assert_eq!(state.last_event_ts, "2026-02-20T15:01:00Z");
+    }
+
+    #[test]
+    fn test_state_repaired_moves_to_payload_status() {
+        let mut state = make_state(AgentStatus::Chatting);
+        state.prev_status = Some(AgentStatus::Working);
+        state.peer_agent_id = Some("lead".into());
+        let mut event = make_event(EventType::StateRepaired);
+        event.source = EventSource::Synthetic;
+        event.payload = serde_json::json!({ "to_status": "working" });
+        let result = on_event(&event, &mut state, &test_config());
+        assert!(matches!(result, TransitionResult::Changed { .. }));
+        assert_eq!(state.status, AgentStatus::Working);
+        assert_eq!(state.peer_agent_id, None);
+        assert_eq!(state.prev_status, None);
+
+        // 상태가 같으면 대화 상대만 지운다
+        state.peer_agent_id = Some("lead".into());
+        let result = on_event(&event, &mut state, &test_config());
+        assert!(matches!(result, TransitionResult::NoOp));
+        assert_eq!(state.peer_agent_id, None);
+    }
+
     // === 복귀 전이 ===
 
     #[test]
     fn test_pending_input_to_working() {
         let mut state = make_state(AgentStatus::PendingInput);
         let event = make_event(EventType::AgentUnblocked);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Working);
     }
 
@@ -479,7 +908,7 @@ mod tests {
     fn test_failed_to_working() {
         let mut state = make_state(AgentStatus::Failed);
         let event = make_event(EventType::TaskStarted);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Working);
     }
 
@@ -487,7 +916,7 @@ mod tests {
     fn test_completed_to_working() {
         let mut state = make_state(AgentStatus::Completed);
         let event = make_event(EventType::TaskStarted);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Working);
     }
 
@@ -495,7 +924,22 @@ mod tests {
     fn test_thinking_to_working() {
         let mut state = make_state(AgentStatus::Thinking);
         let event = make_event(EventType::ToolStarted);
-        on_event(&event, &mut state, &test_config(), 0);
+        on_event(&event, &mut state, &test_config());
         assert_eq!(state.status, AgentStatus::Working);
     }
+
+    #[test]
+    fn test_tool_event_updates_activity() {
+        let mut state = make_state(AgentStatus::Idle);
+        let mut event = make_event(EventType::ToolStarted);
+        event.payload = serde_json::json!({
+            "tool_name": "Edit",
+            "activity": "editing",
+            "activity_summary": "Editing lib.rs",
+        });
+        on_event(&event, &mut state, &test_config());
+        assert_eq!(state.current_task.as_deref(), Some("Edit"));
+        assert_eq!(state.activity, Some(ToolCategory::Editing));
+        assert_eq!(state.activity_summary.as_deref(), Some("Editing lib.rs"));
+    }
 }
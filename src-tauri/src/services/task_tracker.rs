@@ -0,0 +1,241 @@
+use crate::models::event::{EventType, NormalizedEvent};
+use crate::models::task::{TaskChange, TaskRecord, TaskStatus, TaskStatusChange};
+use crate::services::normalizer::derive_agent_id;
+
+/// TaskCreate/TaskUpdate의 PostToolUse hook 이벤트에서 작업 변경분을 추출한다.
+/// PreToolUse 시점에는 호출이 성공할지 모르므로 보지 않는다. 실패한 호출이면 None.
+pub fn extract_change(event: &NormalizedEvent) -> Option<TaskChange> {
+    let task_id = event.task_id.clone()?;
+    let raw = &event.raw;
+    let tool_name = raw.get("tool_name").and_then(|v| v.as_str())?;
+    let hook_type = raw.get("hook_type").and_then(|v| v.as_str());
+    if hook_type != Some("PostToolUse") || event.event_type == EventType::ToolFailed {
+        return None;
+    }
+
+    let input = raw.get("tool_input");
+    let input_str = |key: &str| {
+        input
+            .and_then(|i| i.get(key))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+
+    let title = input_str("subject").or_else(|| input_str("title"));
+    let description = input_str("description");
+
+    match tool_name {
+        "TaskCreate" => Some(TaskChange {
+            workspace_id: event.workspace_id.clone(),
+            task_id,
+            agent_id: event.agent_id.clone(),
+            ts: event.ts.clone(),
+            is_create: true,
+            title,
+            description,
+            owner_agent_id: None,
+            status: Some(TaskStatus::Pending),
+        }),
+        "TaskUpdate" => {
+            // owner는 팀 내 에이전트 이름이므로 agent_id 규칙(§7.3)으로 변환
+            let team_name = raw.get("team_name").and_then(|v| v.as_str());
+            let owner_agent_id = input_str("owner")
+                .map(|owner| derive_agent_id(team_name, Some(&owner), None));
+
+            Some(TaskChange {
+                workspace_id: event.workspace_id.clone(),
+                task_id,
+                agent_id: event.agent_id.clone(),
+                ts: event.ts.clone(),
+                is_create: false,
+                title,
+                description,
+                owner_agent_id,
+                status: input_str("status").and_then(|s| TaskStatus::parse(&s)),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// 기존 작업 레코드에 변경분을 병합한다. 레코드가 없으면 새로 만든다
+/// (TaskCreate를 놓친 경우에도 TaskUpdate만으로 보드에 나타나도록).
+pub fn merge(existing: Option<TaskRecord>, change: &TaskChange) -> TaskRecord {
+    let mut record = existing.unwrap_or_else(|| TaskRecord {
+        workspace_id: change.workspace_id.clone(),
+        task_id: change.task_id.clone(),
+        title: None,
+        description: None,
+        owner_agent_id: None,
+        status: TaskStatus::Pending,
+        status_history: Vec::new(),
+        created_ts: None,
+        started_ts: None,
+        completed_ts: None,
+        updated_ts: change.ts.clone(),
+    });
+
+    if change.is_create && record.created_ts.is_none() {
+        record.created_ts = Some(change.ts.clone());
+    }
+    if change.title.is_some() {
+        record.title = change.title.clone();
+    }
+    if change.description.is_some() {
+        record.description = change.description.clone();
+    }
+    if change.owner_agent_id.is_some() {
+        record.owner_agent_id = change.owner_agent_id.clone();
+    }
+
+    if let Some(status) = &change.status {
+        let is_first_entry = record.status_history.is_empty();
+        let status_changed = *status != record.status;
+        if is_first_entry || status_changed {
+            record.status_history.push(TaskStatusChange {
+                status: status.clone(),
+                agent_id: change.agent_id.clone(),
+                ts: change.ts.clone(),
+            });
+        }
+        record.status = status.clone();
+
+        match status {
+            TaskStatus::InProgress => {
+                if record.started_ts.is_none() {
+                    record.started_ts = Some(change.ts.clone());
+                }
+                // 명시된 owner 없이 시작하면 시작한 에이전트를 owner로 본다
+                if record.owner_agent_id.is_none() {
+                    record.owner_agent_id = Some(change.agent_id.clone());
+                }
+            }
+            TaskStatus::Completed | TaskStatus::Failed => {
+                // 같은 상태를 다시 보내도 끝난 시각은 그대로 둔다
+                if record.completed_ts.is_none() || status_changed {
+                    record.completed_ts = Some(change.ts.clone());
+                }
+            }
+            TaskStatus::Pending | TaskStatus::Deleted => {}
+        }
+    }
+
+    record.updated_ts = change.ts.clone();
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::normalizer::normalize;
+    use serde_json::json;
+
+    fn hook(tool_name: &str, hook_type: &str, extra: serde_json::Value) -> NormalizedEvent {
+        let mut raw = json!({
+            "hook_type": hook_type,
+            "session_id": "sess-1",
+            "team_name": "my-team",
+            "agent_name": "leader",
+            "tool_name": tool_name,
+            "_meta": {
+                "workspace_id": "my-project",
+                "terminal_session_id": "term-1",
+                "collected_at": "2026-02-20T15:00:00Z"
+            }
+        });
+        for (k, v) in extra.as_object().expect("object") {
+            raw[k] = v.clone();
+        }
+        normalize(&raw).expect("should normalize")
+    }
+
+    #[test]
+    fn test_task_lifecycle() {
+        let created = hook(
+            "TaskCreate",
+            "PostToolUse",
+            json!({
+                "tool_input": {"subject": "Write docs", "description": "API docs"},
+                "tool_response": {"task": {"id": "1"}},
+            }),
+        );
+        let change = extract_change(&created).expect("create change");
+        let record = merge(None, &change);
+        assert_eq!(record.title.as_deref(), Some("Write docs"));
+        assert_eq!(record.status, TaskStatus::Pending);
+        assert!(record.created_ts.is_some());
+
+        let started = hook(
+            "TaskUpdate",
+            "PostToolUse",
+            json!({"tool_input": {"taskId": "1", "status": "in_progress", "owner": "worker-01"}}),
+        );
+        let record = merge(Some(record), &extract_change(&started).expect("update change"));
+        assert_eq!(record.status, TaskStatus::InProgress);
+        assert_eq!(record.owner_agent_id.as_deref(), Some("my-team/worker-01"));
+        assert!(record.started_ts.is_some());
+
+        let failed = hook(
+            "TaskUpdate",
+            "PostToolUse",
+            json!({"tool_input": {"taskId": "1", "status": "failed"}}),
+        );
+        let record = merge(Some(record), &extract_change(&failed).expect("update change"));
+        assert_eq!(record.status, TaskStatus::Failed);
+        assert!(record.completed_ts.is_some());
+
+        // 같은 상태를 다시 보내도 끝난 시각과 이력은 그대로
+        let mut resent = extract_change(&failed).expect("update change");
+        resent.ts = "2026-02-20T16:00:00Z".into();
+        let completed_ts = record.completed_ts.clone();
+        let record = merge(Some(record), &resent);
+        assert_eq!(record.completed_ts, completed_ts);
+        let history: Vec<TaskStatus> =
+            record.status_history.iter().map(|h| h.status.clone()).collect();
+        assert_eq!(
+            history,
+            vec![TaskStatus::Pending, TaskStatus::InProgress, TaskStatus::Failed]
+        );
+    }
+
+    #[test]
+    fn test_update_without_create_creates_record() {
+        let started = hook(
+            "TaskUpdate",
+            "PostToolUse",
+            json!({"tool_input": {"taskId": "9", "status": "in_progress"}}),
+        );
+        let record = merge(None, &extract_change(&started).expect("update change"));
+        assert_eq!(record.task_id, "9");
+        assert!(record.created_ts.is_none());
+        assert_eq!(record.owner_agent_id.as_deref(), Some("my-team/leader"));
+    }
+
+    #[test]
+    fn test_pre_tool_use_task_create_without_id_is_skipped() {
+        let created = hook(
+            "TaskCreate",
+            "PreToolUse",
+            json!({"tool_input": {"subject": "Write docs"}}),
+        );
+        assert!(extract_change(&created).is_none());
+    }
+
+    #[test]
+    fn test_only_successful_post_tool_use_updates_task() {
+        let input = json!({"tool_input": {"taskId": "1", "status": "completed"}});
+        // 호출 전에는 성공 여부를 모른다
+        assert!(extract_change(&hook("TaskUpdate", "PreToolUse", input.clone())).is_none());
+
+        let mut failed_input = input.clone();
+        failed_input["error"] = json!("task not found");
+        let failed = hook("TaskUpdate", "PostToolUse", failed_input);
+        assert_eq!(failed.event_type, EventType::ToolFailed);
+        assert!(extract_change(&failed).is_none());
+
+        let succeeded = hook("TaskUpdate", "PostToolUse", input);
+        let change = extract_change(&succeeded).expect("update change");
+        assert_eq!(change.status, Some(TaskStatus::Completed));
+    }
+}
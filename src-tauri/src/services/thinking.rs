@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+/// 말풍선 표시용 문자열 절삭. 바이트가 아닌 문자(char) 단위로 자르므로
+/// 멀티바이트 문자(한글, 이모지 등) 중간에서 잘리지 않는다.
+/// 잘린 경우 마지막 글자를 말줄임표로 대체하여 전체 길이가 max_chars를 넘지 않게 한다.
+pub fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// 에이전트별 thinking_updated 쓰로틀.
+/// 같은 텍스트의 반복이나 throttle_ms 이내의 연속 갱신은 건너뛴다.
+#[derive(Debug, Default)]
+pub struct ThinkingThrottle {
+    last: HashMap<String, (chrono::DateTime<chrono::Utc>, String)>,
+}
+
+impl ThinkingThrottle {
+    /// 이 갱신을 반영해도 되는지. 기록은 하지 않는다 (저장이 커밋된 뒤 record를 호출한다).
+    /// ts 파싱에 실패하면 쓰로틀 없이 통과시킨다.
+    pub fn allows(&self, agent_id: &str, ts: &str, text: &str, throttle_ms: u64) -> bool {
+        let Some(ts) = parse_ts(ts) else {
+            return true;
+        };
+
+        if let Some((last_ts, last_text)) = self.last.get(agent_id) {
+            if last_text == text {
+                return false;
+            }
+            let elapsed_ms = (ts - *last_ts).num_milliseconds();
+            if elapsed_ms >= 0 && (elapsed_ms as u64) < throttle_ms {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 반영된 갱신의 시각/텍스트를 기록한다. ts 파싱에 실패하면 기록하지 않는다.
+    pub fn record(&mut self, agent_id: &str, ts: &str, text: &str) {
+        if let Some(ts) = parse_ts(ts) {
+            self.last.insert(agent_id.to_string(), (ts, text.to_string()));
+        }
+    }
+}
+
+fn parse_ts(ts: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emit(throttle: &mut ThinkingThrottle, agent_id: &str, ts: &str, text: &str) -> bool {
+        let allowed = throttle.allows(agent_id, ts, text, 1000);
+        if allowed {
+            throttle.record(agent_id, ts, text);
+        }
+        allowed
+    }
+
+    #[test]
+    fn test_truncate_chars_short_text_unchanged() {
+        assert_eq!(truncate_chars("hello", 10), "hello");
+        assert_eq!(truncate_chars("hello", 5), "hello");
+    }
+
+    #[test]
+    fn test_truncate_chars_multibyte() {
+        let text = "생각하는 중입니다";
+        let truncated = truncate_chars(text, 4);
+        assert_eq!(truncated, "생각하…");
+        assert_eq!(truncated.chars().count(), 4);
+    }
+
+    #[test]
+    fn test_throttle_skips_rapid_updates() {
+        let mut throttle = ThinkingThrottle::default();
+        assert!(emit(&mut throttle, "a1", "2026-02-20T15:00:00.000Z", "first"));
+        assert!(!emit(&mut throttle, "a1", "2026-02-20T15:00:00.500Z", "second"));
+        assert!(emit(&mut throttle, "a1", "2026-02-20T15:00:01.500Z", "third"));
+    }
+
+    #[test]
+    fn test_throttle_skips_identical_text() {
+        let mut throttle = ThinkingThrottle::default();
+        assert!(emit(&mut throttle, "a1", "2026-02-20T15:00:00Z", "same"));
+        assert!(!emit(&mut throttle, "a1", "2026-02-20T15:00:10Z", "same"));
+    }
+
+    #[test]
+    fn test_throttle_is_per_agent() {
+        let mut throttle = ThinkingThrottle::default();
+        assert!(emit(&mut throttle, "a1", "2026-02-20T15:00:00Z", "text"));
+        assert!(emit(&mut throttle, "a2", "2026-02-20T15:00:00Z", "text"));
+    }
+
+    #[test]
+    fn test_throttle_unrecorded_update_does_not_suppress_next() {
+        let mut throttle = ThinkingThrottle::default();
+        // 저장되지 않은(중복/롤백) 갱신은 기록하지 않는다
+        assert!(throttle.allows("a1", "2026-02-20T15:00:00.000Z", "dropped", 1000));
+        assert!(emit(&mut throttle, "a1", "2026-02-20T15:00:00.500Z", "kept"));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::models::event::{EventType, NormalizedEvent, Severity};
+
+/// 정규화 이벤트 검증 (hooks-integration.md §7.5).
+/// 위반 사항 목록을 반환하며, 비어 있으면 유효한 이벤트다.
+pub fn validate(event: &NormalizedEvent) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    // 공통 필수 필드
+    let required = [
+        ("id", &event.id),
+        ("version", &event.version),
+        ("ts", &event.ts),
+        ("workspace_id", &event.workspace_id),
+        ("terminal_session_id", &event.terminal_session_id),
+        ("agent_id", &event.agent_id),
+    ];
+    for (field, value) in required {
+        if value.trim().is_empty() {
+            violations.push(format!("{field} is required"));
+        }
+    }
+
+    // ts는 UTC ISO-8601
+    if !event.ts.is_empty() && chrono::DateTime::parse_from_rfc3339(&event.ts).is_err() {
+        violations.push(format!("ts must be ISO-8601 (got {:?})", event.ts));
+    }
+
+    // 타입별 필수 필드
+    // task_created는 PreToolUse 시점에 id가 아직 없으므로 task_id를 요구하지 않는다.
+    let is_task = matches!(
+        event.event_type,
+        EventType::TaskStarted
+            | EventType::TaskProgress
+            | EventType::TaskCompleted
+            | EventType::TaskFailed
+    );
+    if is_task && event.task_id.as_deref().is_none_or(|s| s.is_empty()) {
+        violations.push("task_id is required for task_* events".to_string());
+    }
+
+    let is_tool = matches!(
+        event.event_type,
+        EventType::ToolStarted | EventType::ToolSucceeded | EventType::ToolFailed
+    );
+    if is_tool && !has_non_empty_str(&event.payload, "tool_name") {
+        violations.push("payload.tool_name is required for tool_* events".to_string());
+    }
+
+    if event.event_type == EventType::ToolFailed {
+        if event.payload.get("exit_code").is_none_or(|v| v.is_null()) {
+            violations.push("payload.exit_code is required for tool_failed".to_string());
+        }
+        if !has_non_empty_str(&event.payload, "error_message") {
+            violations.push("payload.error_message is required for tool_failed".to_string());
+        }
+    }
+
+    violations
+}
+
+/// 검증에 실패한 이벤트를 schema_error 이벤트로 바꾼다.
+/// 식별자와 raw는 유지하고, 원래 타입과 payload는 payload 안에 보존한다.
+pub fn into_schema_error(mut event: NormalizedEvent, violations: Vec<String>) -> NormalizedEvent {
+    let original_event_type = event.event_type.clone();
+    let original_payload = std::mem::take(&mut event.payload);
+
+    event.event_type = EventType::SchemaError;
+    event.severity = Severity::Warn;
+    event.payload = serde_json::json!({
+        "violations": violations,
+        "original_event_type": original_event_type,
+        "original_payload": original_payload,
+    });
+    event
+}
+
+fn has_non_empty_str(payload: &serde_json::Value, key: &str) -> bool {
+    payload
+        .get(key)
+        .and_then(|v| v.as_str())
+        .is_some_and(|s| !s.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::normalizer::normalize;
+    use serde_json::json;
+
+    fn post_tool_use(extra: serde_json::Value) -> NormalizedEvent {
+        let mut raw = json!({
+            "hook_type": "PostToolUse",
+            "session_id": "sess-1",
+            "_meta": {
+                "workspace_id": "my-project",
+                "terminal_session_id": "term-1",
+                "collected_at": "2026-02-20T15:00:00Z"
+            }
+        });
+        for (k, v) in extra.as_object().expect("object") {
+            raw[k] = v.clone();
+        }
+        normalize(&raw).expect("should normalize")
+    }
+
+    #[test]
+    fn test_valid_tool_succeeded() {
+        let event = post_tool_use(json!({"tool_name": "Read"}));
+        assert!(validate(&event).is_empty());
+    }
+
+    #[test]
+    fn test_missing_tool_name() {
+        let event = post_tool_use(json!({}));
+        let violations = validate(&event);
+        assert_eq!(violations, vec!["payload.tool_name is required for tool_* events"]);
+    }
+
+    #[test]
+    fn test_tool_failed_requires_exit_code() {
+        let event = post_tool_use(json!({"tool_name": "Bash", "error": "boom"}));
+        let violations = validate(&event);
+        assert_eq!(violations, vec!["payload.exit_code is required for tool_failed"]);
+
+        let event = post_tool_use(json!({"tool_name": "Bash", "error": "boom", "exit_code": 1}));
+        assert!(validate(&event).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_ts() {
+        let mut event = post_tool_use(json!({"tool_name": "Read"}));
+        event.ts = "yesterday".into();
+        assert_eq!(validate(&event).len(), 1);
+    }
+
+    #[test]
+    fn test_into_schema_error_preserves_original() {
+        let event = post_tool_use(json!({}));
+        let id = event.id.clone();
+        let violations = validate(&event);
+        let schema_error = into_schema_error(event, violations);
+
+        assert_eq!(schema_error.id, id);
+        assert_eq!(schema_error.event_type, EventType::SchemaError);
+        assert_eq!(schema_error.payload["original_event_type"], json!("tool_succeeded"));
+        assert_eq!(schema_error.payload["violations"].as_array().map(|v| v.len()), Some(1));
+    }
+}
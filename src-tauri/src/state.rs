@@ -1,6 +1,12 @@
 use crate::config::AppConfig;
 use crate::models::agent::SlotCounts;
+use crate::services::chat_queue::ChatQueue;
 use crate::services::cursor_poll::HitZone;
+use crate::services::failure::FailureClassifier;
+use crate::services::redaction::Redactor;
+use crate::services::scheduler::Scheduler;
+use crate::services::thinking::ThinkingThrottle;
+use crate::storage::agent_store::AgentStore;
 use crate::storage::db::DbPool;
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
@@ -10,8 +16,17 @@ use std::sync::{Arc, Mutex};
 #[derive(Clone)]
 pub struct AppState {
     pub db: DbPool,
+    /// agents + agent_state 메모리 사본 (write-through)
+    pub agents: Arc<AgentStore>,
     pub config: Arc<AppConfig>,
     pub slot_counts: Arc<Mutex<SlotCounts>>,
     pub cursor_polling_active: Arc<AtomicBool>,
     pub hit_zones: Arc<Mutex<Vec<HitZone>>>,
+    pub thinking_throttle: Arc<Mutex<ThinkingThrottle>>,
+    pub redactor: Arc<Redactor>,
+    pub failure_classifier: Arc<FailureClassifier>,
+    pub chat_queue: Arc<Mutex<ChatQueue>>,
+    pub scheduler: Arc<Scheduler>,
+    /// 업그레이드 이전(v1) 핑거프린트 행 중 가장 최근 ts. 이 시각 이후 이벤트는 v1 중복 비교를 건너뛴다
+    pub legacy_fingerprint_until: Option<Arc<str>>,
 }
@@ -0,0 +1,288 @@
+use crate::error::AppError;
+use crate::models::agent::{AgentState, MascotAgent};
+use crate::storage::agents_repo::AgentsRepo;
+use crate::storage::db::{self, DbPool};
+use crate::storage::state_repo::StateRepo;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+
+#[derive(Default)]
+struct Snapshot {
+    agents: HashMap<String, MascotAgent>,
+    states: HashMap<String, AgentState>,
+}
+
+/// agents + agent_state의 메모리 사본. 시작할 때 한 번 읽고, 이후 읽기는 메모리에서만 한다.
+/// 쓰기는 SQLite에 먼저 저장한 뒤 메모리에 반영한다 (write-through).
+/// 쓰기끼리는 writer 락으로 순서를 맞추고, 읽기는 DB 락을 기다리지 않는다.
+pub struct AgentStore {
+    db: DbPool,
+    snapshot: RwLock<Snapshot>,
+    writer: Mutex<()>,
+}
+
+impl AgentStore {
+    pub fn load(db: DbPool) -> Result<Self, AppError> {
+        let store = Self {
+            db,
+            snapshot: RwLock::new(Snapshot::default()),
+            writer: Mutex::new(()),
+        };
+        store.reload()?;
+        Ok(store)
+    }
+
+    /// DB에서 다시 읽는다
+    pub fn reload(&self) -> Result<(), AppError> {
+        let _writer = self.writer.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        self.reload_locked()
+    }
+
+    /// 워크스페이스 병합처럼 여러 행을 SQL로 직접 바꾸는 작업. writer 락을 잡은 채로 실행하고
+    /// 메모리 사본을 다시 읽는다 (그 사이 다른 쓰기가 옛 행으로 덮어쓰지 않도록).
+    pub fn bulk_update<T>(&self, f: impl FnOnce() -> Result<T, AppError>) -> Result<T, AppError> {
+        let _writer = self.writer.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let result = f();
+        self.reload_locked()?;
+        result
+    }
+
+    fn reload_locked(&self) -> Result<(), AppError> {
+        let agents = AgentsRepo::new(self.db.clone()).get_all()?;
+        let states = StateRepo::new(self.db.clone()).get_all()?;
+
+        let mut snapshot = self.write()?;
+        snapshot.agents = agents.into_iter().map(|a| (a.agent_id.clone(), a)).collect();
+        snapshot.states = states.into_iter().map(|s| (s.agent_id.clone(), s)).collect();
+        Ok(())
+    }
+
+    /// 현재 상태를 덮어쓴 에이전트 목록 (agent_id 순)
+    pub fn all_agents(&self) -> Result<Vec<MascotAgent>, AppError> {
+        let snapshot = self.read()?;
+        let mut agents: Vec<MascotAgent> = snapshot
+            .agents
+            .values()
+            .map(|agent| with_state(agent, snapshot.states.get(&agent.agent_id)))
+            .collect();
+        agents.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+        Ok(agents)
+    }
+
+    /// 현재 상태를 덮어쓴 에이전트
+    pub fn get_agent(&self, agent_id: &str) -> Result<Option<MascotAgent>, AppError> {
+        let snapshot = self.read()?;
+        Ok(snapshot
+            .agents
+            .get(agent_id)
+            .map(|agent| with_state(agent, snapshot.states.get(agent_id))))
+    }
+
+    pub fn get_state(&self, agent_id: &str) -> Result<Option<AgentState>, AppError> {
+        Ok(self.read()?.states.get(agent_id).cloned())
+    }
+
+    /// agent_id 순
+    pub fn all_states(&self) -> Result<Vec<AgentState>, AppError> {
+        let mut states: Vec<AgentState> = self.read()?.states.values().cloned().collect();
+        states.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+        Ok(states)
+    }
+
+    /// unit of work: agents/agent_state와 다른 테이블 쓰기를 한 트랜잭션으로 묶는다.
+    /// f 안에서 에이전트/상태는 `AgentTx`로 읽고 쓰고, 다른 repo는 `XRepo::new(tx.conn())`으로 만든다.
+    /// 커밋된 뒤에만 메모리 사본에 반영한다. f가 에러면 롤백하고 사본은 그대로 둔다.
+    pub fn transaction<T>(
+        &self,
+        f: impl FnOnce(&mut AgentTx<'_>) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let _writer = self.writer.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let (value, staged) = db::with_transaction(&self.db, |conn| {
+            let mut tx = AgentTx { conn, store: self, staged: Snapshot::default() };
+            let value = f(&mut tx)?;
+            Ok((value, tx.staged))
+        })?;
+
+        let mut snapshot = self.write()?;
+        snapshot.agents.extend(staged.agents);
+        snapshot.states.extend(staged.states);
+        Ok(value)
+    }
+
+    /// 신규 에이전트와 초기 상태를 저장한다
+    pub fn register(&self, agent: &MascotAgent, state: &AgentState) -> Result<(), AppError> {
+        self.transaction(|tx| tx.register(agent, state))
+    }
+
+    pub fn put_state(&self, state: &AgentState) -> Result<(), AppError> {
+        self.transaction(|tx| tx.put_state(state))
+    }
+
+    /// 드래그 드롭 후 home_x만 바꾼다
+    pub fn set_home_x(&self, agent_id: &str, home_x: f64) -> Result<(), AppError> {
+        let _writer = self.writer.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        StateRepo::new(self.db.clone()).update_home_x(agent_id, home_x)?;
+        if let Some(state) = self.write()?.states.get_mut(agent_id) {
+            state.home_x = home_x;
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> Result<std::sync::RwLockReadGuard<'_, Snapshot>, AppError> {
+        self.snapshot.read().map_err(|e| AppError::LockPoisoned(e.to_string()))
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, Snapshot>, AppError> {
+        self.snapshot.write().map_err(|e| AppError::LockPoisoned(e.to_string()))
+    }
+}
+
+/// `AgentStore::transaction` 안의 쓰기. 읽기는 이 트랜잭션에서 쓴 값을 먼저 본다
+pub struct AgentTx<'a> {
+    conn: &'a Connection,
+    store: &'a AgentStore,
+    staged: Snapshot,
+}
+
+impl<'a> AgentTx<'a> {
+    /// 같은 트랜잭션에서 쓸 연결 (`XRepo::new(tx.conn())`)
+    pub fn conn(&self) -> &'a Connection {
+        self.conn
+    }
+
+    pub fn agent_exists(&self, agent_id: &str) -> Result<bool, AppError> {
+        Ok(self.staged.agents.contains_key(agent_id)
+            || self.store.read()?.agents.contains_key(agent_id))
+    }
+
+    pub fn get_state(&self, agent_id: &str) -> Result<Option<AgentState>, AppError> {
+        match self.staged.states.get(agent_id) {
+            Some(state) => Ok(Some(state.clone())),
+            None => self.store.get_state(agent_id),
+        }
+    }
+
+    pub fn register(&mut self, agent: &MascotAgent, state: &AgentState) -> Result<(), AppError> {
+        AgentsRepo::new(self.conn).upsert(agent)?;
+        StateRepo::new(self.conn).upsert(state)?;
+        self.staged.agents.insert(agent.agent_id.clone(), agent.clone());
+        self.staged.states.insert(state.agent_id.clone(), state.clone());
+        Ok(())
+    }
+
+    pub fn put_state(&mut self, state: &AgentState) -> Result<(), AppError> {
+        StateRepo::new(self.conn).upsert(state)?;
+        self.staged.states.insert(state.agent_id.clone(), state.clone());
+        Ok(())
+    }
+}
+
+/// agents 테이블에 없는 현재 상태 필드를 덮어쓴다
+fn with_state(agent: &MascotAgent, state: Option<&AgentState>) -> MascotAgent {
+    let mut agent = agent.clone();
+    if let Some(s) = state {
+        agent.status = s.status.clone();
+        agent.thinking_text = s.thinking_text.clone();
+        agent.current_task = s.current_task.clone();
+        agent.activity = s.activity.clone();
+        agent.activity_summary = s.activity_summary.clone();
+    }
+    agent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agent::*;
+    use crate::storage::db::init_db_in_memory;
+
+    fn agent(id: &str) -> MascotAgent {
+        MascotAgent {
+            agent_id: id.to_string(),
+            display_name: id.to_string(),
+            role: AgentRole::Worker,
+            employment_type: EmploymentType::Contractor,
+            workspace_id: "ws".to_string(),
+            status: AgentStatus::Offline,
+            thinking_text: None,
+            current_task: None,
+            activity: None,
+            activity_summary: None,
+            appearance: AppearanceProfile {
+                body_index: 0, hair_index: 0, outfit_index: 0,
+                accessory_index: 0, face_index: 0,
+                hair_hue: 0.0, outfit_hue: 0.0, skin_hue: 0.0, skin_lightness: 80.0,
+            },
+            last_active_ts: "2026-02-20T15:00:00Z".to_string(),
+        }
+    }
+
+    fn state(id: &str, status: AgentStatus) -> AgentState {
+        AgentState {
+            agent_id: id.to_string(),
+            status,
+            prev_status: None,
+            thinking_text: None,
+            current_task: Some("Bash".into()),
+            activity: None,
+            activity_summary: None,
+            consecutive_failures: 0,
+            unresponsive: false,
+            workspace_id: "ws".to_string(),
+            since: "2026-02-20T15:00:00Z".to_string(),
+            last_event_ts: "2026-02-20T15:00:00Z".to_string(),
+            session_id: None,
+            peer_agent_id: None,
+            home_x: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_writes_go_through_to_db() {
+        let db = init_db_in_memory().expect("db init");
+        let store = AgentStore::load(db.clone()).expect("load");
+        store.register(&agent("ws/b"), &state("ws/b", AgentStatus::Offline)).expect("register");
+        store.register(&agent("ws/a"), &state("ws/a", AgentStatus::Offline)).expect("register");
+        store.put_state(&state("ws/a", AgentStatus::Working)).expect("put");
+        store.set_home_x("ws/a", 0.25).expect("home_x");
+
+        // 메모리에서 읽기: 상태가 덮어써지고 agent_id 순
+        let agents = store.all_agents().expect("all");
+        assert_eq!(agents.iter().map(|a| a.agent_id.as_str()).collect::<Vec<_>>(), ["ws/a", "ws/b"]);
+        assert_eq!(agents[0].status, AgentStatus::Working);
+        assert_eq!(agents[0].current_task.as_deref(), Some("Bash"));
+
+        // 새로 읽어도 같은 내용
+        let reloaded = AgentStore::load(db).expect("load");
+        let a = reloaded.get_state("ws/a").expect("get").expect("exists");
+        assert_eq!(a.status, AgentStatus::Working);
+        assert_eq!(a.home_x, 0.25);
+        assert_eq!(reloaded.get_agent("ws/b").expect("get").expect("exists").status, AgentStatus::Offline);
+        assert!(reloaded.get_agent("ws/c").expect("get").is_none());
+    }
+
+    #[test]
+    fn test_failed_transaction_rolls_back_db_and_memory() {
+        let db = init_db_in_memory().expect("db init");
+        let store = AgentStore::load(db.clone()).expect("load");
+        store.register(&agent("ws/a"), &state("ws/a", AgentStatus::Idle)).expect("register");
+
+        let result: Result<(), AppError> = store.transaction(|tx| {
+            tx.register(&agent("ws/b"), &state("ws/b", AgentStatus::Offline))?;
+            tx.put_state(&state("ws/a", AgentStatus::Working))?;
+            // 트랜잭션 안에서는 방금 쓴 값이 보인다
+            assert!(tx.agent_exists("ws/b")?);
+            assert_eq!(tx.get_state("ws/a")?.map(|s| s.status), Some(AgentStatus::Working));
+            Err(AppError::StateTransition("boom".into()))
+        });
+        assert!(result.is_err());
+
+        // 메모리도 DB도 이전 그대로
+        assert!(store.get_agent("ws/b").expect("get").is_none());
+        assert_eq!(store.get_state("ws/a").expect("get").map(|s| s.status), Some(AgentStatus::Idle));
+        let reloaded = AgentStore::load(db).expect("load");
+        assert!(reloaded.get_agent("ws/b").expect("get").is_none());
+        assert_eq!(reloaded.get_state("ws/a").expect("get").map(|s| s.status), Some(AgentStatus::Idle));
+    }
+}
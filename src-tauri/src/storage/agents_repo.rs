@@ -1,14 +1,14 @@
 use crate::error::AppError;
 use crate::models::agent::*;
-use crate::storage::db::DbPool;
+use crate::storage::db::DbHandle;
 
-pub struct AgentsRepo {
-    db: DbPool,
+pub struct AgentsRepo<'a> {
+    db: DbHandle<'a>,
 }
 
-impl AgentsRepo {
-    pub fn new(db: DbPool) -> Self {
-        Self { db }
+impl<'a> AgentsRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
     }
 
     /// 에이전트를 저장하거나 갱신한다.
@@ -16,7 +16,7 @@ impl AgentsRepo {
     /// 갱신하지 않는다. 최초 insert 시 last_active_ts를 first_seen_ts로 사용하는
     /// 이유는 에이전트를 처음 인지한 시점 = 해당 이벤트의 타임스탬프이기 때문이다.
     pub fn upsert(&self, agent: &MascotAgent) -> Result<(), AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let appearance_json = serde_json::to_string(&agent.appearance)
             .map_err(|e| AppError::Normalize(e.to_string()))?;
 
@@ -46,7 +46,7 @@ impl AgentsRepo {
     /// 기본값이 설정된다. 호출자는 StateRepo에서 AgentState를 조회하여
     /// 이 필드들을 덮어써야 한다.
     pub fn get_by_id(&self, agent_id: &str) -> Result<Option<MascotAgent>, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let mut stmt = conn.prepare(
             "SELECT agent_id, display_name, role, employment_type, workspace_id, appearance_json, last_active_ts
              FROM agents WHERE agent_id = ?1",
@@ -70,6 +70,8 @@ impl AgentsRepo {
                 status: AgentStatus::Offline,
                 thinking_text: None,
                 current_task: None,
+                activity: None,
+                activity_summary: None,
                 appearance: serde_json::from_str(&appearance_str).map_err(|e| {
                     rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e))
                 })?,
@@ -89,7 +91,7 @@ impl AgentsRepo {
     /// 기본값이 설정된다. 호출자는 StateRepo에서 AgentState를 조회하여
     /// 이 필드들을 덮어써야 한다.
     pub fn get_all(&self) -> Result<Vec<MascotAgent>, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let mut stmt = conn.prepare(
             "SELECT agent_id, display_name, role, employment_type, workspace_id, appearance_json, last_active_ts
              FROM agents",
@@ -118,6 +120,8 @@ impl AgentsRepo {
                     status: AgentStatus::Offline,
                     thinking_text: None,
                     current_task: None,
+                    activity: None,
+                    activity_summary: None,
                     appearance: serde_json::from_str(&appearance_str).map_err(|e| {
                         rusqlite::Error::FromSqlConversionFailure(
                             5, rusqlite::types::Type::Text, Box::new(e),
@@ -147,6 +151,8 @@ mod tests {
             status: AgentStatus::Idle,
             thinking_text: None,
             current_task: None,
+            activity: None,
+            activity_summary: None,
             appearance: AppearanceProfile {
                 body_index: 0, hair_index: 1, outfit_index: 0,
                 accessory_index: 0, face_index: 0,
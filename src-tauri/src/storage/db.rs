@@ -1,18 +1,127 @@
 use crate::error::AppError;
 use rusqlite::Connection;
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
 
 pub type DbPool = Arc<Mutex<Connection>>;
 
+/// repo가 쓰는 연결. 풀이면 호출마다 락을 잡고, unit of work 안이면 그 트랜잭션의 연결을 그대로 쓴다.
+/// `XRepo::new(state.db.clone())`와 `XRepo::new(conn)`이 같은 메서드를 공유한다.
+#[derive(Clone)]
+pub enum DbHandle<'a> {
+    Pool(DbPool),
+    Tx(&'a Connection),
+}
+
+impl From<DbPool> for DbHandle<'_> {
+    fn from(db: DbPool) -> Self {
+        DbHandle::Pool(db)
+    }
+}
+
+impl<'a> From<&'a Connection> for DbHandle<'a> {
+    fn from(conn: &'a Connection) -> Self {
+        DbHandle::Tx(conn)
+    }
+}
+
+impl DbHandle<'_> {
+    pub fn conn(&self) -> Result<Conn<'_>, AppError> {
+        match self {
+            DbHandle::Pool(db) => db
+                .lock()
+                .map(Conn::Locked)
+                .map_err(|e| AppError::LockPoisoned(e.to_string())),
+            DbHandle::Tx(conn) => Ok(Conn::Borrowed(conn)),
+        }
+    }
+}
+
+/// `DbHandle::conn`이 돌려주는 연결 (잠근 풀 또는 빌린 트랜잭션)
+pub enum Conn<'a> {
+    Locked(MutexGuard<'a, Connection>),
+    Borrowed(&'a Connection),
+}
+
+impl Deref for Conn<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            Conn::Locked(guard) => guard,
+            Conn::Borrowed(conn) => conn,
+        }
+    }
+}
+
+/// unit of work: DB를 잠그고 한 트랜잭션 안에서 f를 실행한다. f가 에러면 전부 롤백한다.
+/// f 안에서는 풀(`state.db`)을 다시 잠그면 안 된다 (repo는 넘겨받은 연결로 만든다).
+pub fn with_transaction<T>(
+    db: &DbPool,
+    f: impl FnOnce(&Connection) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let conn = db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+    in_transaction(&conn, f)
+}
+
+/// f를 트랜잭션 안에서 실행한다. 이미 unit of work 안이면 그 트랜잭션에 합류하고 커밋은 바깥에 맡긴다
+pub fn in_transaction<T>(
+    conn: &Connection,
+    f: impl FnOnce(&Connection) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    if !conn.is_autocommit() {
+        return f(conn);
+    }
+    let tx = conn.unchecked_transaction()?;
+    let value = f(&tx)?;
+    tx.commit()?;
+    Ok(value)
+}
+
+/// 스키마 마이그레이션 한 단계. version 순으로 한 번씩, 단계마다 트랜잭션 안에서 실행하고
+/// 같은 트랜잭션에서 `PRAGMA user_version`을 올린다. 실패하면 그 단계 전체가 롤백된다.
+struct Migration {
+    version: i64,
+    name: &'static str,
+    up: fn(&Connection) -> Result<(), AppError>,
+}
+
+/// 새 단계는 끝에만 추가한다. 이미 배포된 단계는 고치지 않는다.
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "baseline", up: baseline },
+    Migration { version: 2, name: "events_agent_ts_index", up: events_agent_ts_index },
+];
+
+/// 이 앱이 아는 최신 스키마 버전
+pub const SCHEMA_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
 /// SQLite 연결을 열고 마이그레이션을 실행한다.
+/// 기존 DB를 올려야 하면 먼저 같은 디렉토리에 `<파일명>.v<현재 버전>.bak` 사본을 만든다.
 pub fn init_db(db_path: &Path) -> Result<DbPool, AppError> {
     let conn = Connection::open(db_path)?;
 
+    let is_new = !has_tables(&conn)?;
+    if is_new {
+        // 파일에 첫 페이지가 쓰이기 전(WAL 전환 전)에만 VACUUM 없이 바로 적용된다
+        conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+    }
+
     conn.execute_batch("PRAGMA journal_mode=WAL;")?;
     conn.execute_batch("PRAGMA foreign_keys=ON;")?;
 
+    let from = schema_version(&conn)?;
+    if from < SCHEMA_VERSION && !is_new {
+        let backup = backup_path(db_path, from);
+        backup_db(&conn, &backup)?;
+        tracing::info!(
+            "db: backed up schema v{from} to {} before migrating to v{SCHEMA_VERSION}",
+            backup.display(),
+        );
+    }
+
     run_migrations(&conn)?;
+    log_pending_incremental_vacuum(&conn)?;
 
     Ok(Arc::new(Mutex::new(conn)))
 }
@@ -27,6 +136,69 @@ pub fn init_db_in_memory() -> Result<DbPool, AppError> {
 }
 
 fn run_migrations(conn: &Connection) -> Result<(), AppError> {
+    let current = schema_version(conn)?;
+    if current > SCHEMA_VERSION {
+        return Err(AppError::Migration(format!(
+            "database schema v{current} is newer than this app (v{SCHEMA_VERSION})"
+        )));
+    }
+    migrate(conn, MIGRATIONS)
+}
+
+fn migrate(conn: &Connection, migrations: &[Migration]) -> Result<(), AppError> {
+    let current = schema_version(conn)?;
+    for step in migrations.iter().filter(|m| m.version > current) {
+        let tx = conn.unchecked_transaction()?;
+        (step.up)(&tx)
+            .map_err(|e| AppError::Migration(format!("v{} {}: {e}", step.version, step.name)))?;
+        tx.pragma_update(None, "user_version", step.version)?;
+        tx.commit()?;
+        tracing::info!("db: migrated to schema v{} ({})", step.version, step.name);
+    }
+    Ok(())
+}
+
+/// 이벤트 정리 후 빈 페이지를 파일에서 반환하려면 auto_vacuum=INCREMENTAL이어야 한다.
+/// 새 DB는 만들 때 켜고, 기존 DB는 전환에 전체 VACUUM이 필요하다 (큰 파일은 오래 걸리고
+/// 파일 크기만큼 여유 공간이 든다). 시작을 막지 않도록 compact_db에서 전환하고, 그 전까지는 로그만 남긴다.
+fn log_pending_incremental_vacuum(conn: &Connection) -> Result<(), AppError> {
+    let mode: i64 = conn.pragma_query_value(None, "auto_vacuum", |row| row.get(0))?;
+    if mode != 2 {
+        tracing::info!(
+            "db: incremental auto_vacuum pending; run compact_db to convert (full VACUUM)"
+        );
+    }
+    Ok(())
+}
+
+fn schema_version(conn: &Connection) -> Result<i64, AppError> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+fn has_tables(conn: &Connection) -> Result<bool, AppError> {
+    let count: i64 =
+        conn.query_row("SELECT count(*) FROM sqlite_master WHERE type = 'table'", [], |row| row.get(0))?;
+    Ok(count > 0)
+}
+
+fn backup_path(db_path: &Path, version: i64) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{version}.bak"));
+    db_path.with_file_name(name)
+}
+
+/// WAL에 남은 내용까지 포함한 일관된 사본. 같은 버전의 이전 사본은 덮어쓴다.
+fn backup_db(conn: &Connection, backup: &Path) -> Result<(), AppError> {
+    if backup.exists() {
+        std::fs::remove_file(backup)?;
+    }
+    conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()])?;
+    Ok(())
+}
+
+/// v1: 버전 관리 이전 스키마. user_version 0인 DB는 만들어진 시기에 따라 테이블/컬럼이 제각각이므로
+/// IF NOT EXISTS와 컬럼 보강으로 맞춘다. 이후 단계는 정확히 이전 버전의 스키마를 가정한다.
+fn baseline(conn: &Connection) -> Result<(), AppError> {
     conn.execute_batch(
         "
         CREATE TABLE IF NOT EXISTS agents (
@@ -52,6 +224,10 @@ fn run_migrations(conn: &Connection) -> Result<(), AppError> {
             session_id TEXT,
             peer_agent_id TEXT,
             home_x REAL NOT NULL DEFAULT 0.0,
+            activity TEXT,
+            activity_summary TEXT,
+            consecutive_failures INTEGER NOT NULL DEFAULT 0,
+            unresponsive INTEGER NOT NULL DEFAULT 0,
             FOREIGN KEY (agent_id) REFERENCES agents(agent_id)
         );
 
@@ -72,7 +248,9 @@ fn run_migrations(conn: &Connection) -> Result<(), AppError> {
             payload_json TEXT,
             thinking_text TEXT,
             raw_json TEXT,
-            fingerprint TEXT UNIQUE
+            fingerprint TEXT UNIQUE,
+            fingerprint_version INTEGER,
+            redactions_json TEXT
         );
 
         CREATE INDEX IF NOT EXISTS idx_events_agent_id ON events(agent_id);
@@ -80,12 +258,98 @@ fn run_migrations(conn: &Connection) -> Result<(), AppError> {
         CREATE INDEX IF NOT EXISTS idx_events_type ON events(event_type);
         CREATE INDEX IF NOT EXISTS idx_agent_state_workspace ON agent_state(workspace_id);
 
+        CREATE TABLE IF NOT EXISTS thinking_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id TEXT NOT NULL,
+            ts TEXT NOT NULL,
+            text TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_thinking_history_agent ON thinking_history(agent_id, id);
+
+        CREATE TABLE IF NOT EXISTS tasks (
+            workspace_id TEXT NOT NULL,
+            task_id TEXT NOT NULL,
+            title TEXT,
+            description TEXT,
+            owner_agent_id TEXT,
+            status TEXT NOT NULL,
+            status_history_json TEXT NOT NULL DEFAULT '[]',
+            created_ts TEXT,
+            started_ts TEXT,
+            completed_ts TEXT,
+            updated_ts TEXT NOT NULL,
+            PRIMARY KEY (workspace_id, task_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS agent_transitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id TEXT NOT NULL,
+            workspace_id TEXT NOT NULL,
+            from_status TEXT NOT NULL,
+            to_status TEXT NOT NULL,
+            event_id TEXT,
+            started_ts TEXT NOT NULL,
+            ts TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            started_ms INTEGER,
+            ended_ms INTEGER
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_transitions_agent ON agent_transitions(agent_id, ended_ms);
+        CREATE INDEX IF NOT EXISTS idx_transitions_workspace ON agent_transitions(workspace_id, ended_ms);
+
+        CREATE TABLE IF NOT EXISTS workspace_aliases (
+            alias TEXT PRIMARY KEY,
+            workspace_id TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_events_workspace ON events(workspace_id);
+
         CREATE TABLE IF NOT EXISTS settings (
             key TEXT PRIMARY KEY,
             value TEXT NOT NULL
         );
         ",
     )?;
+
+    // 기존 DB의 테이블에는 나중에 추가된 컬럼이 없다.
+    // fingerprint_version이 NULL인 행은 v1 스킴으로 저장된 것으로 취급한다.
+    add_column_if_missing(conn, "events", "fingerprint_version", "INTEGER")?;
+    add_column_if_missing(conn, "events", "redactions_json", "TEXT")?;
+    add_column_if_missing(conn, "agent_state", "activity", "TEXT")?;
+    add_column_if_missing(conn, "agent_state", "activity_summary", "TEXT")?;
+    add_column_if_missing(conn, "agent_state", "consecutive_failures", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "agent_state", "unresponsive", "INTEGER NOT NULL DEFAULT 0")?;
+    Ok(())
+}
+
+/// v2: 에이전트별 최근 이벤트 조회(이력서, 상태 보정)가 ts 정렬까지 인덱스로 처리하도록
+fn events_agent_ts_index(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "
+        CREATE INDEX IF NOT EXISTS idx_events_agent_ts ON events(agent_id, ts);
+        DROP INDEX IF EXISTS idx_events_agent_id;
+        ",
+    )?;
+    Ok(())
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> Result<(), AppError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl};"))?;
+    }
     Ok(())
 }
 
@@ -93,6 +357,53 @@ fn run_migrations(conn: &Connection) -> Result<(), AppError> {
 mod tests {
     use super::*;
 
+    /// 배포된 스키마로 만든 실제 DB 덤프 (tests/fixtures/db). MIGRATIONS로 만들지 않으므로
+    /// 마이그레이션이 과거 스키마와 어긋나면 여기서 드러난다. 모두 같은 데이터 행을 담고 있다
+    const BASELINE_DUMP: &str = include_str!("../../tests/fixtures/db/baseline_v0.sql");
+    /// user_version 도입 직전 릴리스. v1(baseline 단계) 결과와 스키마가 같다
+    const UNVERSIONED_DUMP: &str = include_str!("../../tests/fixtures/db/unversioned_v0.sql");
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})")).expect("prepare");
+        stmt.query_map([], |row| row.get::<_, String>(1))
+            .expect("query")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("collect")
+    }
+
+    fn index_exists(conn: &Connection, name: &str) -> bool {
+        conn.query_row(
+            "SELECT count(*) FROM sqlite_master WHERE type = 'index' AND name = ?1",
+            [name],
+            |row| row.get::<_, i64>(0),
+        )
+        .expect("query")
+            > 0
+    }
+
+    /// 최신 스키마이고 픽스처 데이터가 그대로 남아 있는지
+    fn assert_latest_with_fixture(conn: &Connection) {
+        assert_eq!(schema_version(conn).expect("version"), SCHEMA_VERSION);
+        assert!(columns(conn, "agent_state").contains(&"consecutive_failures".to_string()));
+        assert!(columns(conn, "events").contains(&"redactions_json".to_string()));
+        assert!(index_exists(conn, "idx_events_agent_ts"));
+        assert!(!index_exists(conn, "idx_events_agent_id"));
+
+        let (status, failures): (String, i64) = conn
+            .query_row(
+                "SELECT status, consecutive_failures FROM agent_state WHERE agent_id = 'ws/worker'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("state row survives");
+        assert_eq!(status, "\"working\"");
+        assert_eq!(failures, 0);
+        let events: i64 = conn
+            .query_row("SELECT count(*) FROM events WHERE fingerprint = 'fp-1'", [], |row| row.get(0))
+            .expect("query");
+        assert_eq!(events, 1);
+    }
+
     #[test]
     fn test_init_db_in_memory() {
         let db = init_db_in_memory().expect("should init in-memory db");
@@ -101,5 +412,149 @@ mod tests {
             .query_row("SELECT count(*) FROM agents", [], |row| row.get(0))
             .expect("should query");
         assert_eq!(count, 0);
+        assert_eq!(schema_version(&conn).expect("version"), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrates_real_dumps() {
+        for (name, dump, version) in [
+            ("baseline_v0", BASELINE_DUMP, 0),
+            ("unversioned_v0", UNVERSIONED_DUMP, 0),
+            ("v1", UNVERSIONED_DUMP, 1),
+        ] {
+            let conn = Connection::open_in_memory().expect("open");
+            conn.execute_batch(dump).unwrap_or_else(|e| panic!("load {name}: {e}"));
+            // 덤프는 적재 순서 때문에 외래 키를 끈다. init_db처럼 다시 켜고 마이그레이션
+            conn.execute_batch("PRAGMA foreign_keys=ON;").expect("foreign keys");
+            conn.pragma_update(None, "user_version", version).expect("set version");
+            run_migrations(&conn).unwrap_or_else(|e| panic!("migrate {name}: {e}"));
+            assert_latest_with_fixture(&conn);
+
+            let home_x: f64 = conn
+                .query_row("SELECT home_x FROM agent_state WHERE agent_id = 'ws/worker'", [], |row| {
+                    row.get(0)
+                })
+                .expect("query");
+            assert_eq!(home_x, 0.42, "{name}");
+            let language: String = conn
+                .query_row("SELECT value FROM settings WHERE key = 'language'", [], |row| row.get(0))
+                .expect("settings row survives");
+            assert_eq!(language, "ko", "{name}");
+        }
+    }
+
+    #[test]
+    fn test_failed_step_rolls_back_and_newer_schema_is_rejected() {
+        fn broken(conn: &Connection) -> Result<(), AppError> {
+            conn.execute_batch("CREATE TABLE half_done (id INTEGER); SELECT * FROM no_such_table;")?;
+            Ok(())
+        }
+        let conn = Connection::open_in_memory().expect("open");
+        let steps = [
+            Migration { version: 1, name: "baseline", up: baseline },
+            Migration { version: 2, name: "broken", up: broken },
+        ];
+        let err = migrate(&conn, &steps).expect_err("step 2 fails");
+        assert!(err.to_string().contains("v2 broken"), "{err}");
+        // 1단계는 커밋, 2단계는 테이블 생성까지 롤백
+        assert_eq!(schema_version(&conn).expect("version"), 1);
+        assert!(columns(&conn, "half_done").is_empty());
+
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).expect("set version");
+        assert!(run_migrations(&conn).is_err());
+    }
+
+    #[test]
+    fn test_unit_of_work_rolls_back_repo_writes() {
+        use crate::storage::settings_repo::SettingsRepo;
+
+        let db = init_db_in_memory().expect("db init");
+        let result: Result<(), AppError> = with_transaction(&db, |tx| {
+            SettingsRepo::new(tx).set("language", "ko")?;
+            // 안쪽 in_transaction은 바깥 트랜잭션에 합류한다 (자체 커밋 없음)
+            in_transaction(tx, |inner| SettingsRepo::new(inner).set("autostart", "true"))?;
+            Err(AppError::InvalidArgument("boom".into()))
+        });
+        assert!(result.is_err());
+
+        let settings = SettingsRepo::new(db.clone());
+        assert_eq!(settings.get("language").expect("get"), None);
+        assert_eq!(settings.get("autostart").expect("get"), None);
+
+        with_transaction(&db, |tx| SettingsRepo::new(tx).set("language", "ko")).expect("commit");
+        assert_eq!(settings.get("language").expect("get").as_deref(), Some("ko"));
+    }
+
+    fn auto_vacuum(db: &DbPool) -> i64 {
+        db.lock()
+            .expect("lock")
+            .pragma_query_value(None, "auto_vacuum", |row| row.get(0))
+            .expect("pragma")
+    }
+
+    #[test]
+    fn test_init_db_backs_up_before_upgrade() {
+        use crate::storage::maintenance_repo::MaintenanceRepo;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("mascot.db");
+        {
+            let conn = Connection::open(&path).expect("open");
+            conn.execute_batch(BASELINE_DUMP).expect("baseline dump");
+        }
+
+        let db = init_db(&path).expect("init");
+        assert_latest_with_fixture(&db.lock().expect("lock"));
+        // 기존 DB는 시작 시 전체 VACUUM하지 않는다. compact_db에서 전환
+        assert_eq!(auto_vacuum(&db), 0, "pending");
+        MaintenanceRepo::new(db.clone()).vacuum().expect("vacuum");
+        assert_eq!(auto_vacuum(&db), 2, "incremental");
+
+        let backup = dir.path().join("mascot.db.v0.bak");
+        let old = Connection::open(&backup).expect("open backup");
+        assert_eq!(schema_version(&old).expect("version"), 0);
+        assert!(!columns(&old, "agent_state").contains(&"consecutive_failures".to_string()));
+        drop(db);
+
+        // 최신 버전이면 사본을 만들지 않는다
+        init_db(&path).expect("reopen");
+        let backups = std::fs::read_dir(dir.path())
+            .expect("read_dir")
+            .filter(|e| e.as_ref().expect("entry").file_name().to_string_lossy().ends_with(".bak"))
+            .count();
+        assert_eq!(backups, 1);
+
+        // 새 DB는 사본 없이 처음부터 incremental
+        let fresh = init_db(&dir.path().join("fresh.db")).expect("fresh");
+        assert!(!dir.path().join("fresh.db.v0.bak").exists());
+        assert_eq!(auto_vacuum(&fresh), 2, "incremental");
+    }
+
+    #[test]
+    fn test_migrations_add_missing_event_columns() {
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(
+            "CREATE TABLE events (
+                id TEXT PRIMARY KEY, version TEXT NOT NULL, ts TEXT NOT NULL,
+                event_type TEXT NOT NULL, source TEXT NOT NULL, workspace_id TEXT NOT NULL,
+                terminal_session_id TEXT NOT NULL, run_id TEXT, session_id TEXT,
+                agent_id TEXT NOT NULL, target_agent_id TEXT, task_id TEXT,
+                severity TEXT NOT NULL DEFAULT 'info', payload_json TEXT,
+                thinking_text TEXT, raw_json TEXT, fingerprint TEXT UNIQUE
+            );",
+        )
+        .expect("create old events table");
+
+        run_migrations(&conn).expect("migrate");
+        run_migrations(&conn).expect("migrations are idempotent");
+
+        let mut stmt = conn.prepare("PRAGMA table_info(events)").expect("prepare");
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .expect("query")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("collect");
+        assert!(columns.contains(&"fingerprint_version".to_string()));
+        assert!(columns.contains(&"redactions_json".to_string()));
     }
 }
@@ -1,6 +1,6 @@
 use crate::error::AppError;
-use crate::models::event::{NormalizedEvent, ResumeEvent};
-use crate::storage::db::DbPool;
+use crate::models::event::{EventType, Fingerprint, NormalizedEvent, ResumeEvent, SchemaErrorCount};
+use crate::storage::db::DbHandle;
 
 fn extract_summary_from_type(event_type: &str, payload_str: &str) -> String {
     let payload: serde_json::Value = serde_json::from_str(payload_str).unwrap_or_default();
@@ -17,18 +17,31 @@ fn extract_summary_from_type(event_type: &str, payload_str: &str) -> String {
     }
 }
 
-pub struct EventsRepo {
-    db: DbPool,
+pub struct EventsRepo<'a> {
+    db: DbHandle<'a>,
 }
 
-impl EventsRepo {
-    pub fn new(db: DbPool) -> Self {
-        Self { db }
+impl<'a> EventsRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
     }
 
     /// 이벤트 저장. fingerprint 중복 시 skip (upsert).
-    pub fn insert(&self, event: &NormalizedEvent, fingerprint: &str) -> Result<bool, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+    /// 업그레이드 이전 스킴(v1)으로 저장된 행은 legacy_value가 있을 때만 그 값으로 중복 여부를 확인한다.
+    pub fn insert(&self, event: &NormalizedEvent, fingerprint: &Fingerprint) -> Result<bool, AppError> {
+        let conn = self.db.conn()?;
+
+        if let Some(legacy) = &fingerprint.legacy_value {
+            let legacy_exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM events WHERE fingerprint = ?1
+                 AND (fingerprint_version IS NULL OR fingerprint_version = 1))",
+                rusqlite::params![legacy],
+                |row| row.get(0),
+            )?;
+            if legacy_exists {
+                return Ok(false);
+            }
+        }
 
         let event_type_str = serde_json::to_string(&event.event_type)
             .map_err(|e| AppError::Normalize(e.to_string()))?;
@@ -38,12 +51,20 @@ impl EventsRepo {
             .map_err(|e| AppError::Normalize(e.to_string()))?;
         let payload_json = event.payload.to_string();
         let raw_json = event.raw.to_string();
+        let redactions_json = if event.redactions.is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::to_string(&event.redactions)
+                    .map_err(|e| AppError::Normalize(e.to_string()))?,
+            )
+        };
 
         let rows = conn.execute(
             "INSERT OR IGNORE INTO events (id, version, ts, event_type, source, workspace_id,
              terminal_session_id, run_id, session_id, agent_id, target_agent_id, task_id,
-             severity, payload_json, thinking_text, raw_json, fingerprint)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+             severity, payload_json, thinking_text, raw_json, fingerprint, fingerprint_version, redactions_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
             rusqlite::params![
                 event.id,
                 event.version,
@@ -61,7 +82,9 @@ impl EventsRepo {
                 payload_json,
                 event.thinking_text,
                 raw_json,
-                fingerprint,
+                fingerprint.value,
+                fingerprint.version,
+                redactions_json,
             ],
         )?;
 
@@ -70,7 +93,7 @@ impl EventsRepo {
 
     /// 에이전트의 최근 이벤트 조회 (이력서용)
     pub fn get_recent_by_agent(&self, agent_id: &str, limit: usize) -> Result<Vec<ResumeEvent>, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let mut stmt = conn.prepare(
             "SELECT event_type, payload_json, ts FROM events WHERE agent_id = ?1 ORDER BY ts DESC LIMIT ?2",
         )?;
@@ -95,7 +118,7 @@ impl EventsRepo {
 
     /// 완료된 작업 수 카운트
     pub fn count_completed_tasks(&self, agent_id: &str) -> Result<u64, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let count: i64 = conn.query_row(
             "SELECT COUNT(*) FROM events WHERE agent_id = ?1 AND event_type = '\"task_completed\"'",
             rusqlite::params![agent_id],
@@ -106,7 +129,7 @@ impl EventsRepo {
 
     /// 사용한 도구 수 카운트
     pub fn count_tools_used(&self, agent_id: &str) -> Result<u64, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let count: i64 = conn.query_row(
             "SELECT COUNT(*) FROM events WHERE agent_id = ?1 AND event_type = '\"tool_started\"'",
             rusqlite::params![agent_id],
@@ -114,6 +137,93 @@ impl EventsRepo {
         )?;
         Ok(count as u64)
     }
+
+    /// 세션에서 온 마지막 이벤트 타입 (state-machine.md §5.2 상태 보정용).
+    /// synthetic 이벤트와 상태를 알려주지 않는 heartbeat/notification/schema_error는 제외한다.
+    pub fn last_session_event_type(&self, agent_id: &str) -> Result<Option<EventType>, AppError> {
+        let conn = self.db.conn()?;
+        let result = conn.query_row(
+            "SELECT event_type FROM events WHERE agent_id = ?1 AND source != '\"synthetic\"'
+             AND event_type NOT IN ('\"heartbeat\"', '\"notification\"', '\"schema_error\"')
+             ORDER BY ts DESC LIMIT 1",
+            rusqlite::params![agent_id],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(event_type) => serde_json::from_str(&event_type)
+                .map(Some)
+                .map_err(|e| AppError::Normalize(e.to_string())),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 워크스페이스별 schema_error 이벤트 수 (많은 순)
+    pub fn count_schema_errors_by_workspace(&self) -> Result<Vec<SchemaErrorCount>, AppError> {
+        let conn = self.db.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT workspace_id, COUNT(*) FROM events WHERE event_type = '\"schema_error\"'
+             GROUP BY workspace_id ORDER BY COUNT(*) DESC, workspace_id",
+        )?;
+
+        let counts = stmt
+            .query_map([], |row| {
+                let count: i64 = row.get(1)?;
+                Ok(SchemaErrorCount {
+                    workspace_id: row.get(0)?,
+                    count: count as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(counts)
+    }
+
+    /// ts가 before_ts보다 이른 이벤트를 최대 limit개 삭제한다 (보존 기간).
+    /// ts는 RFC3339 UTC 문자열이라 사전순 비교로 충분하다. 삭제한 수를 반환
+    pub fn delete_before(&self, before_ts: &str, limit: usize) -> Result<u64, AppError> {
+        let conn = self.db.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM events WHERE rowid IN
+             (SELECT rowid FROM events WHERE ts < ?1 LIMIT ?2)",
+            rusqlite::params![before_ts, limit as i64],
+        )?;
+        Ok(deleted as u64)
+    }
+
+    /// 최신 keep개를 넘는 오래된 이벤트를 최대 limit개 삭제한다 (최대 행 수)
+    pub fn delete_beyond(&self, keep: u64, limit: usize) -> Result<u64, AppError> {
+        let conn = self.db.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM events WHERE rowid IN
+             (SELECT rowid FROM events ORDER BY ts DESC, rowid DESC LIMIT ?2 OFFSET ?1)",
+            rusqlite::params![keep as i64, limit as i64],
+        )?;
+        Ok(deleted as u64)
+    }
+
+    /// v1 스킴(fingerprint_version 없음)으로 저장된 행 중 가장 최근 ts. 시작 시 한 번 조회한다
+    pub fn legacy_fingerprint_until(&self) -> Result<Option<String>, AppError> {
+        let conn = self.db.conn()?;
+        let ts = conn.query_row(
+            "SELECT MAX(ts) FROM events WHERE fingerprint_version IS NULL OR fingerprint_version = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(ts)
+    }
+
+    /// ts가 before_ts보다 이른 이벤트의 raw_json을 최대 limit개 지운다. 정규화된 필드는 남긴다
+    pub fn strip_raw_before(&self, before_ts: &str, limit: usize) -> Result<u64, AppError> {
+        let conn = self.db.conn()?;
+        let stripped = conn.execute(
+            "UPDATE events SET raw_json = NULL WHERE rowid IN
+             (SELECT rowid FROM events WHERE ts < ?1 AND raw_json IS NOT NULL LIMIT ?2)",
+            rusqlite::params![before_ts, limit as i64],
+        )?;
+        Ok(stripped as u64)
+    }
 }
 
 #[cfg(test)]
@@ -122,7 +232,7 @@ mod tests {
     use crate::models::event::*;
     use crate::storage::db::init_db_in_memory;
 
-    fn make_test_event(id: &str, fingerprint_suffix: &str) -> (NormalizedEvent, String) {
+    fn make_test_event(id: &str, fingerprint_suffix: &str) -> (NormalizedEvent, Fingerprint) {
         let event = NormalizedEvent {
             id: id.to_string(),
             version: "1.0".to_string(),
@@ -140,8 +250,13 @@ mod tests {
             payload: serde_json::json!({}),
             thinking_text: None,
             raw: serde_json::json!({"type": "test"}),
+            redactions: Vec::new(),
+        };
+        let fingerprint = Fingerprint {
+            value: format!("fp-{}", fingerprint_suffix),
+            version: 2,
+            legacy_value: Some(format!("legacy-{}", fingerprint_suffix)),
         };
-        let fingerprint = format!("fp-{}", fingerprint_suffix);
         (event, fingerprint)
     }
 
@@ -149,6 +264,7 @@ mod tests {
     fn test_insert_returns_true() {
         let db = init_db_in_memory().expect("db init");
         let repo = EventsRepo::new(db);
+        assert_eq!(repo.legacy_fingerprint_until().expect("query"), None);
         let (event, fp) = make_test_event("evt-1", "1");
         let inserted = repo.insert(&event, &fp).expect("insert");
         assert!(inserted);
@@ -165,4 +281,82 @@ mod tests {
         let inserted = repo.insert(&event2, &fp).expect("second insert same fp");
         assert!(!inserted);
     }
+
+    #[test]
+    fn test_count_schema_errors_by_workspace() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = EventsRepo::new(db);
+        for (i, workspace) in ["ws-a", "ws-b", "ws-a"].iter().enumerate() {
+            let (mut event, fp) = make_test_event(&format!("evt-{i}"), &i.to_string());
+            event.event_type = EventType::SchemaError;
+            event.workspace_id = workspace.to_string();
+            repo.insert(&event, &fp).expect("insert");
+        }
+        let (event, fp) = make_test_event("evt-ok", "ok");
+        repo.insert(&event, &fp).expect("insert");
+
+        let counts = repo.count_schema_errors_by_workspace().expect("count");
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].workspace_id, "ws-a");
+        assert_eq!(counts[0].count, 2);
+        assert_eq!(counts[1].count, 1);
+    }
+
+    #[test]
+    fn test_last_session_event_type_skips_synthetic_and_heartbeat() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = EventsRepo::new(db);
+        assert_eq!(repo.last_session_event_type("agent-01").expect("query"), None);
+
+        let rows = [
+            ("2026-02-20T15:00:00Z", EventType::ToolStarted, EventSource::Hook),
+            ("2026-02-20T15:00:01Z", EventType::Heartbeat, EventSource::Hook),
+            ("2026-02-20T15:00:02Z", EventType::TimerElapsed, EventSource::Synthetic),
+        ];
+        for (i, (ts, event_type, source)) in rows.into_iter().enumerate() {
+            let (mut event, fp) = make_test_event(&format!("evt-{i}"), &i.to_string());
+            event.ts = ts.to_string();
+            event.event_type = event_type;
+            event.source = source;
+            repo.insert(&event, &fp).expect("insert");
+        }
+
+        assert_eq!(
+            repo.last_session_event_type("agent-01").expect("query"),
+            Some(EventType::ToolStarted)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_of_legacy_fingerprint_returns_false() {
+        let db = init_db_in_memory().expect("db init");
+        {
+            // 업그레이드 이전 행: fingerprint_version 없이 v1 값만 저장됨
+            let conn = db.lock().expect("lock");
+            conn.execute(
+                "INSERT INTO events (id, version, ts, event_type, source, workspace_id,
+                 terminal_session_id, agent_id, fingerprint)
+                 VALUES ('evt-old', '1.0', '2026-02-20T15:00:00Z', '\"agent_started\"', '\"hook\"',
+                 'test-project', 'term-1', 'agent-01', 'legacy-1')",
+                [],
+            )
+            .expect("insert legacy row");
+        }
+        let repo = EventsRepo::new(db);
+        assert_eq!(
+            repo.legacy_fingerprint_until().expect("query").as_deref(),
+            Some("2026-02-20T15:00:00Z")
+        );
+
+        let (event, fp) = make_test_event("evt-new", "1");
+        assert!(!repo.insert(&event, &fp).expect("insert"));
+
+        // v2 행의 값이 우연히 같아도 legacy 비교 대상이 아니다
+        let (event, mut fp) = make_test_event("evt-2", "2");
+        repo.insert(&event, &fp).expect("insert");
+        let (event, _) = make_test_event("evt-3", "3");
+        fp.value = "fp-3".into();
+        fp.legacy_value = Some("fp-2".into());
+        assert!(repo.insert(&event, &fp).expect("insert"));
+    }
 }
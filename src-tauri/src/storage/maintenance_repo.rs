@@ -0,0 +1,63 @@
+use crate::error::AppError;
+use crate::models::maintenance::DbStats;
+use crate::storage::db::DbHandle;
+
+/// DB 전체 크기/정리 (retention 작업과 compact_db에서 사용)
+pub struct MaintenanceRepo<'a> {
+    db: DbHandle<'a>,
+}
+
+impl<'a> MaintenanceRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
+    }
+
+    pub fn stats(&self) -> Result<DbStats, AppError> {
+        let conn = self.db.conn()?;
+        let pragma = |name: &str| -> Result<u64, AppError> {
+            let value: i64 = conn.pragma_query_value(None, name, |row| row.get(0))?;
+            Ok(value as u64)
+        };
+        let page_size = pragma("page_size")?;
+        let size_bytes = pragma("page_count")? * page_size;
+        let free_bytes = pragma("freelist_count")? * page_size;
+
+        let count = |sql: &str| -> Result<u64, AppError> {
+            let value: i64 = conn.query_row(sql, [], |row| row.get(0))?;
+            Ok(value as u64)
+        };
+        Ok(DbStats {
+            size_bytes,
+            free_bytes,
+            events: count("SELECT count(*) FROM events")?,
+            events_with_raw: count("SELECT count(*) FROM events WHERE raw_json IS NOT NULL")?,
+            transitions: count("SELECT count(*) FROM agent_transitions")?,
+            thinking_history: count("SELECT count(*) FROM thinking_history")?,
+            tasks: count("SELECT count(*) FROM tasks")?,
+            oldest_event_ts: conn.query_row("SELECT min(ts) FROM events", [], |row| row.get(0))?,
+        })
+    }
+
+    /// 빈 페이지를 파일에서 반환한다 (auto_vacuum=INCREMENTAL인 DB에서만 효과가 있다).
+    /// WAL을 비워 줄어든 크기가 바로 파일에 반영되게 한다
+    pub fn incremental_vacuum(&self) -> Result<(), AppError> {
+        let conn = self.db.conn()?;
+        conn.execute_batch("PRAGMA incremental_vacuum;")?;
+        checkpoint(&conn)
+    }
+
+    /// 전체 VACUUM. 파일을 다시 써서 조각난 페이지까지 정리한다 (수동 compact용).
+    /// 기존 DB의 auto_vacuum=INCREMENTAL 전환도 여기서 함께 적용된다
+    pub fn vacuum(&self) -> Result<(), AppError> {
+        let conn = self.db.conn()?;
+        conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+        conn.execute_batch("VACUUM;")?;
+        checkpoint(&conn)
+    }
+}
+
+fn checkpoint(conn: &rusqlite::Connection) -> Result<(), AppError> {
+    // 인메모리 DB는 WAL이 없어 결과 행만 돌려주고 아무것도 하지 않는다
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    Ok(())
+}
@@ -1,5 +1,11 @@
 pub mod db;
+pub mod agent_store;
 pub mod agents_repo;
 pub mod events_repo;
+pub mod maintenance_repo;
 pub mod settings_repo;
 pub mod state_repo;
+pub mod tasks_repo;
+pub mod thinking_repo;
+pub mod transitions_repo;
+pub mod workspaces_repo;
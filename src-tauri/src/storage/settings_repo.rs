@@ -1,17 +1,17 @@
 use crate::error::AppError;
-use crate::storage::db::DbPool;
+use crate::storage::db::DbHandle;
 
-pub struct SettingsRepo {
-    db: DbPool,
+pub struct SettingsRepo<'a> {
+    db: DbHandle<'a>,
 }
 
-impl SettingsRepo {
-    pub fn new(db: DbPool) -> Self {
-        Self { db }
+impl<'a> SettingsRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
     }
 
     pub fn get(&self, key: &str) -> Result<Option<String>, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?1")?;
         let result = stmt.query_row(rusqlite::params![key], |row| row.get(0));
 
@@ -23,7 +23,7 @@ impl SettingsRepo {
     }
 
     pub fn set(&self, key: &str, value: &str) -> Result<(), AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         conn.execute(
             "INSERT INTO settings (key, value) VALUES (?1, ?2)
              ON CONFLICT(key) DO UPDATE SET value = excluded.value",
@@ -34,7 +34,7 @@ impl SettingsRepo {
 
     #[allow(dead_code)]
     pub fn delete(&self, key: &str) -> Result<bool, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let rows = conn.execute("DELETE FROM settings WHERE key = ?1", rusqlite::params![key])?;
         Ok(rows > 0)
     }
@@ -1,6 +1,6 @@
 use crate::error::AppError;
 use crate::models::agent::AgentState;
-use crate::storage::db::DbPool;
+use crate::storage::db::DbHandle;
 
 fn row_to_agent_state(row: &rusqlite::Row<'_>) -> Result<AgentState, rusqlite::Error> {
     let status_str: String = row.get(1)?;
@@ -18,6 +18,16 @@ fn row_to_agent_state(row: &rusqlite::Row<'_>) -> Result<AgentState, rusqlite::E
             })?,
         thinking_text: row.get(3)?,
         current_task: row.get(4)?,
+        activity: row
+            .get::<_, Option<String>>(11)?
+            .map(|s| serde_json::from_str(&s))
+            .transpose()
+            .map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(11, rusqlite::types::Type::Text, Box::new(e))
+            })?,
+        activity_summary: row.get(12)?,
+        consecutive_failures: row.get(13)?,
+        unresponsive: row.get(14)?,
         workspace_id: row.get(5)?,
         since: row.get(6)?,
         last_event_ts: row.get(7)?,
@@ -27,27 +37,31 @@ fn row_to_agent_state(row: &rusqlite::Row<'_>) -> Result<AgentState, rusqlite::E
     })
 }
 
-pub struct StateRepo {
-    db: DbPool,
+pub struct StateRepo<'a> {
+    db: DbHandle<'a>,
 }
 
-impl StateRepo {
-    pub fn new(db: DbPool) -> Self {
-        Self { db }
+impl<'a> StateRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
     }
 
     pub fn upsert(&self, state: &AgentState) -> Result<(), AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let status_str = serde_json::to_string(&state.status)
             .map_err(|e| AppError::Normalize(e.to_string()))?;
         let prev_status_str = state.prev_status.as_ref()
             .map(|s| serde_json::to_string(s))
             .transpose()
             .map_err(|e| AppError::Normalize(e.to_string()))?;
+        let activity_str = state.activity.as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| AppError::Normalize(e.to_string()))?;
 
         conn.execute(
-            "INSERT INTO agent_state (agent_id, status, prev_status, thinking_text, current_task, workspace_id, since, last_event_ts, session_id, peer_agent_id, home_x)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "INSERT INTO agent_state (agent_id, status, prev_status, thinking_text, current_task, workspace_id, since, last_event_ts, session_id, peer_agent_id, home_x, activity, activity_summary, consecutive_failures, unresponsive)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
              ON CONFLICT(agent_id) DO UPDATE SET
                status = excluded.status,
                prev_status = excluded.prev_status,
@@ -56,7 +70,11 @@ impl StateRepo {
                since = excluded.since,
                last_event_ts = excluded.last_event_ts,
                session_id = excluded.session_id,
-               peer_agent_id = excluded.peer_agent_id",
+               peer_agent_id = excluded.peer_agent_id,
+               activity = excluded.activity,
+               activity_summary = excluded.activity_summary,
+               consecutive_failures = excluded.consecutive_failures,
+               unresponsive = excluded.unresponsive",
             rusqlite::params![
                 state.agent_id,
                 status_str,
@@ -69,15 +87,19 @@ impl StateRepo {
                 state.session_id,
                 state.peer_agent_id,
                 state.home_x,
+                activity_str,
+                state.activity_summary,
+                state.consecutive_failures,
+                state.unresponsive,
             ],
         )?;
         Ok(())
     }
 
     pub fn get(&self, agent_id: &str) -> Result<Option<AgentState>, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let mut stmt = conn.prepare(
-            "SELECT agent_id, status, prev_status, thinking_text, current_task, workspace_id, since, last_event_ts, session_id, peer_agent_id, home_x
+            "SELECT agent_id, status, prev_status, thinking_text, current_task, workspace_id, since, last_event_ts, session_id, peer_agent_id, home_x, activity, activity_summary, consecutive_failures, unresponsive
              FROM agent_state WHERE agent_id = ?1",
         )?;
 
@@ -92,10 +114,7 @@ impl StateRepo {
 
     /// 드래그 드롭 후 캐릭터의 home_x만 업데이트한다.
     pub fn update_home_x(&self, agent_id: &str, home_x: f64) -> Result<(), AppError> {
-        let conn = self
-            .db
-            .lock()
-            .map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         conn.execute(
             "UPDATE agent_state SET home_x = ?1 WHERE agent_id = ?2",
             rusqlite::params![home_x, agent_id],
@@ -104,9 +123,9 @@ impl StateRepo {
     }
 
     pub fn get_all(&self) -> Result<Vec<AgentState>, AppError> {
-        let conn = self.db.lock().map_err(|e| AppError::LockPoisoned(e.to_string()))?;
+        let conn = self.db.conn()?;
         let mut stmt = conn.prepare(
-            "SELECT agent_id, status, prev_status, thinking_text, current_task, workspace_id, since, last_event_ts, session_id, peer_agent_id, home_x
+            "SELECT agent_id, status, prev_status, thinking_text, current_task, workspace_id, since, last_event_ts, session_id, peer_agent_id, home_x, activity, activity_summary, consecutive_failures, unresponsive
              FROM agent_state",
         )?;
 
@@ -131,6 +150,10 @@ mod tests {
             prev_status: None,
             thinking_text: None,
             current_task: None,
+            activity: None,
+            activity_summary: None,
+            consecutive_failures: 0,
+            unresponsive: false,
             workspace_id: "test-project".to_string(),
             since: "2026-02-20T15:00:00Z".to_string(),
             last_event_ts: "2026-02-20T15:00:00Z".to_string(),
@@ -153,11 +176,14 @@ mod tests {
         }
 
         let repo = StateRepo::new(db);
-        repo.upsert(&make_test_state("a1")).expect("upsert");
+        let mut state = make_test_state("a1");
+        state.consecutive_failures = 2;
+        repo.upsert(&state).expect("upsert");
 
         let state = repo.get("a1").expect("get").expect("should exist");
         assert_eq!(state.status, AgentStatus::Idle);
         assert_eq!(state.home_x, 0.5);
+        assert_eq!(state.consecutive_failures, 2);
     }
 
     #[test]
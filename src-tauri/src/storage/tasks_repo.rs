@@ -0,0 +1,160 @@
+use crate::error::AppError;
+use crate::models::task::TaskRecord;
+use crate::storage::db::DbHandle;
+
+const SELECT_COLUMNS: &str = "SELECT workspace_id, task_id, title, description, owner_agent_id, status,
+        status_history_json, created_ts, started_ts, completed_ts, updated_ts
+     FROM tasks";
+
+fn row_to_task(row: &rusqlite::Row<'_>) -> Result<TaskRecord, rusqlite::Error> {
+    let status_str: String = row.get(5)?;
+    let history_str: String = row.get(6)?;
+    Ok(TaskRecord {
+        workspace_id: row.get(0)?,
+        task_id: row.get(1)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        owner_agent_id: row.get(4)?,
+        status: serde_json::from_str(&status_str).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        status_history: serde_json::from_str(&history_str).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(6, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        created_ts: row.get(7)?,
+        started_ts: row.get(8)?,
+        completed_ts: row.get(9)?,
+        updated_ts: row.get(10)?,
+    })
+}
+
+pub struct TasksRepo<'a> {
+    db: DbHandle<'a>,
+}
+
+impl<'a> TasksRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
+    }
+
+    pub fn upsert(&self, task: &TaskRecord) -> Result<(), AppError> {
+        let conn = self.db.conn()?;
+        let status_str = serde_json::to_string(&task.status)
+            .map_err(|e| AppError::Normalize(e.to_string()))?;
+        let history_json = serde_json::to_string(&task.status_history)
+            .map_err(|e| AppError::Normalize(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO tasks (workspace_id, task_id, title, description, owner_agent_id, status,
+             status_history_json, created_ts, started_ts, completed_ts, updated_ts)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             ON CONFLICT(workspace_id, task_id) DO UPDATE SET
+               title = excluded.title,
+               description = excluded.description,
+               owner_agent_id = excluded.owner_agent_id,
+               status = excluded.status,
+               status_history_json = excluded.status_history_json,
+               created_ts = excluded.created_ts,
+               started_ts = excluded.started_ts,
+               completed_ts = excluded.completed_ts,
+               updated_ts = excluded.updated_ts",
+            rusqlite::params![
+                task.workspace_id,
+                task.task_id,
+                task.title,
+                task.description,
+                task.owner_agent_id,
+                status_str,
+                history_json,
+                task.created_ts,
+                task.started_ts,
+                task.completed_ts,
+                task.updated_ts,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get(&self, workspace_id: &str, task_id: &str) -> Result<Option<TaskRecord>, AppError> {
+        let conn = self.db.conn()?;
+        let mut stmt =
+            conn.prepare(&format!("{SELECT_COLUMNS} WHERE workspace_id = ?1 AND task_id = ?2"))?;
+
+        let result = stmt.query_row(rusqlite::params![workspace_id, task_id], row_to_task);
+
+        match result {
+            Ok(task) => Ok(Some(task)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 작업 보드 조회. workspace_id가 주어지면 해당 워크스페이스만, 최근 갱신순.
+    pub fn get_all(&self, workspace_id: Option<&str>) -> Result<Vec<TaskRecord>, AppError> {
+        let conn = self.db.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            "{SELECT_COLUMNS} WHERE (?1 IS NULL OR workspace_id = ?1) ORDER BY updated_ts DESC"
+        ))?;
+
+        let tasks = stmt
+            .query_map(rusqlite::params![workspace_id], row_to_task)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tasks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task::{TaskStatus, TaskStatusChange};
+    use crate::storage::db::init_db_in_memory;
+
+    fn make_test_task(workspace_id: &str, task_id: &str) -> TaskRecord {
+        TaskRecord {
+            workspace_id: workspace_id.to_string(),
+            task_id: task_id.to_string(),
+            title: Some("Write docs".to_string()),
+            description: None,
+            owner_agent_id: None,
+            status: TaskStatus::Pending,
+            status_history: vec![TaskStatusChange {
+                status: TaskStatus::Pending,
+                agent_id: "my-team/leader".to_string(),
+                ts: "2026-02-20T15:00:00Z".to_string(),
+            }],
+            created_ts: Some("2026-02-20T15:00:00Z".to_string()),
+            started_ts: None,
+            completed_ts: None,
+            updated_ts: "2026-02-20T15:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_upsert_and_get() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = TasksRepo::new(db);
+        let mut task = make_test_task("ws-1", "1");
+        repo.upsert(&task).expect("upsert");
+
+        task.status = TaskStatus::InProgress;
+        repo.upsert(&task).expect("upsert again");
+
+        let found = repo.get("ws-1", "1").expect("get").expect("should exist");
+        assert_eq!(found.status, TaskStatus::InProgress);
+        assert_eq!(found.status_history.len(), 1);
+        assert!(repo.get("ws-2", "1").expect("get").is_none());
+    }
+
+    #[test]
+    fn test_get_all_filters_by_workspace() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = TasksRepo::new(db);
+        repo.upsert(&make_test_task("ws-1", "1")).expect("upsert");
+        repo.upsert(&make_test_task("ws-1", "2")).expect("upsert");
+        repo.upsert(&make_test_task("ws-2", "1")).expect("upsert");
+
+        assert_eq!(repo.get_all(None).expect("get_all").len(), 3);
+        assert_eq!(repo.get_all(Some("ws-1")).expect("get_all").len(), 2);
+    }
+}
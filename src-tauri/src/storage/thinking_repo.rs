@@ -0,0 +1,83 @@
+use crate::error::AppError;
+use crate::models::event::ThinkingSnippet;
+use crate::storage::db::DbHandle;
+
+pub struct ThinkingRepo<'a> {
+    db: DbHandle<'a>,
+}
+
+impl<'a> ThinkingRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
+    }
+
+    /// thinking 스니펫을 추가하고, 에이전트별로 최신 limit개만 남기고 정리한다.
+    pub fn push(&self, agent_id: &str, ts: &str, text: &str, limit: usize) -> Result<(), AppError> {
+        let conn = self.db.conn()?;
+        conn.execute(
+            "INSERT INTO thinking_history (agent_id, ts, text) VALUES (?1, ?2, ?3)",
+            rusqlite::params![agent_id, ts, text],
+        )?;
+        conn.execute(
+            "DELETE FROM thinking_history
+             WHERE agent_id = ?1 AND id NOT IN (
+               SELECT id FROM thinking_history WHERE agent_id = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            rusqlite::params![agent_id, limit as i64],
+        )?;
+        Ok(())
+    }
+
+    /// 에이전트의 최근 thinking 스니펫 조회 (최신순)
+    pub fn get_recent(&self, agent_id: &str, limit: usize) -> Result<Vec<ThinkingSnippet>, AppError> {
+        let conn = self.db.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT text, ts FROM thinking_history WHERE agent_id = ?1 ORDER BY id DESC LIMIT ?2",
+        )?;
+
+        let snippets = stmt
+            .query_map(rusqlite::params![agent_id, limit as i64], |row| {
+                Ok(ThinkingSnippet {
+                    text: row.get(0)?,
+                    ts: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(snippets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::db::init_db_in_memory;
+
+    #[test]
+    fn test_push_and_get_recent() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = ThinkingRepo::new(db);
+        repo.push("a1", "2026-02-20T15:00:00Z", "first", 10).expect("push");
+        repo.push("a1", "2026-02-20T15:00:05Z", "second", 10).expect("push");
+
+        let snippets = repo.get_recent("a1", 10).expect("get_recent");
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(snippets[0].text, "second");
+        assert_eq!(snippets[1].text, "first");
+    }
+
+    #[test]
+    fn test_push_prunes_beyond_limit() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = ThinkingRepo::new(db);
+        for i in 0..5 {
+            repo.push("a1", "2026-02-20T15:00:00Z", &format!("t{i}"), 3).expect("push");
+        }
+        repo.push("a2", "2026-02-20T15:00:00Z", "other", 3).expect("push");
+
+        let snippets = repo.get_recent("a1", 10).expect("get_recent");
+        let texts: Vec<&str> = snippets.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["t4", "t3", "t2"]);
+        assert_eq!(repo.get_recent("a2", 10).expect("get_recent").len(), 1);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::error::AppError;
+use crate::models::transition::{parse_ts_ms, TimeInState, TransitionRecord};
+use crate::storage::db::DbHandle;
+use std::collections::BTreeMap;
+
+pub struct TransitionsRepo<'a> {
+    db: DbHandle<'a>,
+}
+
+impl<'a> TransitionsRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
+    }
+
+    pub fn insert(&self, record: &TransitionRecord) -> Result<(), AppError> {
+        let conn = self.db.conn()?;
+        let from_str = serde_json::to_string(&record.from_status)
+            .map_err(|e| AppError::Normalize(e.to_string()))?;
+        let to_str = serde_json::to_string(&record.to_status)
+            .map_err(|e| AppError::Normalize(e.to_string()))?;
+        // 구간 계산용 epoch ms. ts 파싱에 실패한 행은 합계에서 제외된다
+        let ended_ms = parse_ts_ms(&record.ts);
+        let started_ms = ended_ms.map(|end| end - record.duration_ms);
+
+        conn.execute(
+            "INSERT INTO agent_transitions (agent_id, workspace_id, from_status, to_status, event_id,
+             started_ts, ts, duration_ms, started_ms, ended_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                record.agent_id,
+                record.workspace_id,
+                from_str,
+                to_str,
+                record.event_id,
+                record.started_ts,
+                record.ts,
+                record.duration_ms,
+                started_ms,
+                ended_ms,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// [from_ms, to_ms) 구간에서 에이전트/상태별로 머문 시간.
+    /// 끝난 체류는 agent_transitions에서, 진행 중인 체류는 agent_state.since ~ now_ms로 계산한다.
+    pub fn time_in_state(
+        &self,
+        agent_id: Option<&str>,
+        workspace_id: Option<&str>,
+        from_ms: i64,
+        to_ms: i64,
+        now_ms: i64,
+    ) -> Result<Vec<TimeInState>, AppError> {
+        let conn = self.db.conn()?;
+        // (agent_id, workspace_id, status JSON) → (ms, intervals)
+        let mut totals: BTreeMap<(String, String, String), (i64, u64)> = BTreeMap::new();
+
+        let mut stmt = conn.prepare(
+            "SELECT agent_id, workspace_id, from_status,
+                SUM(MIN(ended_ms, ?2) - MAX(started_ms, ?1)), COUNT(*)
+             FROM agent_transitions
+             WHERE ended_ms > ?1 AND started_ms < ?2
+               AND (?3 IS NULL OR agent_id = ?3)
+               AND (?4 IS NULL OR workspace_id = ?4)
+             GROUP BY agent_id, workspace_id, from_status",
+        )?;
+        let rows = stmt.query_map(
+            rusqlite::params![from_ms, to_ms, agent_id, workspace_id],
+            |row| {
+                Ok((
+                    (row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?),
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            },
+        )?;
+        for row in rows {
+            let (key, ms, count) = row?;
+            let entry = totals.entry(key).or_default();
+            entry.0 += ms;
+            entry.1 += count as u64;
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT agent_id, workspace_id, status, since FROM agent_state
+             WHERE (?1 IS NULL OR agent_id = ?1) AND (?2 IS NULL OR workspace_id = ?2)",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![agent_id, workspace_id], |row| {
+            Ok((
+                (row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?),
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (key, since) = row?;
+            let Some(started_ms) = parse_ts_ms(&since) else {
+                continue;
+            };
+            let overlap = now_ms.min(to_ms) - started_ms.max(from_ms);
+            if overlap > 0 {
+                let entry = totals.entry(key).or_default();
+                entry.0 += overlap;
+                entry.1 += 1;
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|((agent_id, workspace_id, status), (ms, intervals))| {
+                Ok(TimeInState {
+                    agent_id,
+                    workspace_id,
+                    status: serde_json::from_str(&status)
+                        .map_err(|e| AppError::Normalize(e.to_string()))?,
+                    total_secs: ms as f64 / 1000.0,
+                    intervals,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agent::{AgentState, AgentStatus};
+    use crate::storage::db::init_db_in_memory;
+    use crate::storage::state_repo::StateRepo;
+
+    fn ms(ts: &str) -> i64 {
+        parse_ts_ms(ts).expect("ts")
+    }
+
+    fn record(from: AgentStatus, to: AgentStatus, started_ts: &str, ts: &str) -> TransitionRecord {
+        TransitionRecord {
+            agent_id: "ws/worker-02".into(),
+            workspace_id: "ws".into(),
+            from_status: from,
+            to_status: to,
+            event_id: Some("evt-1".into()),
+            started_ts: started_ts.into(),
+            ts: ts.into(),
+            duration_ms: ms(ts) - ms(started_ts),
+        }
+    }
+
+    #[test]
+    fn test_time_in_state_clips_to_range_and_includes_current_state() {
+        let db = init_db_in_memory().expect("db init");
+        {
+            let conn = db.lock().unwrap();
+            conn.execute(
+                "INSERT INTO agents (agent_id, display_name, workspace_id, first_seen_ts, last_active_ts) VALUES ('ws/worker-02', 'worker-02', 'ws', '2026-02-20T09:00:00Z', '2026-02-20T09:00:00Z')",
+                [],
+            ).unwrap();
+        }
+        let repo = TransitionsRepo::new(db.clone());
+        use AgentStatus::*;
+        // 09:00-10:00 working, 10:00-10:30 pending_input, 10:30-11:00 working, 11:00~ pending_input
+        repo.insert(&record(Working, PendingInput, "2026-02-20T09:00:00Z", "2026-02-20T10:00:00Z")).unwrap();
+        repo.insert(&record(PendingInput, Working, "2026-02-20T10:00:00Z", "2026-02-20T10:30:00Z")).unwrap();
+        repo.insert(&record(Working, PendingInput, "2026-02-20T10:30:00Z", "2026-02-20T11:00:00Z")).unwrap();
+        StateRepo::new(db)
+            .upsert(&AgentState {
+                agent_id: "ws/worker-02".into(),
+                status: PendingInput,
+                prev_status: None,
+                thinking_text: None,
+                current_task: None,
+                activity: None,
+                activity_summary: None,
+                consecutive_failures: 0,
+                unresponsive: false,
+                workspace_id: "ws".into(),
+                since: "2026-02-20T11:00:00Z".into(),
+                last_event_ts: "2026-02-20T11:00:00Z".into(),
+                session_id: None,
+                peer_agent_id: None,
+                home_x: 0.0,
+            })
+            .unwrap();
+
+        // 09:30 ~ 12:00, now = 11:15
+        let totals = repo
+            .time_in_state(
+                Some("ws/worker-02"),
+                None,
+                ms("2026-02-20T09:30:00Z"),
+                ms("2026-02-20T12:00:00Z"),
+                ms("2026-02-20T11:15:00Z"),
+            )
+            .expect("totals");
+        let get = |status: AgentStatus| totals.iter().find(|t| t.status == status).expect("status");
+        assert_eq!(get(Working).total_secs, 60.0 * 60.0);
+        assert_eq!(get(Working).intervals, 2);
+        assert_eq!(get(PendingInput).total_secs, 45.0 * 60.0);
+        assert_eq!(get(PendingInput).intervals, 2);
+
+        let other = repo
+            .time_in_state(None, Some("other"), 0, ms("2026-02-20T12:00:00Z"), ms("2026-02-20T12:00:00Z"))
+            .expect("totals");
+        assert!(other.is_empty());
+    }
+}
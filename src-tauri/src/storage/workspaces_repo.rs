@@ -0,0 +1,247 @@
+use crate::error::AppError;
+use crate::models::workspace::{WorkspaceMerge, WorkspaceSummary};
+use crate::storage::db::{in_transaction, DbHandle};
+use std::collections::HashMap;
+
+pub struct WorkspacesRepo<'a> {
+    db: DbHandle<'a>,
+}
+
+impl<'a> WorkspacesRepo<'a> {
+    pub fn new(db: impl Into<DbHandle<'a>>) -> Self {
+        Self { db: db.into() }
+    }
+
+    /// 별칭이면 대상 workspace_id, 아니면 그대로 반환
+    pub fn resolve(&self, workspace_id: &str) -> Result<String, AppError> {
+        let conn = self.db.conn()?;
+        let target = conn.query_row(
+            "SELECT workspace_id FROM workspace_aliases WHERE alias = ?1",
+            rusqlite::params![workspace_id],
+            |row| row.get::<_, String>(0),
+        );
+        match target {
+            Ok(target) => Ok(target),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(workspace_id.to_string()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 별칭 추가/변경. 대상이 별칭이면 그 대상으로, 이 별칭을 가리키던 별칭도 새 대상으로 옮긴다 (체인 없음).
+    pub fn set_alias(&self, alias: &str, workspace_id: &str) -> Result<(), AppError> {
+        let target = self.resolve(workspace_id)?;
+        if alias == target {
+            return Err(AppError::InvalidArgument(format!(
+                "alias {alias:?} would point to itself"
+            )));
+        }
+
+        in_transaction(&*self.db.conn()?, |tx| {
+            tx.execute(
+                "UPDATE workspace_aliases SET workspace_id = ?1 WHERE workspace_id = ?2",
+                rusqlite::params![target, alias],
+            )?;
+            tx.execute(
+                "INSERT INTO workspace_aliases (alias, workspace_id) VALUES (?1, ?2)
+                 ON CONFLICT(alias) DO UPDATE SET workspace_id = excluded.workspace_id",
+                rusqlite::params![alias, target],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// 별칭 삭제. 없던 별칭이면 false.
+    pub fn remove_alias(&self, alias: &str) -> Result<bool, AppError> {
+        let conn = self.db.conn()?;
+        let rows = conn.execute(
+            "DELETE FROM workspace_aliases WHERE alias = ?1",
+            rusqlite::params![alias],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// 에이전트/이벤트/작업에 등장하는 모든 워크스페이스 (최근 이벤트순)
+    pub fn list(&self) -> Result<Vec<WorkspaceSummary>, AppError> {
+        let conn = self.db.conn()?;
+
+        let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+        let mut stmt = conn.prepare(
+            "SELECT alias, workspace_id FROM workspace_aliases ORDER BY alias",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (alias, workspace_id) = row?;
+            aliases.entry(workspace_id).or_default().push(alias);
+        }
+
+        let mut stmt = conn.prepare(
+            "WITH ids AS (
+                SELECT workspace_id FROM agents
+                UNION SELECT workspace_id FROM events
+                UNION SELECT workspace_id FROM tasks
+             )
+             SELECT ids.workspace_id,
+                (SELECT COUNT(*) FROM agents a WHERE a.workspace_id = ids.workspace_id),
+                (SELECT COUNT(*) FROM events e WHERE e.workspace_id = ids.workspace_id),
+                (SELECT MAX(ts) FROM events e WHERE e.workspace_id = ids.workspace_id) AS last_ts
+             FROM ids
+             ORDER BY last_ts IS NULL, last_ts DESC, ids.workspace_id",
+        )?;
+        let workspaces = stmt
+            .query_map([], |row| {
+                let workspace_id: String = row.get(0)?;
+                let agent_count: i64 = row.get(1)?;
+                let event_count: i64 = row.get(2)?;
+                Ok(WorkspaceSummary {
+                    aliases: aliases.remove(&workspace_id).unwrap_or_default(),
+                    workspace_id,
+                    agent_count: agent_count as u64,
+                    event_count: event_count as u64,
+                    last_event_ts: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(workspaces)
+    }
+
+    /// 에이전트/이벤트/작업 중 하나라도 이 워크스페이스에 속하면 true
+    pub fn exists(&self, workspace_id: &str) -> Result<bool, AppError> {
+        let conn = self.db.conn()?;
+        let exists = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM agents WHERE workspace_id = ?1)
+                 OR EXISTS(SELECT 1 FROM events WHERE workspace_id = ?1)
+                 OR EXISTS(SELECT 1 FROM tasks WHERE workspace_id = ?1)",
+            rusqlite::params![workspace_id],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// from의 이벤트/에이전트/작업을 into로 옮기고 from → into 별칭을 남긴다 (한 트랜잭션).
+    /// 같은 task_id가 양쪽에 있으면 into 쪽 작업을 유지한다.
+    pub fn merge(&self, from: &str, into: &str) -> Result<WorkspaceMerge, AppError> {
+        if from == into {
+            return Err(AppError::InvalidArgument(format!(
+                "cannot merge workspace {from:?} into itself"
+            )));
+        }
+
+        in_transaction(&*self.db.conn()?, |tx| {
+            let params = rusqlite::params![into, from];
+
+            let events_moved = tx.execute("UPDATE events SET workspace_id = ?1 WHERE workspace_id = ?2", params)?;
+            let agents_moved = tx.execute("UPDATE agents SET workspace_id = ?1 WHERE workspace_id = ?2", params)?;
+            tx.execute("UPDATE agent_state SET workspace_id = ?1 WHERE workspace_id = ?2", params)?;
+            tx.execute("UPDATE agent_transitions SET workspace_id = ?1 WHERE workspace_id = ?2", params)?;
+            let tasks_moved = tx.execute(
+                "UPDATE OR IGNORE tasks SET workspace_id = ?1 WHERE workspace_id = ?2",
+                params,
+            )?;
+            tx.execute("DELETE FROM tasks WHERE workspace_id = ?1", rusqlite::params![from])?;
+
+            // 별칭 정리: from을 가리키던 별칭은 into로, into 자신은 별칭에서 제외
+            tx.execute("UPDATE workspace_aliases SET workspace_id = ?1 WHERE workspace_id = ?2", params)?;
+            tx.execute("DELETE FROM workspace_aliases WHERE alias = ?1", rusqlite::params![into])?;
+            tx.execute(
+                "INSERT INTO workspace_aliases (alias, workspace_id) VALUES (?1, ?2)
+                 ON CONFLICT(alias) DO UPDATE SET workspace_id = excluded.workspace_id",
+                rusqlite::params![from, into],
+            )?;
+
+            Ok(WorkspaceMerge {
+                from: from.to_string(),
+                into: into.to_string(),
+                events_moved: events_moved as u64,
+                agents_moved: agents_moved as u64,
+                tasks_moved: tasks_moved as u64,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task::{TaskRecord, TaskStatus};
+    use crate::storage::db::{init_db_in_memory, DbPool};
+    use crate::storage::tasks_repo::TasksRepo;
+
+    fn insert_event(db: &DbPool, id: &str, workspace_id: &str, ts: &str) {
+        let conn = db.lock().expect("lock");
+        conn.execute(
+            "INSERT INTO events (id, version, ts, event_type, source, workspace_id,
+             terminal_session_id, agent_id, fingerprint)
+             VALUES (?1, '1.1', ?2, '\"tool_started\"', '\"hook\"', ?3, 'term-1', 'agent-01', ?1)",
+            rusqlite::params![id, ts, workspace_id],
+        )
+        .expect("insert event");
+    }
+
+    fn make_task(workspace_id: &str, task_id: &str, title: &str) -> TaskRecord {
+        TaskRecord {
+            workspace_id: workspace_id.into(),
+            task_id: task_id.into(),
+            title: Some(title.into()),
+            description: None,
+            owner_agent_id: None,
+            status: TaskStatus::Pending,
+            status_history: Vec::new(),
+            created_ts: None,
+            started_ts: None,
+            completed_ts: None,
+            updated_ts: "2026-02-20T15:00:00Z".into(),
+        }
+    }
+
+    #[test]
+    fn test_alias_resolve_and_remove() {
+        let db = init_db_in_memory().expect("db init");
+        let repo = WorkspacesRepo::new(db);
+        assert_eq!(repo.resolve("widgets-wsl").expect("resolve"), "widgets-wsl");
+
+        repo.set_alias("widgets-wsl", "widgets").expect("set alias");
+        assert_eq!(repo.resolve("widgets-wsl").expect("resolve"), "widgets");
+
+        // 대상이 별칭이면 최종 대상으로 (체인 없음)
+        repo.set_alias("widgets-old", "widgets-wsl").expect("set alias");
+        assert_eq!(repo.resolve("widgets-old").expect("resolve"), "widgets");
+
+        assert!(repo.set_alias("widgets", "widgets-wsl").is_err());
+        assert!(repo.remove_alias("widgets-wsl").expect("remove"));
+        assert!(!repo.remove_alias("widgets-wsl").expect("remove"));
+    }
+
+    #[test]
+    fn test_merge_moves_rows_and_leaves_alias() {
+        let db = init_db_in_memory().expect("db init");
+        insert_event(&db, "evt-1", "widgets", "2026-02-20T15:00:00Z");
+        insert_event(&db, "evt-2", "widgets-wsl", "2026-02-20T15:05:00Z");
+        let tasks = TasksRepo::new(db.clone());
+        tasks.upsert(&make_task("widgets", "1", "keep")).expect("task");
+        tasks.upsert(&make_task("widgets-wsl", "1", "dropped")).expect("task");
+        tasks.upsert(&make_task("widgets-wsl", "2", "moved")).expect("task");
+
+        let repo = WorkspacesRepo::new(db.clone());
+        let result = repo.merge("widgets-wsl", "widgets").expect("merge");
+        assert_eq!(result.events_moved, 1);
+        assert_eq!(result.tasks_moved, 1);
+
+        let workspaces = repo.list().expect("list");
+        assert_eq!(workspaces.len(), 1);
+        assert_eq!(workspaces[0].workspace_id, "widgets");
+        assert_eq!(workspaces[0].event_count, 2);
+        assert_eq!(workspaces[0].last_event_ts.as_deref(), Some("2026-02-20T15:05:00Z"));
+        assert_eq!(workspaces[0].aliases, vec!["widgets-wsl".to_string()]);
+
+        let merged = tasks.get_all(Some("widgets")).expect("tasks");
+        assert_eq!(merged.len(), 2);
+        assert!(merged.iter().any(|t| t.title.as_deref() == Some("keep")));
+        assert!(!repo.exists("widgets-wsl").expect("exists"));
+        assert_eq!(repo.resolve("widgets-wsl").expect("resolve"), "widgets");
+
+        assert!(repo.merge("widgets", "widgets").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+-- 2254c8e 시점 스키마로 만든 DB의 덤프 (PRAGMA user_version = 0). 마이그레이션 테스트용, 수정하지 말 것
+PRAGMA foreign_keys=OFF;
+BEGIN TRANSACTION;
+CREATE TABLE agent_state (
+            agent_id TEXT PRIMARY KEY,
+            status TEXT NOT NULL DEFAULT 'offline',
+            prev_status TEXT,
+            thinking_text TEXT,
+            current_task TEXT,
+            workspace_id TEXT NOT NULL,
+            since TEXT NOT NULL,
+            last_event_ts TEXT NOT NULL,
+            session_id TEXT,
+            peer_agent_id TEXT,
+            home_x REAL NOT NULL DEFAULT 0.0,
+            activity TEXT,
+            activity_summary TEXT,
+            consecutive_failures INTEGER NOT NULL DEFAULT 0,
+            unresponsive INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (agent_id) REFERENCES agents(agent_id)
+        );
+INSERT INTO "agent_state" VALUES('ws/worker','"working"',NULL,NULL,NULL,'ws','2026-02-20T15:00:00Z','2026-02-20T15:00:00Z',NULL,NULL,0.42,NULL,NULL,0,0);
+CREATE TABLE agent_transitions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id TEXT NOT NULL,
+            workspace_id TEXT NOT NULL,
+            from_status TEXT NOT NULL,
+            to_status TEXT NOT NULL,
+            event_id TEXT,
+            started_ts TEXT NOT NULL,
+            ts TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            started_ms INTEGER,
+            ended_ms INTEGER
+        );
+CREATE TABLE agents (
+            agent_id TEXT PRIMARY KEY,
+            display_name TEXT NOT NULL,
+            role TEXT NOT NULL DEFAULT 'unknown',
+            employment_type TEXT NOT NULL DEFAULT 'contractor',
+            workspace_id TEXT NOT NULL,
+            appearance_json TEXT,
+            first_seen_ts TEXT NOT NULL,
+            last_active_ts TEXT NOT NULL
+        );
+INSERT INTO "agents" VALUES('ws/worker','worker','"worker"','"contractor"','ws',NULL,'2026-02-20T15:00:00Z','2026-02-20T15:00:00Z');
+CREATE TABLE events (
+            id TEXT PRIMARY KEY,
+            version TEXT NOT NULL,
+            ts TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            source TEXT NOT NULL,
+            workspace_id TEXT NOT NULL,
+            terminal_session_id TEXT NOT NULL,
+            run_id TEXT,
+            session_id TEXT,
+            agent_id TEXT NOT NULL,
+            target_agent_id TEXT,
+            task_id TEXT,
+            severity TEXT NOT NULL DEFAULT 'info',
+            payload_json TEXT,
+            thinking_text TEXT,
+            raw_json TEXT,
+            fingerprint TEXT UNIQUE,
+            fingerprint_version INTEGER,
+            redactions_json TEXT
+        );
+INSERT INTO "events" VALUES('evt-1','1.1','2026-02-20T15:00:00Z','"tool_started"','"hook"','ws','term',NULL,'sess-1','ws/worker',NULL,NULL,'"info"','{"tool_name":"Read"}',NULL,'{"hook_type":"PreToolUse"}','fp-1',NULL,NULL);
+CREATE TABLE settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+INSERT INTO "settings" VALUES('language','ko');
+CREATE TABLE tasks (
+            workspace_id TEXT NOT NULL,
+            task_id TEXT NOT NULL,
+            title TEXT,
+            description TEXT,
+            owner_agent_id TEXT,
+            status TEXT NOT NULL,
+            status_history_json TEXT NOT NULL DEFAULT '[]',
+            created_ts TEXT,
+            started_ts TEXT,
+            completed_ts TEXT,
+            updated_ts TEXT NOT NULL,
+            PRIMARY KEY (workspace_id, task_id)
+        );
+CREATE TABLE thinking_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id TEXT NOT NULL,
+            ts TEXT NOT NULL,
+            text TEXT NOT NULL
+        );
+CREATE TABLE workspace_aliases (
+            alias TEXT PRIMARY KEY,
+            workspace_id TEXT NOT NULL
+        );
+CREATE INDEX idx_events_agent_id ON events(agent_id);
+CREATE INDEX idx_events_ts ON events(ts);
+CREATE INDEX idx_events_type ON events(event_type);
+CREATE INDEX idx_agent_state_workspace ON agent_state(workspace_id);
+CREATE INDEX idx_thinking_history_agent ON thinking_history(agent_id, id);
+CREATE INDEX idx_transitions_agent ON agent_transitions(agent_id, ended_ms);
+CREATE INDEX idx_transitions_workspace ON agent_transitions(workspace_id, ended_ms);
+CREATE INDEX idx_events_workspace ON events(workspace_id);
+DELETE FROM "sqlite_sequence";
+COMMIT;